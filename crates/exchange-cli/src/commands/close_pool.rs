@@ -0,0 +1,49 @@
+use exchange_client::{find_locked_liquidity_address, find_registry_address, ClosePoolBuilder};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use crate::context::{create_ata, Context};
+use crate::output::{print, TransactionView};
+use crate::Result;
+
+pub fn run(ctx: &Context, pool: &Pubkey, receiver: Option<Pubkey>) -> Result<()> {
+    let signer = ctx.signer()?;
+    let authority = signer.pubkey();
+    let receiver = receiver.unwrap_or(authority);
+    let keys = ctx.client.fetch_pool_keys(pool)?;
+    let fee_tier = ctx.client.fetch_pool(pool)?.fee_tier;
+    let pool_mint_program = ctx.token_program(&keys.mint)?;
+    // pools of the first deployment have neither a registry nor locked liquidity
+    let (registry, _) = find_registry_address(&keys.token_a_mint, &keys.token_b_mint, fee_tier);
+    let (locked_liquidity, _) = find_locked_liquidity_address(pool);
+    let [registry, locked_liquidity] = ctx
+        .rpc()
+        .get_multiple_accounts(&[registry, locked_liquidity])?
+        .try_into()
+        .map_err(|_| "unexpected number of accounts")?;
+
+    let (_, create_a) = create_ata(
+        &authority,
+        &receiver,
+        &keys.token_a_mint,
+        &keys.token_a_program,
+    );
+    let (_, create_b) = create_ata(
+        &authority,
+        &receiver,
+        &keys.token_b_mint,
+        &keys.token_b_program,
+    );
+    let mut close = ClosePoolBuilder::new(&keys, fee_tier, authority, receiver)
+        .pool_mint_program(pool_mint_program);
+    if registry.is_none() {
+        close = close.without_registry();
+    }
+    if locked_liquidity.is_none() {
+        close = close.without_locked_liquidity();
+    }
+    let signature = ctx.send(&signer, &[create_a, create_b, close.instruction()])?;
+
+    print(ctx.output, &TransactionView::new(signature));
+    Ok(())
+}
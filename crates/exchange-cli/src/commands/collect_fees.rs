@@ -0,0 +1,31 @@
+use exchange_client::CollectProtocolFeesBuilder;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use crate::context::{create_ata, Context};
+use crate::output::{print, TransactionView};
+use crate::Result;
+
+pub fn run(ctx: &Context, pool: &Pubkey) -> Result<()> {
+    let signer = ctx.signer()?;
+    let treasury = signer.pubkey();
+    let keys = ctx.client.fetch_pool_keys(pool)?;
+
+    let (_, create_a) = create_ata(
+        &treasury,
+        &treasury,
+        &keys.token_a_mint,
+        &keys.token_a_program,
+    );
+    let (_, create_b) = create_ata(
+        &treasury,
+        &treasury,
+        &keys.token_b_mint,
+        &keys.token_b_program,
+    );
+    let collect = CollectProtocolFeesBuilder::new(&keys, treasury).instruction();
+    let signature = ctx.send(&signer, &[create_a, create_b, collect])?;
+
+    print(ctx.output, &TransactionView::new(signature));
+    Ok(())
+}
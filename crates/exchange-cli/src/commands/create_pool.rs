@@ -0,0 +1,48 @@
+use exchange_client::{sort_mints, InitializePoolBuilder};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use crate::context::Context;
+use crate::output::{print, TransactionView};
+use crate::Result;
+
+pub fn run(
+    ctx: &Context,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    amount_a: u64,
+    amount_b: u64,
+    fee_tier: u16,
+    transfer_hook_programs: Vec<Pubkey>,
+) -> Result<()> {
+    let signer = ctx.signer()?;
+    // The program expects the mints in canonical order, the amounts follow their mint
+    let (token_a_mint, token_b_mint) = sort_mints(mint_a, mint_b);
+    let (amount_a, amount_b) = if token_a_mint == mint_a {
+        (amount_a, amount_b)
+    } else {
+        (amount_b, amount_a)
+    };
+
+    let builder = InitializePoolBuilder::new(
+        signer.pubkey(),
+        token_a_mint,
+        ctx.token_program(&token_a_mint)?,
+        token_b_mint,
+        ctx.token_program(&token_b_mint)?,
+        amount_a,
+        amount_b,
+    )
+    .fee_tier(fee_tier)
+    .transfer_hook_programs(transfer_hook_programs);
+    let signature = ctx.send(&signer, &[builder.instruction()])?;
+
+    print(
+        ctx.output,
+        &TransactionView {
+            pool: Some(builder.pool().to_string()),
+            ..TransactionView::new(signature)
+        },
+    );
+    Ok(())
+}
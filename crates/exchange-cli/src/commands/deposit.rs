@@ -0,0 +1,58 @@
+use exchange_client::quote::quote_deposit_all;
+use exchange_client::{DepositAllTokensBuilder, DepositSingleTokenBuilder};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use super::quote::{fetch_pool_info_now, quote_deposit_single};
+use super::{less_slippage, with_slippage};
+use crate::context::Context;
+use crate::output::{print, TransactionView};
+use crate::transfer_fee::amount_before_transfer_fee;
+use crate::{Result, Token};
+
+/// Deposits both tokens for `pool_tokens`, capping each at its quote plus the slippage
+pub fn run_all(ctx: &Context, pool: &Pubkey, pool_tokens: u64, slippage_bps: u64) -> Result<()> {
+    let signer = ctx.signer()?;
+    let (info, epoch) = fetch_pool_info_now(ctx, pool)?;
+    let quote = quote_deposit_all(pool_tokens, info.supply, info.reserve_a(), info.reserve_b())
+        .map_err(|err| err.to_string())?;
+    // the user pays the transfer fees on top of what the vaults must receive
+    let token_a_amount =
+        amount_before_transfer_fee(info.transfer_fee_a.as_ref(), epoch, quote.token_a_amount)?;
+    let token_b_amount =
+        amount_before_transfer_fee(info.transfer_fee_b.as_ref(), epoch, quote.token_b_amount)?;
+
+    let deposit = DepositAllTokensBuilder::new(
+        &info.keys,
+        signer.pubkey(),
+        pool_tokens,
+        with_slippage(token_a_amount, slippage_bps),
+        with_slippage(token_b_amount, slippage_bps),
+    )
+    .instruction();
+    let signature = ctx.send(&signer, &[deposit])?;
+
+    print(ctx.output, &TransactionView::new(signature));
+    Ok(())
+}
+
+/// Deposits `amount` of one token, failing under its quoted pool tokens less the slippage
+pub fn run_single(
+    ctx: &Context,
+    pool: &Pubkey,
+    token: Token,
+    amount: u64,
+    slippage_bps: u64,
+) -> Result<()> {
+    let signer = ctx.signer()?;
+    let keys = ctx.client.fetch_pool_keys(pool)?;
+    let quote = quote_deposit_single(ctx, pool, token, amount)?;
+
+    let deposit = DepositSingleTokenBuilder::new(&keys, signer.pubkey(), token.is_a(), amount)
+        .minimum_pool_tokens_out(less_slippage(quote.pool_tokens, slippage_bps))
+        .instruction();
+    let signature = ctx.send(&signer, &[deposit])?;
+
+    print(ctx.output, &TransactionView::new(signature));
+    Ok(())
+}
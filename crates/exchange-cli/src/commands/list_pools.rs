@@ -0,0 +1,33 @@
+use exchange_client::{sort_mints, ClientError};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::context::Context;
+use crate::output::{print, PoolListView, PoolView};
+use crate::Result;
+
+pub fn run(ctx: &Context, mint_a: Pubkey, mint_b: Pubkey, fee_tier: Option<u16>) -> Result<()> {
+    let (token_a_mint, token_b_mint) = sort_mints(mint_a, mint_b);
+    let fee_tiers = match fee_tier {
+        Some(fee_tier) => vec![fee_tier],
+        None => (0..ctx.client.fetch_config()?.fee_tiers.len() as u16).collect(),
+    };
+
+    let mut pools = vec![];
+    for fee_tier in fee_tiers {
+        let registry = match ctx
+            .client
+            .fetch_registry(&token_a_mint, &token_b_mint, fee_tier)
+        {
+            Ok(registry) => registry,
+            // No pool of the pair was created in this tier yet
+            Err(ClientError::AccountNotFound(_)) => continue,
+            Err(err) => return Err(err.into()),
+        };
+        for pool in &registry.pools {
+            pools.push(PoolView::from(&ctx.fetch_pool_info(pool)?));
+        }
+    }
+
+    print(ctx.output, &PoolListView(pools));
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use exchange_client::MigratePoolBuilder;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use crate::context::Context;
+use crate::output::{print, TransactionView};
+use crate::Result;
+
+pub fn run(ctx: &Context, pool: &Pubkey) -> Result<()> {
+    let signer = ctx.signer()?;
+    let migrate = MigratePoolBuilder::new(*pool, signer.pubkey()).instruction();
+    let signature = ctx.send(&signer, &[migrate])?;
+
+    print(ctx.output, &TransactionView::new(signature));
+    Ok(())
+}
@@ -0,0 +1,40 @@
+pub mod close_pool;
+pub mod collect_fees;
+pub mod create_pool;
+pub mod deposit;
+pub mod list_pools;
+pub mod migrate_pool;
+pub mod quote;
+pub mod show_pool;
+pub mod swap;
+pub mod withdraw;
+
+/// `amount` raised by `slippage_bps`, the most a trade may cost over its quote
+fn with_slippage(amount: u64, slippage_bps: u64) -> u64 {
+    let amount = amount as u128 * (10_000 + slippage_bps as u128) / 10_000;
+    u64::try_from(amount).unwrap_or(u64::MAX)
+}
+
+/// `amount` lowered by `slippage_bps`, the least a trade may return under its quote
+fn less_slippage(amount: u64, slippage_bps: u64) -> u64 {
+    (amount as u128 * 10_000u128.saturating_sub(slippage_bps as u128) / 10_000) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_slippage() {
+        assert_eq!(with_slippage(10_000, 50), 10_050);
+        assert_eq!(with_slippage(10_000, 0), 10_000);
+        assert_eq!(with_slippage(u64::MAX, 50), u64::MAX);
+    }
+
+    #[test]
+    fn test_less_slippage() {
+        assert_eq!(less_slippage(10_000, 50), 9_950);
+        assert_eq!(less_slippage(10_000, 0), 10_000);
+        assert_eq!(less_slippage(10_000, 20_000), 0);
+    }
+}
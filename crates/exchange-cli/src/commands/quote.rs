@@ -0,0 +1,169 @@
+use clap::Subcommand;
+use exchange_client::quote::{
+    quote_deposit, quote_swap, quote_withdraw, LiquidityQuote, SwapQuote,
+};
+use solana_sdk::account::from_account;
+use solana_sdk::clock::Clock;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::sysvar;
+
+use crate::context::{Context, PoolInfo};
+use crate::output::{print, LiquidityQuoteView, SwapQuoteView};
+use crate::transfer_fee::{amount_after_transfer_fee, amount_before_transfer_fee};
+use crate::{Result, Token};
+
+#[derive(Subcommand)]
+pub enum QuoteCommand {
+    /// Tokens received for an exact amount sold
+    Swap {
+        #[arg(long)]
+        pool: Pubkey,
+        /// Token sold into the pool
+        #[arg(long, value_enum)]
+        token: Token,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Pool tokens minted for an exact single token deposit
+    Deposit {
+        #[arg(long)]
+        pool: Pubkey,
+        #[arg(long, value_enum)]
+        token: Token,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Pool tokens burned for an exact single token withdrawal
+    Withdraw {
+        #[arg(long)]
+        pool: Pubkey,
+        #[arg(long, value_enum)]
+        token: Token,
+        #[arg(long)]
+        amount: u64,
+    },
+}
+
+/// Pool with its volatility decayed at the cluster's time, as the program does before
+/// pricing, and the epoch its transfer fees are taken at
+pub(crate) fn fetch_pool_info_now(ctx: &Context, pool: &Pubkey) -> Result<(PoolInfo, u64)> {
+    let mut info = ctx.fetch_pool_info(pool)?;
+    let clock = ctx.client.fetch_account(&sysvar::clock::ID)?;
+    let clock: Clock = from_account(&clock).ok_or("failed to decode the clock sysvar")?;
+    info.state.dynamic_fee.decay(clock.unix_timestamp);
+    Ok((info, clock.epoch))
+}
+
+/// Swap of `amount` of `token`, with the amounts the user pays and receives after the
+/// transfer fees of both mints, as `swap` would
+pub fn quote_swap_exact_in(
+    ctx: &Context,
+    pool: &Pubkey,
+    token: Token,
+    amount: u64,
+) -> Result<SwapQuote> {
+    let (info, epoch) = fetch_pool_info_now(ctx, pool)?;
+    let config = ctx.client.fetch_config()?;
+    let (source, destination) = info.reserves(token.is_a());
+    let (source_fee, destination_fee) = info.transfer_fees(token.is_a());
+    // the vault only receives what is left after the transfer fee of the source mint
+    let amount_received = amount_after_transfer_fee(source_fee, epoch, amount)?;
+    let mut quote = quote_swap(
+        amount_received,
+        source,
+        destination,
+        &info.state.current_fees().trade_fees(),
+        config.protocol_fee_numerator,
+        config.protocol_fee_denominator,
+    )
+    .map_err(|err| err.to_string())?;
+    if quote.destination_amount == 0 {
+        return Err("the amount is too small to receive any token".into());
+    }
+    quote.source_amount = amount_before_transfer_fee(source_fee, epoch, quote.source_amount)?;
+    quote.destination_amount =
+        amount_after_transfer_fee(destination_fee, epoch, quote.destination_amount)?;
+    Ok(quote)
+}
+
+/// Pool tokens minted for depositing `amount` of `token`, as `deposit_single_token` would
+pub fn quote_deposit_single(
+    ctx: &Context,
+    pool: &Pubkey,
+    token: Token,
+    amount: u64,
+) -> Result<LiquidityQuote> {
+    let (info, epoch) = fetch_pool_info_now(ctx, pool)?;
+    let config = ctx.client.fetch_config()?;
+    let (source, other) = info.reserves(token.is_a());
+    let (source_fee, _) = info.transfer_fees(token.is_a());
+    // pool tokens are minted for what the vault receives after the transfer fee
+    let amount_received = amount_after_transfer_fee(source_fee, epoch, amount)?;
+    let quote = quote_deposit(
+        amount_received,
+        source,
+        other,
+        info.supply,
+        &info.state.current_fees().trade_fees(),
+        config.protocol_fee_numerator,
+        config.protocol_fee_denominator,
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(quote)
+}
+
+/// Pool tokens burned for withdrawing `amount` of `token`, as `withdraw_single_token_out` would
+pub fn quote_withdraw_single(
+    ctx: &Context,
+    pool: &Pubkey,
+    token: Token,
+    amount: u64,
+) -> Result<LiquidityQuote> {
+    let (info, epoch) = fetch_pool_info_now(ctx, pool)?;
+    let config = ctx.client.fetch_config()?;
+    let (destination, other) = info.reserves(token.is_a());
+    let (destination_fee, _) = info.transfer_fees(token.is_a());
+    // the vault sends enough for the user to receive the amount after the transfer fee
+    let amount_sent = amount_before_transfer_fee(destination_fee, epoch, amount)?;
+    let quote = quote_withdraw(
+        amount_sent,
+        destination,
+        other,
+        info.supply,
+        &info.state.current_fees().trade_fees(),
+        config.protocol_fee_numerator,
+        config.protocol_fee_denominator,
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(quote)
+}
+
+pub fn run(ctx: &Context, command: QuoteCommand) -> Result<()> {
+    match command {
+        QuoteCommand::Swap {
+            pool,
+            token,
+            amount,
+        } => {
+            let quote = quote_swap_exact_in(ctx, &pool, token, amount)?;
+            print(ctx.output, &SwapQuoteView::from(quote));
+        }
+        QuoteCommand::Deposit {
+            pool,
+            token,
+            amount,
+        } => {
+            let quote = quote_deposit_single(ctx, &pool, token, amount)?;
+            print(ctx.output, &LiquidityQuoteView::new(amount, quote));
+        }
+        QuoteCommand::Withdraw {
+            pool,
+            token,
+            amount,
+        } => {
+            let quote = quote_withdraw_single(ctx, &pool, token, amount)?;
+            print(ctx.output, &LiquidityQuoteView::new(amount, quote));
+        }
+    }
+    Ok(())
+}
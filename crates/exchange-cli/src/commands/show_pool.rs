@@ -0,0 +1,11 @@
+use solana_sdk::pubkey::Pubkey;
+
+use crate::context::Context;
+use crate::output::{print, PoolView};
+use crate::Result;
+
+pub fn run(ctx: &Context, pool: &Pubkey) -> Result<()> {
+    let info = ctx.fetch_pool_info(pool)?;
+    print(ctx.output, &PoolView::from(&info));
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use exchange_client::SwapBuilder;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use super::less_slippage;
+use super::quote::quote_swap_exact_in;
+use crate::context::{create_ata, Context};
+use crate::output::{print, TransactionView};
+use crate::{Result, Token};
+
+/// Swaps `amount` of `token`, failing under `min_amount_out`, or else under the quoted
+/// amount out less the slippage
+pub fn run(
+    ctx: &Context,
+    pool: &Pubkey,
+    token: Token,
+    amount: u64,
+    min_amount_out: Option<u64>,
+    slippage_bps: u64,
+    host_fee_account: Option<Pubkey>,
+) -> Result<()> {
+    let signer = ctx.signer()?;
+    let user = signer.pubkey();
+    let keys = ctx.client.fetch_pool_keys(pool)?;
+
+    let min_amount_out = match min_amount_out {
+        Some(min_amount_out) => min_amount_out,
+        None => {
+            let quote = quote_swap_exact_in(ctx, pool, token, amount)?;
+            less_slippage(quote.destination_amount, slippage_bps)
+        }
+    };
+
+    let (_, mint, program) = keys.side(!token.is_a());
+    let (_, create_destination) = create_ata(&user, &user, &mint, &program);
+    let mut swap =
+        SwapBuilder::new(&keys, user, token.is_a(), amount).minimum_amount_out(min_amount_out);
+    if let Some(host_fee_account) = host_fee_account {
+        swap = swap.host_fee_account(host_fee_account);
+    }
+    let signature = ctx.send(&signer, &[create_destination, swap.instruction()])?;
+
+    print(ctx.output, &TransactionView::new(signature));
+    Ok(())
+}
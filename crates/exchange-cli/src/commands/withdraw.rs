@@ -0,0 +1,33 @@
+use exchange_client::WithdrawSingleTokenBuilder;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use super::quote::quote_withdraw_single;
+use super::with_slippage;
+use crate::context::{create_ata, Context};
+use crate::output::{print, TransactionView};
+use crate::{Result, Token};
+
+/// Withdraws `amount` of one token, failing over its quoted pool tokens plus the slippage
+pub fn run(
+    ctx: &Context,
+    pool: &Pubkey,
+    token: Token,
+    amount: u64,
+    slippage_bps: u64,
+) -> Result<()> {
+    let signer = ctx.signer()?;
+    let user = signer.pubkey();
+    let keys = ctx.client.fetch_pool_keys(pool)?;
+    let quote = quote_withdraw_single(ctx, pool, token, amount)?;
+
+    let (_, mint, program) = keys.side(token.is_a());
+    let (_, create_destination) = create_ata(&user, &user, &mint, &program);
+    let withdraw = WithdrawSingleTokenBuilder::new(&keys, user, token.is_a(), amount)
+        .maximum_pool_tokens_burned(with_slippage(quote.pool_tokens, slippage_bps))
+        .instruction();
+    let signature = ctx.send(&signer, &[create_destination, withdraw])?;
+
+    print(ctx.output, &TransactionView::new(signature));
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use std::path::PathBuf;
+
+use anchor_lang::AccountDeserialize;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use anchor_spl::token_interface::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use exchange_client::{ExchangeClient, Pool, PoolKeys};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+
+use crate::transfer_fee::transfer_fee_config;
+use crate::{OutputFormat, Result};
+
+/// Pool initialization writes the metadata and creates several accounts, well past the default budget
+const COMPUTE_UNIT_LIMIT: u32 = 400_000;
+
+pub struct Context {
+    pub client: ExchangeClient<RpcClient>,
+    pub output: OutputFormat,
+    keypair: PathBuf,
+}
+
+impl Context {
+    pub fn new(url: &str, keypair: Option<PathBuf>, output: OutputFormat) -> Result<Self> {
+        let keypair = match keypair {
+            Some(path) => path,
+            None => {
+                let home = std::env::var("HOME").map_err(|_| "HOME is not set, pass --keypair")?;
+                PathBuf::from(home).join(".config/solana/id.json")
+            }
+        };
+        let rpc = RpcClient::new_with_commitment(cluster_url(url), CommitmentConfig::confirmed());
+
+        Ok(Self {
+            client: ExchangeClient::new(rpc),
+            output,
+            keypair,
+        })
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        self.client.rpc()
+    }
+
+    /// Reads the keypair file, only commands sending a transaction need it
+    pub fn signer(&self) -> Result<Keypair> {
+        read_keypair_file(&self.keypair).map_err(|err| {
+            format!("failed to read keypair {}: {err}", self.keypair.display()).into()
+        })
+    }
+
+    /// Signs with `signer` alone and waits for confirmation
+    pub fn send(&self, signer: &Keypair, instructions: &[Instruction]) -> Result<Signature> {
+        let mut all = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+            COMPUTE_UNIT_LIMIT,
+        )];
+        all.extend_from_slice(instructions);
+        let blockhash = self.rpc().get_latest_blockhash()?;
+        let transaction =
+            Transaction::new_signed_with_payer(&all, Some(&signer.pubkey()), &[signer], blockhash);
+
+        Ok(self.rpc().send_and_confirm_transaction(&transaction)?)
+    }
+
+    pub fn fetch_pool_info(&self, pool: &Pubkey) -> Result<PoolInfo> {
+        let state = self.client.fetch_pool(pool)?;
+        let accounts = self.fetch_accounts(&[
+            state.token_a,
+            state.token_b,
+            state.mint,
+            state.token_a_mint,
+            state.token_b_mint,
+        ])?;
+        let token_a = TokenAccount::try_deserialize(&mut accounts[0].data.as_slice())?;
+        let token_b = TokenAccount::try_deserialize(&mut accounts[1].data.as_slice())?;
+        let mint = Mint::try_deserialize(&mut accounts[2].data.as_slice())?;
+        let keys = PoolKeys::new(*pool, &state, accounts[3].owner, accounts[4].owner);
+
+        Ok(PoolInfo {
+            keys,
+            vault_a: token_a.amount,
+            vault_b: token_b.amount,
+            supply: mint.supply,
+            transfer_fee_a: transfer_fee_config(&accounts[3])?,
+            transfer_fee_b: transfer_fee_config(&accounts[4])?,
+            state,
+        })
+    }
+
+    /// Owner of the mint, SPL Token or Token-2022
+    pub fn token_program(&self, mint: &Pubkey) -> Result<Pubkey> {
+        Ok(self.client.fetch_account(mint)?.owner)
+    }
+
+    fn fetch_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Account>> {
+        let accounts = self.rpc().get_multiple_accounts(addresses)?;
+        addresses
+            .iter()
+            .zip(accounts)
+            .map(|(address, account)| {
+                account.ok_or_else(|| format!("account {address} not found").into())
+            })
+            .collect()
+    }
+}
+
+/// Pool state with the balances its quotes are computed against
+pub struct PoolInfo {
+    pub keys: PoolKeys,
+    pub state: Pool,
+    pub vault_a: u64,
+    pub vault_b: u64,
+    pub supply: u64,
+    /// Token-2022 transfer fees of the mints, taken on every transfer in and out of the vaults
+    pub transfer_fee_a: Option<TransferFeeConfig>,
+    pub transfer_fee_b: Option<TransferFeeConfig>,
+}
+
+impl PoolInfo {
+    /// Token A owned by the liquidity providers, protocol fees excluded
+    pub fn reserve_a(&self) -> u64 {
+        self.vault_a
+            .saturating_sub(self.state.protocol_fees_token_a)
+    }
+
+    pub fn reserve_b(&self) -> u64 {
+        self.vault_b
+            .saturating_sub(self.state.protocol_fees_token_b)
+    }
+
+    /// (reserve of `token`, reserve of the other side)
+    pub fn reserves(&self, token_a: bool) -> (u64, u64) {
+        if token_a {
+            (self.reserve_a(), self.reserve_b())
+        } else {
+            (self.reserve_b(), self.reserve_a())
+        }
+    }
+
+    /// (transfer fee of `token`, transfer fee of the other side)
+    pub fn transfer_fees(
+        &self,
+        token_a: bool,
+    ) -> (Option<&TransferFeeConfig>, Option<&TransferFeeConfig>) {
+        if token_a {
+            (self.transfer_fee_a.as_ref(), self.transfer_fee_b.as_ref())
+        } else {
+            (self.transfer_fee_b.as_ref(), self.transfer_fee_a.as_ref())
+        }
+    }
+}
+
+/// Idempotently creates the associated token account of `owner` for `mint`
+pub fn create_ata(
+    payer: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> (Pubkey, Instruction) {
+    (
+        get_associated_token_address_with_program_id(owner, mint, token_program),
+        create_associated_token_account_idempotent(payer, owner, mint, token_program),
+    )
+}
+
+/// Full RPC URL for a cluster moniker, anything else is taken as a URL
+pub fn cluster_url(url: &str) -> String {
+    match url {
+        "l" | "localhost" => "http://127.0.0.1:8899",
+        "d" | "devnet" => "https://api.devnet.solana.com",
+        "t" | "testnet" => "https://api.testnet.solana.com",
+        "m" | "mainnet-beta" => "https://api.mainnet-beta.solana.com",
+        url => url,
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cluster_url() {
+        assert_eq!(cluster_url("localhost"), "http://127.0.0.1:8899");
+        assert_eq!(cluster_url("d"), "https://api.devnet.solana.com");
+        assert_eq!(cluster_url("http://my-rpc:8899"), "http://my-rpc:8899");
+    }
+}
@@ -0,0 +1,318 @@
+//! Command-line tool for exchange pool operators.
+
+mod commands;
+mod context;
+mod output;
+mod transfer_fee;
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::commands::quote::QuoteCommand;
+use crate::context::Context;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// JSON RPC URL or moniker of the cluster: localhost, devnet, testnet or mainnet-beta
+    #[arg(short, long, global = true, default_value = "localhost")]
+    url: String,
+    /// Keypair paying for and signing the transactions
+    #[arg(short, long, global = true)]
+    keypair: Option<PathBuf>,
+    #[arg(short, long, global = true, value_enum, default_value_t = OutputFormat::Display)]
+    output: OutputFormat,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Display,
+    Json,
+}
+
+/// Side of a pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Token {
+    A,
+    B,
+}
+
+impl Token {
+    pub fn is_a(self) -> bool {
+        self == Token::A
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a pool and seed it with both tokens
+    CreatePool {
+        #[arg(long)]
+        mint_a: Pubkey,
+        #[arg(long)]
+        mint_b: Pubkey,
+        #[arg(long)]
+        amount_a: u64,
+        #[arg(long)]
+        amount_b: u64,
+        #[arg(long, default_value_t = 0)]
+        fee_tier: u16,
+        /// Transfer hook program the pool's mints may invoke, repeatable
+        #[arg(long = "transfer-hook-program")]
+        transfer_hook_programs: Vec<Pubkey>,
+    },
+    /// Deposit both tokens for an exact amount of pool tokens, or a single token
+    Deposit {
+        #[arg(long)]
+        pool: Pubkey,
+        /// Pool tokens to mint against both tokens
+        #[arg(long, conflicts_with_all = ["token", "amount"], required_unless_present = "token")]
+        pool_tokens: Option<u64>,
+        /// Slippage allowed against the quote: over the tokens paid for a two sided
+        /// deposit, under the pool tokens minted for a single token one
+        #[arg(long, default_value_t = 50)]
+        slippage_bps: u64,
+        #[arg(long, value_enum, requires = "amount")]
+        token: Option<Token>,
+        #[arg(long, requires = "token")]
+        amount: Option<u64>,
+    },
+    /// Withdraw an exact amount of a single token
+    Withdraw {
+        #[arg(long)]
+        pool: Pubkey,
+        #[arg(long, value_enum)]
+        token: Token,
+        #[arg(long)]
+        amount: u64,
+        /// Slippage allowed over the quoted pool tokens burned
+        #[arg(long, default_value_t = 50)]
+        slippage_bps: u64,
+    },
+    /// Swap an exact amount of one token for the other
+    Swap {
+        #[arg(long)]
+        pool: Pubkey,
+        /// Token sold into the pool
+        #[arg(long, value_enum)]
+        token: Token,
+        #[arg(long)]
+        amount: u64,
+        /// Fail unless at least this much of the other token is received, net of its
+        /// transfer fee, instead of the quote less the slippage
+        #[arg(long, conflicts_with = "slippage_bps")]
+        min_amount_out: Option<u64>,
+        /// Slippage allowed under the quoted amount out
+        #[arg(long, default_value_t = 50)]
+        slippage_bps: u64,
+        /// Pool token account of the host receiving a share of the owner fee
+        #[arg(long)]
+        host_fee_account: Option<Pubkey>,
+    },
+    /// Quote an operation against the current pool state without sending it
+    Quote {
+        #[command(subcommand)]
+        command: QuoteCommand,
+    },
+    /// Decode and print a pool
+    ShowPool { pool: Pubkey },
+    /// Print the pools of a token pair, across every fee tier unless one is given
+    ListPools {
+        #[arg(long)]
+        mint_a: Pubkey,
+        #[arg(long)]
+        mint_b: Pubkey,
+        #[arg(long)]
+        fee_tier: Option<u16>,
+    },
+    /// Collect the protocol fees of a pool, signing as the treasury
+    CollectFees {
+        #[arg(long)]
+        pool: Pubkey,
+    },
+    /// Close an empty pool, signing as the admin or the creator
+    ClosePool {
+        #[arg(long)]
+        pool: Pubkey,
+        /// Receives the rent and the tokens left in the vaults, defaults to the signer
+        #[arg(long)]
+        receiver: Option<Pubkey>,
+    },
+    /// Rewrite a pool of an older layout in the current one, paying any extra rent
+    MigratePool { pool: Pubkey },
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let ctx = Context::new(&cli.url, cli.keypair, cli.output)?;
+
+    match cli.command {
+        Command::CreatePool {
+            mint_a,
+            mint_b,
+            amount_a,
+            amount_b,
+            fee_tier,
+            transfer_hook_programs,
+        } => commands::create_pool::run(
+            &ctx,
+            mint_a,
+            mint_b,
+            amount_a,
+            amount_b,
+            fee_tier,
+            transfer_hook_programs,
+        ),
+        Command::Deposit {
+            pool,
+            pool_tokens,
+            slippage_bps,
+            token,
+            amount,
+        } => match (pool_tokens, token, amount) {
+            (Some(pool_tokens), _, _) => {
+                commands::deposit::run_all(&ctx, &pool, pool_tokens, slippage_bps)
+            }
+            (None, Some(token), Some(amount)) => {
+                commands::deposit::run_single(&ctx, &pool, token, amount, slippage_bps)
+            }
+            _ => Err("either --pool-tokens or --token with --amount is required".into()),
+        },
+        Command::Withdraw {
+            pool,
+            token,
+            amount,
+            slippage_bps,
+        } => commands::withdraw::run(&ctx, &pool, token, amount, slippage_bps),
+        Command::Swap {
+            pool,
+            token,
+            amount,
+            min_amount_out,
+            slippage_bps,
+            host_fee_account,
+        } => commands::swap::run(
+            &ctx,
+            &pool,
+            token,
+            amount,
+            min_amount_out,
+            slippage_bps,
+            host_fee_account,
+        ),
+        Command::Quote { command } => commands::quote::run(&ctx, command),
+        Command::ShowPool { pool } => commands::show_pool::run(&ctx, &pool),
+        Command::ListPools {
+            mint_a,
+            mint_b,
+            fee_tier,
+        } => commands::list_pools::run(&ctx, mint_a, mint_b, fee_tier),
+        Command::CollectFees { pool } => commands::collect_fees::run(&ctx, &pool),
+        Command::ClosePool { pool, receiver } => commands::close_pool::run(&ctx, &pool, receiver),
+        Command::MigratePool { pool } => commands::migrate_pool::run(&ctx, &pool),
+    }
+}
+
+fn main() {
+    if let Err(err) = run(Cli::parse()) {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_deposit_args() {
+        let pool = Pubkey::new_unique().to_string();
+        let all = Cli::try_parse_from([
+            "exchange-cli",
+            "deposit",
+            "--pool",
+            &pool,
+            "--pool-tokens",
+            "10",
+        ]);
+        assert!(all.is_ok());
+        let single = Cli::try_parse_from([
+            "exchange-cli",
+            "deposit",
+            "--pool",
+            &pool,
+            "--token",
+            "a",
+            "--amount",
+            "10",
+        ]);
+        assert!(single.is_ok());
+        let both = Cli::try_parse_from([
+            "exchange-cli",
+            "deposit",
+            "--pool",
+            &pool,
+            "--pool-tokens",
+            "10",
+            "--token",
+            "a",
+            "--amount",
+            "10",
+        ]);
+        assert!(both.is_err());
+        let neither = Cli::try_parse_from(["exchange-cli", "deposit", "--pool", &pool]);
+        assert!(neither.is_err());
+    }
+
+    #[test]
+    fn test_swap_args() {
+        let pool = Pubkey::new_unique().to_string();
+        let args = [
+            "exchange-cli",
+            "swap",
+            "--pool",
+            &pool,
+            "--token",
+            "b",
+            "--amount",
+            "10",
+        ];
+        let Command::Swap {
+            min_amount_out,
+            slippage_bps,
+            ..
+        } = Cli::try_parse_from(args).unwrap().command
+        else {
+            panic!("parsed as another command");
+        };
+        assert_eq!(min_amount_out, None);
+        assert_eq!(slippage_bps, 50);
+
+        let with_minimum = [&args[..], &["--min-amount-out", "9"]].concat();
+        let Command::Swap { min_amount_out, .. } =
+            Cli::try_parse_from(with_minimum).unwrap().command
+        else {
+            panic!("parsed as another command");
+        };
+        assert_eq!(min_amount_out, Some(9));
+
+        let both = [
+            &args[..],
+            &["--min-amount-out", "9", "--slippage-bps", "10"],
+        ]
+        .concat();
+        assert!(Cli::try_parse_from(both).is_err());
+    }
+}
@@ -0,0 +1,343 @@
+use std::fmt;
+
+use exchange_client::quote::{LiquidityQuote, SwapQuote};
+use exchange_client::{Fee, Pool};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use crate::context::PoolInfo;
+use crate::OutputFormat;
+
+pub fn print<T: Serialize + fmt::Display>(format: OutputFormat, value: &T) {
+    match format {
+        OutputFormat::Display => println!("{value}"),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(value).expect("views serialize to JSON")
+        ),
+    }
+}
+
+/// `numerator / denominator` as a percentage
+fn percent(numerator: u64, denominator: u64) -> String {
+    if denominator == 0 {
+        return "0%".to_string();
+    }
+    format!("{}%", numerator as f64 * 100.0 / denominator as f64)
+}
+
+#[derive(Serialize)]
+pub struct FeesView {
+    pub trade_fee_numerator: u64,
+    pub trade_fee_denominator: u64,
+    pub owner_trade_fee_numerator: u64,
+    pub owner_trade_fee_denominator: u64,
+    pub owner_withdraw_fee_numerator: u64,
+    pub owner_withdraw_fee_denominator: u64,
+}
+
+impl From<&Fee> for FeesView {
+    fn from(fees: &Fee) -> Self {
+        Self {
+            trade_fee_numerator: fees.trade_fee_numerator,
+            trade_fee_denominator: fees.trade_fee_denominator,
+            owner_trade_fee_numerator: fees.owner_trade_fee_numerator,
+            owner_trade_fee_denominator: fees.owner_trade_fee_denominator,
+            owner_withdraw_fee_numerator: fees.owner_withdraw_fee_numerator,
+            owner_withdraw_fee_denominator: fees.owner_withdraw_fee_denomiator,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DynamicFeeView {
+    pub min_fee_numerator: u64,
+    pub max_fee_numerator: u64,
+    pub max_volatility: u64,
+    pub decay_period: i64,
+    pub volatility_accumulator: u64,
+    pub last_update_timestamp: i64,
+}
+
+/// Decoded pool state with its balances
+#[derive(Serialize)]
+pub struct PoolView {
+    pub address: String,
+    pub creator: String,
+    pub token_a_mint: String,
+    pub token_b_mint: String,
+    pub token_a: String,
+    pub token_b: String,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub protocol_fees_token_a: u64,
+    pub protocol_fees_token_b: u64,
+    pub mint: String,
+    pub supply: u64,
+    pub fee_account: String,
+    pub fee_tier: u16,
+    pub fees: FeesView,
+    /// Trade fee numerator charged on the next swap
+    pub current_trade_fee_numerator: u64,
+    pub dynamic_fee: Option<DynamicFeeView>,
+    pub transfer_hook_programs: Vec<String>,
+    /// Layout version, older pools need `migrate-pool`
+    pub version: u8,
+}
+
+impl From<&PoolInfo> for PoolView {
+    fn from(info: &PoolInfo) -> Self {
+        let state = &info.state;
+        let dynamic = &state.dynamic_fee;
+
+        Self {
+            address: info.keys.pool.to_string(),
+            creator: state.creator.to_string(),
+            token_a_mint: state.token_a_mint.to_string(),
+            token_b_mint: state.token_b_mint.to_string(),
+            token_a: state.token_a.to_string(),
+            token_b: state.token_b.to_string(),
+            reserve_a: info.reserve_a(),
+            reserve_b: info.reserve_b(),
+            protocol_fees_token_a: state.protocol_fees_token_a,
+            protocol_fees_token_b: state.protocol_fees_token_b,
+            mint: state.mint.to_string(),
+            supply: info.supply,
+            fee_account: state.fee_account.to_string(),
+            fee_tier: state.fee_tier,
+            fees: FeesView::from(&state.fees),
+            current_trade_fee_numerator: state.current_fees().trade_fee_numerator,
+            dynamic_fee: dynamic.is_enabled().then_some(DynamicFeeView {
+                min_fee_numerator: dynamic.config.min_fee_numerator,
+                max_fee_numerator: dynamic.config.max_fee_numerator,
+                max_volatility: dynamic.config.max_volatility,
+                decay_period: dynamic.config.decay_period,
+                volatility_accumulator: dynamic.volatility_accumulator,
+                last_update_timestamp: dynamic.last_update_timestamp,
+            }),
+            transfer_hook_programs: state
+                .transfer_hook_programs()
+                .iter()
+                .map(Pubkey::to_string)
+                .collect(),
+            version: state.version,
+        }
+    }
+}
+
+impl fmt::Display for PoolView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fees = &self.fees;
+        writeln!(f, "Pool:            {}", self.address)?;
+        writeln!(f, "Creator:         {}", self.creator)?;
+        writeln!(
+            f,
+            "Token A:         {} (vault {})",
+            self.token_a_mint, self.token_a
+        )?;
+        writeln!(
+            f,
+            "Token B:         {} (vault {})",
+            self.token_b_mint, self.token_b
+        )?;
+        writeln!(
+            f,
+            "Reserves:        {} / {}",
+            self.reserve_a, self.reserve_b
+        )?;
+        writeln!(
+            f,
+            "Protocol fees:   {} / {}",
+            self.protocol_fees_token_a, self.protocol_fees_token_b
+        )?;
+        writeln!(f, "Pool mint:       {} (supply {})", self.mint, self.supply)?;
+        writeln!(f, "Fee account:     {}", self.fee_account)?;
+        writeln!(f, "Fee tier:        {}", self.fee_tier)?;
+        writeln!(
+            f,
+            "Trade fee:       {}",
+            percent(self.current_trade_fee_numerator, fees.trade_fee_denominator)
+        )?;
+        writeln!(
+            f,
+            "Owner fee:       {}",
+            percent(
+                fees.owner_trade_fee_numerator,
+                fees.owner_trade_fee_denominator
+            )
+        )?;
+        writeln!(
+            f,
+            "Withdraw fee:    {}",
+            percent(
+                fees.owner_withdraw_fee_numerator,
+                fees.owner_withdraw_fee_denominator
+            )
+        )?;
+        match &self.dynamic_fee {
+            Some(dynamic) => writeln!(
+                f,
+                "Dynamic fee:     {} to {}, volatility {}/{} bps",
+                percent(dynamic.min_fee_numerator, fees.trade_fee_denominator),
+                percent(dynamic.max_fee_numerator, fees.trade_fee_denominator),
+                dynamic.volatility_accumulator,
+                dynamic.max_volatility
+            )?,
+            None => writeln!(f, "Dynamic fee:     off")?,
+        }
+        if self.transfer_hook_programs.is_empty() {
+            writeln!(f, "Transfer hooks:  none")?;
+        } else {
+            writeln!(
+                f,
+                "Transfer hooks:  {}",
+                self.transfer_hook_programs.join(", ")
+            )?;
+        }
+        if self.version == Pool::VERSION {
+            write!(f, "Layout version:  {}", self.version)
+        } else {
+            write!(f, "Layout version:  {} (run migrate-pool)", self.version)
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct PoolListView(pub Vec<PoolView>);
+
+impl fmt::Display for PoolListView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "No pools found");
+        }
+        for (i, pool) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+                writeln!(f)?;
+            }
+            write!(f, "{pool}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+pub struct SwapQuoteView {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub trade_fee: u64,
+    pub owner_fee: u64,
+    pub protocol_fee: u64,
+    pub price_impact_bps: u64,
+}
+
+impl From<SwapQuote> for SwapQuoteView {
+    fn from(quote: SwapQuote) -> Self {
+        Self {
+            amount_in: quote.source_amount,
+            amount_out: quote.destination_amount,
+            trade_fee: quote.trade_fee,
+            owner_fee: quote.owner_fee,
+            protocol_fee: quote.protocol_fee,
+            price_impact_bps: quote.price_impact_bps,
+        }
+    }
+}
+
+impl fmt::Display for SwapQuoteView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Amount in:       {}", self.amount_in)?;
+        writeln!(f, "Amount out:      {}", self.amount_out)?;
+        writeln!(f, "Trade fee:       {}", self.trade_fee)?;
+        writeln!(f, "Owner fee:       {}", self.owner_fee)?;
+        writeln!(f, "Protocol fee:    {}", self.protocol_fee)?;
+        write!(f, "Price impact:    {} bps", self.price_impact_bps)
+    }
+}
+
+#[derive(Serialize)]
+pub struct LiquidityQuoteView {
+    pub token_amount: u64,
+    pub pool_tokens: u64,
+    pub trade_fee: u64,
+    pub owner_fee: u64,
+    pub protocol_fee: u64,
+    pub price_impact_bps: u64,
+}
+
+impl LiquidityQuoteView {
+    pub fn new(token_amount: u64, quote: LiquidityQuote) -> Self {
+        Self {
+            token_amount,
+            pool_tokens: quote.pool_tokens,
+            trade_fee: quote.trade_fee,
+            owner_fee: quote.owner_fee,
+            protocol_fee: quote.protocol_fee,
+            price_impact_bps: quote.price_impact_bps,
+        }
+    }
+}
+
+impl fmt::Display for LiquidityQuoteView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Token amount:    {}", self.token_amount)?;
+        writeln!(f, "Pool tokens:     {}", self.pool_tokens)?;
+        writeln!(f, "Trade fee:       {}", self.trade_fee)?;
+        writeln!(f, "Owner fee:       {}", self.owner_fee)?;
+        writeln!(f, "Protocol fee:    {}", self.protocol_fee)?;
+        write!(f, "Price impact:    {} bps", self.price_impact_bps)
+    }
+}
+
+#[derive(Serialize)]
+pub struct TransactionView {
+    pub signature: String,
+    /// Pool created by the transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool: Option<String>,
+}
+
+impl TransactionView {
+    pub fn new(signature: Signature) -> Self {
+        Self {
+            signature: signature.to_string(),
+            pool: None,
+        }
+    }
+}
+
+impl fmt::Display for TransactionView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(pool) = &self.pool {
+            writeln!(f, "Pool:            {pool}")?;
+        }
+        write!(f, "Signature:       {}", self.signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent() {
+        assert_eq!(percent(25, 10_000), "0.25%");
+        assert_eq!(percent(1, 1), "100%");
+        assert_eq!(percent(0, 0), "0%");
+    }
+
+    #[test]
+    fn test_transaction_view() {
+        let view = TransactionView {
+            signature: "sig".to_string(),
+            pool: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&view).unwrap(),
+            r#"{"signature":"sig"}"#
+        );
+        assert_eq!(view.to_string(), "Signature:       sig");
+    }
+}
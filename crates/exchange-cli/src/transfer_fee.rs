@@ -0,0 +1,97 @@
+//! Off-chain mirror of the program's Token-2022 transfer fee adjustments, so
+//! that quotes match what the instructions move.
+
+use anchor_spl::token_interface::spl_token_2022::{
+    self,
+    extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+};
+use solana_sdk::account::Account;
+
+use crate::Result;
+
+/// Transfer fee of a mint account, none for SPL Token mints and mints without the extension
+pub fn transfer_fee_config(mint: &Account) -> Result<Option<TransferFeeConfig>> {
+    if mint.owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint.data)?;
+    Ok(mint.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+/// Amount received when `amount` is sent, net of the transfer fee at `epoch`
+pub fn amount_after_transfer_fee(
+    config: Option<&TransferFeeConfig>,
+    epoch: u64,
+    amount: u64,
+) -> Result<u64> {
+    let Some(config) = config else {
+        return Ok(amount);
+    };
+
+    let fee = config
+        .calculate_epoch_fee(epoch, amount)
+        .ok_or("transfer fee overflow")?;
+    Ok(amount.checked_sub(fee).ok_or("transfer fee overflow")?)
+}
+
+/// Amount to send so that `amount` is received after the transfer fee at `epoch`
+pub fn amount_before_transfer_fee(
+    config: Option<&TransferFeeConfig>,
+    epoch: u64,
+    amount: u64,
+) -> Result<u64> {
+    let Some(config) = config else {
+        return Ok(amount);
+    };
+
+    Ok(config
+        .get_epoch_fee(epoch)
+        .calculate_pre_fee_amount(amount)
+        .ok_or("transfer fee overflow")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_spl::token_interface::spl_token_2022::extension::transfer_fee::TransferFee;
+
+    fn config(basis_points: u16, maximum_fee: u64) -> TransferFeeConfig {
+        let fee = TransferFee {
+            epoch: 0.into(),
+            maximum_fee: maximum_fee.into(),
+            transfer_fee_basis_points: basis_points.into(),
+        };
+        TransferFeeConfig {
+            older_transfer_fee: fee,
+            newer_transfer_fee: fee,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_without_transfer_fee() {
+        assert_eq!(amount_after_transfer_fee(None, 0, 1_000).unwrap(), 1_000);
+        assert_eq!(amount_before_transfer_fee(None, 0, 1_000).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn test_transfer_fee_round_trip() {
+        // 1% capped at 5 tokens
+        let config = config(100, 5);
+        assert_eq!(
+            amount_after_transfer_fee(Some(&config), 3, 300).unwrap(),
+            297
+        );
+        assert_eq!(
+            amount_after_transfer_fee(Some(&config), 3, 10_000).unwrap(),
+            9_995
+        );
+
+        let sent = amount_before_transfer_fee(Some(&config), 3, 297).unwrap();
+        assert_eq!(
+            amount_after_transfer_fee(Some(&config), 3, sent).unwrap(),
+            297
+        );
+    }
+}
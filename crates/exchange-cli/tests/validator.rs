@@ -0,0 +1,278 @@
+//! End-to-end runs of the `exchange-cli` binary against `solana-test-validator`.
+//!
+//! The validator loads the SBF build of the program, so build it first with
+//! `anchor build` and run these with `cargo test -p exchange-cli -- --ignored`.
+//! `SBF_OUT_DIR` overrides where `exchange.so` is looked up.
+
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use anchor_spl::token::spl_token;
+use exchange_client::{AddFeeTierBuilder, Fee, InitializeConfigBuilder};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::Transaction;
+
+const RPC_PORT: u16 = 18899;
+const FAUCET_PORT: u16 = 19900;
+const FEE_DENOMINATOR: u64 = 10_000;
+
+/// Validator running in its own ledger directory, killed and cleaned up on drop
+struct TestValidator {
+    process: Child,
+    directory: PathBuf,
+    payer: Keypair,
+    rpc: RpcClient,
+}
+
+impl TestValidator {
+    /// Starts a validator with the program deployed under the payer's upgrade authority
+    fn start() -> Self {
+        let directory = std::env::temp_dir().join(format!("exchange-cli-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let payer = Keypair::new();
+        write_keypair_file(&payer, directory.join("payer.json")).unwrap();
+
+        let program = std::env::var("SBF_OUT_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy"))
+            .join("exchange.so");
+        assert!(program.exists(), "{} is missing", program.display());
+
+        let process = Command::new("solana-test-validator")
+            .arg("--reset")
+            .arg("--quiet")
+            .arg("--ledger")
+            .arg(directory.join("ledger"))
+            .args(["--rpc-port", &RPC_PORT.to_string()])
+            .args(["--faucet-port", &FAUCET_PORT.to_string()])
+            .args(["--mint", &payer.pubkey().to_string()])
+            .arg("--upgradeable-program")
+            .arg(exchange::ID.to_string())
+            .arg(&program)
+            .arg(payer.pubkey().to_string())
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("solana-test-validator is installed");
+
+        let rpc = RpcClient::new_with_commitment(
+            format!("http://127.0.0.1:{RPC_PORT}"),
+            CommitmentConfig::confirmed(),
+        );
+        let validator = Self {
+            process,
+            directory,
+            payer,
+            rpc,
+        };
+
+        let start = Instant::now();
+        while validator.rpc.get_latest_blockhash().is_err() {
+            assert!(
+                start.elapsed() < Duration::from_secs(60),
+                "validator did not start"
+            );
+            sleep(Duration::from_millis(500));
+        }
+        validator
+    }
+
+    fn send(&self, instructions: &[Instruction], signers: &[&Keypair]) {
+        let blockhash = self.rpc.get_latest_blockhash().unwrap();
+        let mut all = vec![&self.payer];
+        all.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &all,
+            blockhash,
+        );
+        self.rpc.send_and_confirm_transaction(&transaction).unwrap();
+    }
+
+    /// Creates the config with a single fee tier, as the admin does once after deploying
+    fn initialize_config(&self) {
+        let admin = self.payer.pubkey();
+        let fees = Fee {
+            trade_fee_numerator: 30,
+            trade_fee_denominator: FEE_DENOMINATOR,
+            owner_trade_fee_numerator: 5,
+            owner_trade_fee_denominator: FEE_DENOMINATOR,
+            owner_withdraw_fee_numerator: 0,
+            owner_withdraw_fee_denomiator: FEE_DENOMINATOR,
+        };
+        self.send(
+            &[
+                InitializeConfigBuilder::new(admin).instruction(),
+                AddFeeTierBuilder::new(admin, fees).instruction(),
+            ],
+            &[],
+        );
+    }
+
+    /// SPL Token mint with `amount` minted to the payer's associated token account
+    fn create_mint(&self, amount: u64) -> Pubkey {
+        let mint = Keypair::new();
+        let payer = self.payer.pubkey();
+        let rent = self
+            .rpc
+            .get_minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN)
+            .unwrap();
+        self.send(
+            &[
+                system_instruction::create_account(
+                    &payer,
+                    &mint.pubkey(),
+                    rent,
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::ID,
+                ),
+                spl_token::instruction::initialize_mint2(
+                    &spl_token::ID,
+                    &mint.pubkey(),
+                    &payer,
+                    None,
+                    6,
+                )
+                .unwrap(),
+                create_associated_token_account_idempotent(
+                    &payer,
+                    &payer,
+                    &mint.pubkey(),
+                    &spl_token::ID,
+                ),
+                spl_token::instruction::mint_to(
+                    &spl_token::ID,
+                    &mint.pubkey(),
+                    &get_associated_token_address(&payer, &mint.pubkey()),
+                    &payer,
+                    &[],
+                    amount,
+                )
+                .unwrap(),
+            ],
+            &[&mint],
+        );
+        mint.pubkey()
+    }
+
+    fn cli(&self, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_exchange-cli"))
+            .args(["--url", &format!("http://127.0.0.1:{RPC_PORT}")])
+            .arg("--keypair")
+            .arg(self.directory.join("payer.json"))
+            .args(["--output", "json"])
+            .args(args)
+            .output()
+            .unwrap()
+    }
+
+    /// Runs the CLI and parses its JSON output, panicking with its error when it fails
+    fn cli_json(&self, args: &[&str]) -> serde_json::Value {
+        let output = self.cli(args);
+        assert!(
+            output.status.success(),
+            "exchange-cli {args:?} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        serde_json::from_slice(&output.stdout).unwrap()
+    }
+}
+
+impl Drop for TestValidator {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+        let _ = std::fs::remove_dir_all(&self.directory);
+    }
+}
+
+fn field(value: &serde_json::Value, name: &str) -> u64 {
+    value[name]
+        .as_u64()
+        .unwrap_or_else(|| panic!("{name} missing in {value}"))
+}
+
+#[test]
+#[ignore = "needs solana-test-validator and the SBF build of the program"]
+fn create_pool_swap_and_show_pool() {
+    let validator = TestValidator::start();
+    validator.initialize_config();
+    let mint_a = validator.create_mint(10_000_000).to_string();
+    let mint_b = validator.create_mint(10_000_000).to_string();
+
+    let created = validator.cli_json(&[
+        "create-pool",
+        "--mint-a",
+        &mint_a,
+        "--mint-b",
+        &mint_b,
+        "--amount-a",
+        "1000000",
+        "--amount-b",
+        "1000000",
+    ]);
+    let pool = created["pool"].as_str().unwrap().to_string();
+
+    let pool_view = validator.cli_json(&["show-pool", &pool]);
+    // the amounts follow their mint whichever order the pool sorted them in
+    let token = if pool_view["token_a_mint"] == mint_a.as_str() {
+        "a"
+    } else {
+        "b"
+    };
+    let (reserve_in, reserve_out) = if token == "a" {
+        ("reserve_a", "reserve_b")
+    } else {
+        ("reserve_b", "reserve_a")
+    };
+    assert_eq!(field(&pool_view, reserve_in), 1_000_000);
+    assert_eq!(field(&pool_view, reserve_out), 1_000_000);
+
+    let quote = validator.cli_json(&[
+        "quote", "swap", "--pool", &pool, "--token", token, "--amount", "10000",
+    ]);
+    let amount_out = field(&quote, "amount_out");
+    assert!(amount_out > 0 && amount_out < 10_000);
+
+    // asking for more than the quote is rejected on chain and leaves the pool untouched
+    let too_much = (amount_out + 1).to_string();
+    let rejected = validator.cli(&[
+        "swap",
+        "--pool",
+        &pool,
+        "--token",
+        token,
+        "--amount",
+        "10000",
+        "--min-amount-out",
+        &too_much,
+    ]);
+    assert!(!rejected.status.success());
+
+    let minimum = amount_out.to_string();
+    validator.cli_json(&[
+        "swap",
+        "--pool",
+        &pool,
+        "--token",
+        token,
+        "--amount",
+        "10000",
+        "--min-amount-out",
+        &minimum,
+    ]);
+
+    let pool_view = validator.cli_json(&["show-pool", &pool]);
+    assert_eq!(field(&pool_view, reserve_in), 1_010_000);
+    assert_eq!(field(&pool_view, reserve_out), 1_000_000 - amount_out);
+}
@@ -0,0 +1,230 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator};
+use exchange::quote::{LiquidityQuote, SwapQuote};
+use exchange::state::{Config, Fee, LegacyPool, Pool, PoolRegistry};
+
+use crate::error::{ClientError, Result};
+use crate::pda::find_pool_authority_address;
+
+fn deserialize_account<T: AccountDeserialize>(mut data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut data).map_err(ClientError::Deserialize)
+}
+
+/// Pool account data, discriminator included.
+///
+/// Pools of an older Borsh layout are converted, their `version` tells they
+/// need `migrate_pool`.
+pub fn deserialize_pool(data: &[u8]) -> Result<Pool> {
+    if !data.starts_with(&Pool::DISCRIMINATOR) {
+        return Err(ClientError::Deserialize(
+            ErrorCode::AccountDiscriminatorMismatch.into(),
+        ));
+    }
+
+    if data.len() == Pool::MAX_SIZE {
+        // RPC buffers aren't aligned for the zero-copy layout
+        return Ok(bytemuck::pod_read_unaligned(&data[8..]));
+    }
+    LegacyPool::try_from_account_data(data)
+        .and_then(Pool::try_from)
+        .map_err(ClientError::Deserialize)
+}
+
+pub fn deserialize_config(data: &[u8]) -> Result<Config> {
+    deserialize_account(data)
+}
+
+pub fn deserialize_registry(data: &[u8]) -> Result<PoolRegistry> {
+    deserialize_account(data)
+}
+
+/// Borsh encoded fees as embedded in the pool and config accounts, without a discriminator
+pub fn deserialize_fee(mut data: &[u8]) -> Result<Fee> {
+    Fee::deserialize(&mut data).map_err(|error| ClientError::Deserialize(error.into()))
+}
+
+/// The runtime drops trailing zeros of the return data, quotes are plain u64 fields
+fn deserialize_quote<T: AnchorDeserialize>(data: &[u8]) -> Result<T> {
+    let mut data = data.to_vec();
+    data.resize(std::mem::size_of::<T>().max(data.len()), 0);
+    T::try_from_slice(&data).map_err(|error| ClientError::Deserialize(error.into()))
+}
+
+/// Return data of `quote_swap`
+pub fn deserialize_swap_quote(data: &[u8]) -> Result<SwapQuote> {
+    deserialize_quote(data)
+}
+
+/// Return data of `quote_deposit_single` and `quote_withdraw_single`
+pub fn deserialize_liquidity_quote(data: &[u8]) -> Result<LiquidityQuote> {
+    deserialize_quote(data)
+}
+
+/// Every address the pool instructions need, resolved once from the pool state
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolKeys {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub creator: Pubkey,
+    pub token_a: Pubkey,
+    pub token_b: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub mint: Pubkey,
+    pub fee_account: Pubkey,
+    /// Owner of the token A mint, SPL Token or Token-2022
+    pub token_a_program: Pubkey,
+    pub token_b_program: Pubkey,
+}
+
+impl PoolKeys {
+    pub fn new(
+        pool: Pubkey,
+        state: &Pool,
+        token_a_program: Pubkey,
+        token_b_program: Pubkey,
+    ) -> Self {
+        Self {
+            pool,
+            authority: find_pool_authority_address(&pool).0,
+            creator: state.creator,
+            token_a: state.token_a,
+            token_b: state.token_b,
+            token_a_mint: state.token_a_mint,
+            token_b_mint: state.token_b_mint,
+            mint: state.mint,
+            fee_account: state.fee_account,
+            token_a_program,
+            token_b_program,
+        }
+    }
+
+    /// (vault, mint, token program) of the token A side, or of token B when `token_a` is false
+    pub fn side(&self, token_a: bool) -> (Pubkey, Pubkey, Pubkey) {
+        if token_a {
+            (self.token_a, self.token_a_mint, self.token_a_program)
+        } else {
+            (self.token_b, self.token_b_mint, self.token_b_program)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AnchorSerialize;
+    use exchange::state::{BaselinePool, PoolLayout};
+
+    #[test]
+    fn quote_with_trailing_zeros_dropped_deserializes() {
+        let quote = LiquidityQuote {
+            pool_tokens: 4_987,
+            price_impact_bps: 0,
+        };
+        let data = quote.try_to_vec().unwrap();
+        let trimmed = &data[..2];
+
+        assert_eq!(deserialize_liquidity_quote(trimmed).unwrap(), quote);
+        assert_eq!(deserialize_liquidity_quote(&[]).unwrap().pool_tokens, 0);
+    }
+
+    #[test]
+    fn legacy_pools_read_with_their_version() {
+        let legacy = LegacyPool {
+            bump: 254,
+            token_a: Pubkey::new_unique(),
+            token_b: Pubkey::new_unique(),
+            token_a_mint: Pubkey::new_unique(),
+            token_b_mint: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            fee_account: Pubkey::new_unique(),
+            fees: Fee {
+                trade_fee_numerator: 25,
+                trade_fee_denominator: 10_000,
+                owner_trade_fee_numerator: 5,
+                owner_trade_fee_denominator: 10_000,
+                owner_withdraw_fee_numerator: 0,
+                owner_withdraw_fee_denomiator: 10_000,
+            },
+            fee_tier: 1,
+            protocol_fees_token_a: 7,
+            protocol_fees_token_b: 9,
+            dynamic_fee: Default::default(),
+            transfer_hook_programs: vec![Pubkey::new_unique(); Pool::MAX_TRANSFER_HOOK_PROGRAMS],
+            layout: PoolLayout {
+                version: 1,
+                ..PoolLayout::default()
+            },
+        };
+        let mut data = Pool::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        // a full list of transfer hooks left only zeros after it in unversioned pools
+        let mut unversioned = data[..data.len() - PoolLayout::MAX_SIZE].to_vec();
+        unversioned.resize(LegacyPool::UNVERSIONED_SIZE, 0);
+        data.resize(LegacyPool::MAX_SIZE, 0);
+
+        let pool = deserialize_pool(&data).unwrap();
+        assert_eq!(pool.version, 1);
+        assert_eq!(pool.creator, legacy.creator);
+        assert_eq!(pool.fees.trade_fee_numerator, 25);
+        assert_eq!(pool.protocol_fees_token_b, 9);
+        assert_eq!(
+            pool.transfer_hook_programs(),
+            legacy.transfer_hook_programs.as_slice()
+        );
+        assert_eq!(deserialize_pool(&unversioned).unwrap().version, 0);
+    }
+
+    #[test]
+    fn baseline_pools_read_under_the_default_fee_tier() {
+        let baseline = BaselinePool {
+            bump: 253,
+            token_a: Pubkey::new_unique(),
+            token_b: Pubkey::new_unique(),
+            token_a_mint: Pubkey::new_unique(),
+            token_b_mint: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            fee_account: Pubkey::new_unique(),
+            fees: Fee {
+                trade_fee_numerator: 25,
+                trade_fee_denominator: 10_000,
+                owner_trade_fee_numerator: 5,
+                owner_trade_fee_denominator: 10_000,
+                owner_withdraw_fee_numerator: 0,
+                owner_withdraw_fee_denomiator: 10_000,
+            },
+        };
+        let mut data = Pool::DISCRIMINATOR.to_vec();
+        baseline.serialize(&mut data).unwrap();
+        data.resize(LegacyPool::BASELINE_SIZE, 0);
+
+        let pool = deserialize_pool(&data).unwrap();
+        assert_eq!(pool.version, 0);
+        assert_eq!(pool.bump, 253);
+        assert_eq!(pool.fee_account, baseline.fee_account);
+        assert_eq!(pool.fees.trade_fee_numerator, 25);
+        assert_eq!(pool.fee_tier, 0);
+        assert_eq!(pool.protocol_fees_token_a, 0);
+        assert!(!pool.dynamic_fee.is_enabled());
+        assert!(pool.transfer_hook_programs().is_empty());
+    }
+
+    #[test]
+    fn zero_copy_pool_reads_from_unaligned_data() {
+        let mut state: Pool = bytemuck::Zeroable::zeroed();
+        state.version = Pool::VERSION;
+        state.protocol_fees_token_a = 42;
+
+        let mut data = vec![0];
+        data.extend_from_slice(&Pool::DISCRIMINATOR);
+        data.extend_from_slice(bytemuck::bytes_of(&state));
+
+        let pool = deserialize_pool(&data[1..]).unwrap();
+        assert_eq!(pool.version, Pool::VERSION);
+        assert_eq!(pool.protocol_fees_token_a, 42);
+        assert!(deserialize_pool(&data[..Pool::MAX_SIZE]).is_err());
+    }
+}
@@ -0,0 +1,16 @@
+use anchor_lang::prelude::Pubkey;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("rpc request failed: {0}")]
+    Rpc(Box<dyn std::error::Error + Send + Sync>),
+    #[error("account {0} not found")]
+    AccountNotFound(Pubkey),
+    #[error("account {0} is owned by {1}, not the expected program")]
+    InvalidOwner(Pubkey, Pubkey),
+    #[error("failed to deserialize account: {0}")]
+    Deserialize(anchor_lang::error::Error),
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
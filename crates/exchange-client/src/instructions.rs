@@ -0,0 +1,1157 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+use exchange::state::{DynamicFeeConfig, Fee};
+
+use crate::accounts::PoolKeys;
+use crate::pda::*;
+
+fn instruction(
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+    remaining_accounts: &[AccountMeta],
+) -> Instruction {
+    let mut accounts = accounts.to_account_metas(None);
+    accounts.extend_from_slice(remaining_accounts);
+    Instruction {
+        program_id: exchange::ID,
+        accounts,
+        data: data.data(),
+    }
+}
+
+fn associated_token_address(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
+
+/// Creates the config, signed by the upgrade authority of the program
+pub struct InitializeConfigBuilder {
+    admin: Pubkey,
+}
+
+impl InitializeConfigBuilder {
+    pub fn new(admin: Pubkey) -> Self {
+        Self { admin }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        instruction(
+            exchange::accounts::InitializeConfig {
+                config: find_config_address().0,
+                admin: self.admin,
+                program: exchange::ID,
+                program_data: find_program_data_address().0,
+                system_program: system_program::ID,
+            },
+            exchange::instruction::InitializeConfig {},
+            &[],
+        )
+    }
+}
+
+pub struct AddFeeTierBuilder {
+    admin: Pubkey,
+    fees: Fee,
+    dynamic_fee: Option<DynamicFeeConfig>,
+}
+
+impl AddFeeTierBuilder {
+    pub fn new(admin: Pubkey, fees: Fee) -> Self {
+        Self {
+            admin,
+            fees,
+            dynamic_fee: None,
+        }
+    }
+
+    /// Scales the trade fee of the tier's pools with their volatility
+    pub fn dynamic_fee(mut self, dynamic_fee: DynamicFeeConfig) -> Self {
+        self.dynamic_fee = Some(dynamic_fee);
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        instruction(
+            exchange::accounts::AddFeeTier {
+                config: find_config_address().0,
+                admin: self.admin,
+            },
+            exchange::instruction::AddFeeTier {
+                fees: self.fees,
+                dynamic_fee: self.dynamic_fee,
+            },
+            &[],
+        )
+    }
+}
+
+pub struct DisableFeeTierBuilder {
+    admin: Pubkey,
+    fee_tier: u16,
+}
+
+impl DisableFeeTierBuilder {
+    pub fn new(admin: Pubkey, fee_tier: u16) -> Self {
+        Self { admin, fee_tier }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        instruction(
+            exchange::accounts::DisableFeeTier {
+                config: find_config_address().0,
+                admin: self.admin,
+            },
+            exchange::instruction::DisableFeeTier {
+                fee_tier: self.fee_tier,
+            },
+            &[],
+        )
+    }
+}
+
+pub struct SetProtocolFeeBuilder {
+    admin: Pubkey,
+    protocol_fee_numerator: u64,
+    protocol_fee_denominator: u64,
+    treasury: Pubkey,
+}
+
+impl SetProtocolFeeBuilder {
+    pub fn new(
+        admin: Pubkey,
+        protocol_fee_numerator: u64,
+        protocol_fee_denominator: u64,
+        treasury: Pubkey,
+    ) -> Self {
+        Self {
+            admin,
+            protocol_fee_numerator,
+            protocol_fee_denominator,
+            treasury,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        instruction(
+            exchange::accounts::SetProtocolFee {
+                config: find_config_address().0,
+                admin: self.admin,
+            },
+            exchange::instruction::SetProtocolFee {
+                protocol_fee_numerator: self.protocol_fee_numerator,
+                protocol_fee_denominator: self.protocol_fee_denominator,
+                treasury: self.treasury,
+            },
+            &[],
+        )
+    }
+}
+
+pub struct SetHostFeeBuilder {
+    admin: Pubkey,
+    host_fee_numerator: u64,
+    host_fee_denominator: u64,
+}
+
+impl SetHostFeeBuilder {
+    pub fn new(admin: Pubkey, host_fee_numerator: u64, host_fee_denominator: u64) -> Self {
+        Self {
+            admin,
+            host_fee_numerator,
+            host_fee_denominator,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        instruction(
+            exchange::accounts::SetHostFee {
+                config: find_config_address().0,
+                admin: self.admin,
+            },
+            exchange::instruction::SetHostFee {
+                host_fee_numerator: self.host_fee_numerator,
+                host_fee_denominator: self.host_fee_denominator,
+            },
+            &[],
+        )
+    }
+}
+
+/// Removes a pool from the registry it was created in, signed by the admin
+pub struct UnregisterPoolBuilder {
+    admin: Pubkey,
+    registry: Pubkey,
+    pool: Pubkey,
+}
+
+impl UnregisterPoolBuilder {
+    pub fn new(admin: Pubkey, registry: Pubkey, pool: Pubkey) -> Self {
+        Self {
+            admin,
+            registry,
+            pool,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        instruction(
+            exchange::accounts::UnregisterPool {
+                config: find_config_address().0,
+                registry: self.registry,
+                admin: self.admin,
+            },
+            exchange::instruction::UnregisterPool { pool: self.pool },
+            &[],
+        )
+    }
+}
+
+/// Sends the protocol fees of a pool to the treasury, by default to its associated token accounts
+pub struct CollectProtocolFeesBuilder<'a> {
+    keys: &'a PoolKeys,
+    treasury: Pubkey,
+    treasury_token_a_account: Option<Pubkey>,
+    treasury_token_b_account: Option<Pubkey>,
+    remaining_accounts: Vec<AccountMeta>,
+}
+
+impl<'a> CollectProtocolFeesBuilder<'a> {
+    pub fn new(keys: &'a PoolKeys, treasury: Pubkey) -> Self {
+        Self {
+            keys,
+            treasury,
+            treasury_token_a_account: None,
+            treasury_token_b_account: None,
+            remaining_accounts: vec![],
+        }
+    }
+
+    pub fn treasury_token_a_account(mut self, account: Pubkey) -> Self {
+        self.treasury_token_a_account = Some(account);
+        self
+    }
+
+    pub fn treasury_token_b_account(mut self, account: Pubkey) -> Self {
+        self.treasury_token_b_account = Some(account);
+        self
+    }
+
+    /// Extra accounts of the mints' transfer hooks
+    pub fn remaining_accounts(mut self, accounts: Vec<AccountMeta>) -> Self {
+        self.remaining_accounts = accounts;
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let keys = self.keys;
+        instruction(
+            exchange::accounts::CollectProtocolFees {
+                config: find_config_address().0,
+                pool_authority: keys.authority,
+                pool: keys.pool,
+                pool_token_a_account: keys.token_a,
+                pool_token_b_account: keys.token_b,
+                token_a_mint: keys.token_a_mint,
+                token_b_mint: keys.token_b_mint,
+                treasury_token_a_account: self.treasury_token_a_account.unwrap_or_else(|| {
+                    associated_token_address(
+                        &self.treasury,
+                        &keys.token_a_mint,
+                        &keys.token_a_program,
+                    )
+                }),
+                treasury_token_b_account: self.treasury_token_b_account.unwrap_or_else(|| {
+                    associated_token_address(
+                        &self.treasury,
+                        &keys.token_b_mint,
+                        &keys.token_b_program,
+                    )
+                }),
+                treasury: self.treasury,
+                token_a_program: keys.token_a_program,
+                token_b_program: keys.token_b_program,
+            },
+            exchange::instruction::CollectProtocolFees {},
+            &self.remaining_accounts,
+        )
+    }
+}
+
+/// Creates a pool of a sorted mint pair, funded from the creator's associated token accounts
+pub struct InitializePoolBuilder {
+    creator: Pubkey,
+    token_a_mint: Pubkey,
+    token_a_program: Pubkey,
+    token_b_mint: Pubkey,
+    token_b_program: Pubkey,
+    initial_token_a_amount: u64,
+    initial_token_b_amount: u64,
+    fee_tier: u16,
+    transfer_hook_programs: Vec<Pubkey>,
+    creator_token_a: Option<Pubkey>,
+    creator_token_b: Option<Pubkey>,
+    remaining_accounts: Vec<AccountMeta>,
+}
+
+impl InitializePoolBuilder {
+    pub fn new(
+        creator: Pubkey,
+        token_a_mint: Pubkey,
+        token_a_program: Pubkey,
+        token_b_mint: Pubkey,
+        token_b_program: Pubkey,
+        initial_token_a_amount: u64,
+        initial_token_b_amount: u64,
+    ) -> Self {
+        Self {
+            creator,
+            token_a_mint,
+            token_a_program,
+            token_b_mint,
+            token_b_program,
+            initial_token_a_amount,
+            initial_token_b_amount,
+            fee_tier: 0,
+            transfer_hook_programs: vec![],
+            creator_token_a: None,
+            creator_token_b: None,
+            remaining_accounts: vec![],
+        }
+    }
+
+    pub fn fee_tier(mut self, fee_tier: u16) -> Self {
+        self.fee_tier = fee_tier;
+        self
+    }
+
+    /// Transfer hook programs the pool's mints may invoke
+    pub fn transfer_hook_programs(mut self, programs: Vec<Pubkey>) -> Self {
+        self.transfer_hook_programs = programs;
+        self
+    }
+
+    pub fn creator_token_a(mut self, account: Pubkey) -> Self {
+        self.creator_token_a = Some(account);
+        self
+    }
+
+    pub fn creator_token_b(mut self, account: Pubkey) -> Self {
+        self.creator_token_b = Some(account);
+        self
+    }
+
+    /// Extra accounts of the mints' transfer hooks
+    pub fn remaining_accounts(mut self, accounts: Vec<AccountMeta>) -> Self {
+        self.remaining_accounts = accounts;
+        self
+    }
+
+    /// Address of the pool the instruction creates
+    pub fn pool(&self) -> Pubkey {
+        find_pool_address(&self.token_a_mint, &self.token_b_mint, &self.creator).0
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let pool = self.pool();
+        let (pool_mint, _) = find_pool_mint_address(&pool);
+        instruction(
+            exchange::accounts::InitializePool {
+                pool,
+                registry: find_registry_address(
+                    &self.token_a_mint,
+                    &self.token_b_mint,
+                    self.fee_tier,
+                )
+                .0,
+                config: find_config_address().0,
+                pool_authority: find_pool_authority_address(&pool).0,
+                token_a: find_vault_address(&pool, &self.token_a_mint).0,
+                token_b: find_vault_address(&pool, &self.token_b_mint).0,
+                token_a_mint: self.token_a_mint,
+                token_b_mint: self.token_b_mint,
+                creator_token_a: self.creator_token_a.unwrap_or_else(|| {
+                    associated_token_address(
+                        &self.creator,
+                        &self.token_a_mint,
+                        &self.token_a_program,
+                    )
+                }),
+                creator_token_b: self.creator_token_b.unwrap_or_else(|| {
+                    associated_token_address(
+                        &self.creator,
+                        &self.token_b_mint,
+                        &self.token_b_program,
+                    )
+                }),
+                pool_mint,
+                user_pool_token_receipt: associated_token_address(
+                    &self.creator,
+                    &pool_mint,
+                    &anchor_spl::token_2022::ID,
+                ),
+                pool_fee_account: get_pool_fee_account_address(&pool),
+                locked_liquidity: find_locked_liquidity_address(&pool).0,
+                creator: self.creator,
+                system_program: system_program::ID,
+                token_program: anchor_spl::token_2022::ID,
+                token_a_program: self.token_a_program,
+                token_b_program: self.token_b_program,
+                associated_token_program: associated_token::ID,
+            },
+            exchange::instruction::Initialize {
+                fee_tier: self.fee_tier,
+                transfer_hook_programs: self.transfer_hook_programs.clone(),
+                initial_token_a_amount: self.initial_token_a_amount,
+                initial_token_b_amount: self.initial_token_b_amount,
+            },
+            &self.remaining_accounts,
+        )
+    }
+}
+
+/// Rewrites a pool of an older layout in the current one, the payer covering any extra rent
+pub struct MigratePoolBuilder {
+    pool: Pubkey,
+    payer: Pubkey,
+}
+
+impl MigratePoolBuilder {
+    pub fn new(pool: Pubkey, payer: Pubkey) -> Self {
+        Self { pool, payer }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        instruction(
+            exchange::accounts::MigratePool {
+                pool: self.pool,
+                payer: self.payer,
+                system_program: system_program::ID,
+            },
+            exchange::instruction::MigratePool {},
+            &[],
+        )
+    }
+}
+
+/// Closes an empty pool, signed by the admin or the creator. The tokens left in the
+/// vaults go by default to the receiver's associated token accounts, every rent to the receiver
+pub struct ClosePoolBuilder<'a> {
+    keys: &'a PoolKeys,
+    fee_tier: u16,
+    authority: Pubkey,
+    receiver: Pubkey,
+    receiver_token_a_account: Option<Pubkey>,
+    receiver_token_b_account: Option<Pubkey>,
+    pool_mint_program: Pubkey,
+    registered: bool,
+    locked_liquidity: bool,
+    remaining_accounts: Vec<AccountMeta>,
+}
+
+impl<'a> ClosePoolBuilder<'a> {
+    pub fn new(keys: &'a PoolKeys, fee_tier: u16, authority: Pubkey, receiver: Pubkey) -> Self {
+        Self {
+            keys,
+            fee_tier,
+            authority,
+            receiver,
+            receiver_token_a_account: None,
+            receiver_token_b_account: None,
+            pool_mint_program: anchor_spl::token_2022::ID,
+            registered: true,
+            locked_liquidity: true,
+            remaining_accounts: vec![],
+        }
+    }
+
+    /// Owner of the pool mint, SPL Token for pools of the first deployment
+    pub fn pool_mint_program(mut self, program: Pubkey) -> Self {
+        self.pool_mint_program = program;
+        self
+    }
+
+    /// Leaves out the registry, for pools created before registries
+    pub fn without_registry(mut self) -> Self {
+        self.registered = false;
+        self
+    }
+
+    /// Leaves out the locked liquidity, for pools created before it was locked
+    pub fn without_locked_liquidity(mut self) -> Self {
+        self.locked_liquidity = false;
+        self
+    }
+
+    pub fn receiver_token_a_account(mut self, account: Pubkey) -> Self {
+        self.receiver_token_a_account = Some(account);
+        self
+    }
+
+    pub fn receiver_token_b_account(mut self, account: Pubkey) -> Self {
+        self.receiver_token_b_account = Some(account);
+        self
+    }
+
+    /// Extra accounts of the mints' transfer hooks
+    pub fn remaining_accounts(mut self, accounts: Vec<AccountMeta>) -> Self {
+        self.remaining_accounts = accounts;
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let keys = self.keys;
+        instruction(
+            exchange::accounts::ClosePool {
+                config: find_config_address().0,
+                pool_authority: keys.authority,
+                pool: keys.pool,
+                registry: self.registered.then(|| {
+                    find_registry_address(&keys.token_a_mint, &keys.token_b_mint, self.fee_tier).0
+                }),
+                pool_token_a_account: keys.token_a,
+                pool_token_b_account: keys.token_b,
+                token_a_mint: keys.token_a_mint,
+                token_b_mint: keys.token_b_mint,
+                pool_mint: keys.mint,
+                pool_token_fee_account: keys.fee_account,
+                locked_liquidity: self
+                    .locked_liquidity
+                    .then(|| find_locked_liquidity_address(&keys.pool).0),
+                receiver_token_a_account: self.receiver_token_a_account.unwrap_or_else(|| {
+                    associated_token_address(
+                        &self.receiver,
+                        &keys.token_a_mint,
+                        &keys.token_a_program,
+                    )
+                }),
+                receiver_token_b_account: self.receiver_token_b_account.unwrap_or_else(|| {
+                    associated_token_address(
+                        &self.receiver,
+                        &keys.token_b_mint,
+                        &keys.token_b_program,
+                    )
+                }),
+                receiver: self.receiver,
+                authority: self.authority,
+                token_program: self.pool_mint_program,
+                token_a_program: keys.token_a_program,
+                token_b_program: keys.token_b_program,
+            },
+            exchange::instruction::ClosePool {},
+            &self.remaining_accounts,
+        )
+    }
+}
+
+/// Swaps token A for B, or B for A, between the user's associated token accounts
+pub struct SwapBuilder<'a> {
+    keys: &'a PoolKeys,
+    user: Pubkey,
+    a_to_b: bool,
+    source_amount: u64,
+    minimum_amount_out: u64,
+    user_source_token_account: Option<Pubkey>,
+    user_destination_token_account: Option<Pubkey>,
+    host_fee_account: Option<Pubkey>,
+    remaining_accounts: Vec<AccountMeta>,
+}
+
+impl<'a> SwapBuilder<'a> {
+    pub fn new(keys: &'a PoolKeys, user: Pubkey, a_to_b: bool, source_amount: u64) -> Self {
+        Self {
+            keys,
+            user,
+            a_to_b,
+            source_amount,
+            minimum_amount_out: 0,
+            user_source_token_account: None,
+            user_destination_token_account: None,
+            host_fee_account: None,
+            remaining_accounts: vec![],
+        }
+    }
+
+    /// Fails the swap when less is received, after the destination transfer fee
+    pub fn minimum_amount_out(mut self, amount: u64) -> Self {
+        self.minimum_amount_out = amount;
+        self
+    }
+
+    pub fn user_source_token_account(mut self, account: Pubkey) -> Self {
+        self.user_source_token_account = Some(account);
+        self
+    }
+
+    pub fn user_destination_token_account(mut self, account: Pubkey) -> Self {
+        self.user_destination_token_account = Some(account);
+        self
+    }
+
+    /// Pool token account of the host receiving its share of the owner fee
+    pub fn host_fee_account(mut self, account: Pubkey) -> Self {
+        self.host_fee_account = Some(account);
+        self
+    }
+
+    /// Extra accounts of the mints' transfer hooks
+    pub fn remaining_accounts(mut self, accounts: Vec<AccountMeta>) -> Self {
+        self.remaining_accounts = accounts;
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let keys = self.keys;
+        let (source, source_mint, source_program) = keys.side(self.a_to_b);
+        let (destination, destination_mint, destination_program) = keys.side(!self.a_to_b);
+        instruction(
+            exchange::accounts::Swap {
+                pool_authority: keys.authority,
+                pool: keys.pool,
+                config: find_config_address().0,
+                pool_source_token_account: source,
+                pool_destination_token_account: destination,
+                source_mint,
+                destination_mint,
+                user_source_token_account: self.user_source_token_account.unwrap_or_else(|| {
+                    associated_token_address(&self.user, &source_mint, &source_program)
+                }),
+                user_destination_token_account: self.user_destination_token_account.unwrap_or_else(
+                    || {
+                        associated_token_address(
+                            &self.user,
+                            &destination_mint,
+                            &destination_program,
+                        )
+                    },
+                ),
+                pool_mint: keys.mint,
+                pool_token_fee_account: keys.fee_account,
+                host_fee_account: self.host_fee_account,
+                user: self.user,
+                creator: keys.creator,
+                system_program: system_program::ID,
+                token_program: anchor_spl::token_2022::ID,
+                source_token_program: source_program,
+                destination_token_program: destination_program,
+            },
+            exchange::instruction::Swap {
+                source_amount: self.source_amount,
+                minimum_amount_out: self.minimum_amount_out,
+            },
+            &self.remaining_accounts,
+        )
+    }
+}
+
+/// Deposits both tokens for `pool_tokens`, bounded by the maximum amounts
+pub struct DepositAllTokensBuilder<'a> {
+    keys: &'a PoolKeys,
+    user: Pubkey,
+    pool_tokens: u64,
+    max_token_a: u64,
+    max_token_b: u64,
+    remaining_accounts: Vec<AccountMeta>,
+}
+
+impl<'a> DepositAllTokensBuilder<'a> {
+    pub fn new(
+        keys: &'a PoolKeys,
+        user: Pubkey,
+        pool_tokens: u64,
+        max_token_a: u64,
+        max_token_b: u64,
+    ) -> Self {
+        Self {
+            keys,
+            user,
+            pool_tokens,
+            max_token_a,
+            max_token_b,
+            remaining_accounts: vec![],
+        }
+    }
+
+    /// Extra accounts of the mints' transfer hooks
+    pub fn remaining_accounts(mut self, accounts: Vec<AccountMeta>) -> Self {
+        self.remaining_accounts = accounts;
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let keys = self.keys;
+        instruction(
+            exchange::accounts::DepositAllTokens {
+                pool_authority: keys.authority,
+                pool: keys.pool,
+                pool_token_a_account: keys.token_a,
+                pool_token_b_account: keys.token_b,
+                token_a_mint: keys.token_a_mint,
+                token_b_mint: keys.token_b_mint,
+                user_token_a_account: associated_token_address(
+                    &self.user,
+                    &keys.token_a_mint,
+                    &keys.token_a_program,
+                ),
+                user_token_b_account: associated_token_address(
+                    &self.user,
+                    &keys.token_b_mint,
+                    &keys.token_b_program,
+                ),
+                user_pool_token_receipt: associated_token_address(
+                    &self.user,
+                    &keys.mint,
+                    &anchor_spl::token_2022::ID,
+                ),
+                pool_mint: keys.mint,
+                pool_token_fee_account: keys.fee_account,
+                user: self.user,
+                creator: keys.creator,
+                system_program: system_program::ID,
+                token_program: anchor_spl::token_2022::ID,
+                token_a_program: keys.token_a_program,
+                token_b_program: keys.token_b_program,
+            },
+            exchange::instruction::DepositAllTokensIn {
+                pool_tokens: self.pool_tokens,
+                max_token_a: self.max_token_a,
+                max_token_b: self.max_token_b,
+            },
+            &self.remaining_accounts,
+        )
+    }
+}
+
+/// Deposits one side of the pool for pool tokens
+pub struct DepositSingleTokenBuilder<'a> {
+    keys: &'a PoolKeys,
+    user: Pubkey,
+    token_a: bool,
+    source_amount: u64,
+    minimum_pool_tokens_out: u64,
+    remaining_accounts: Vec<AccountMeta>,
+}
+
+impl<'a> DepositSingleTokenBuilder<'a> {
+    pub fn new(keys: &'a PoolKeys, user: Pubkey, token_a: bool, source_amount: u64) -> Self {
+        Self {
+            keys,
+            user,
+            token_a,
+            source_amount,
+            minimum_pool_tokens_out: 0,
+            remaining_accounts: vec![],
+        }
+    }
+
+    /// Fails the deposit when fewer pool tokens are minted
+    pub fn minimum_pool_tokens_out(mut self, amount: u64) -> Self {
+        self.minimum_pool_tokens_out = amount;
+        self
+    }
+
+    /// Extra accounts of the mint's transfer hook
+    pub fn remaining_accounts(mut self, accounts: Vec<AccountMeta>) -> Self {
+        self.remaining_accounts = accounts;
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let keys = self.keys;
+        let (_, source_mint, source_program) = keys.side(self.token_a);
+        instruction(
+            exchange::accounts::DepositSingleToken {
+                pool_authority: keys.authority,
+                pool: keys.pool,
+                pool_token_a_account: keys.token_a,
+                pool_token_b_account: keys.token_b,
+                user_source_token_account: associated_token_address(
+                    &self.user,
+                    &source_mint,
+                    &source_program,
+                ),
+                source_mint,
+                user_pool_token_receipt: associated_token_address(
+                    &self.user,
+                    &keys.mint,
+                    &anchor_spl::token_2022::ID,
+                ),
+                pool_mint: keys.mint,
+                user: self.user,
+                system_program: system_program::ID,
+                token_program: anchor_spl::token_2022::ID,
+                source_token_program: source_program,
+                config: find_config_address().0,
+            },
+            exchange::instruction::DepositSingleToken {
+                source_amount: self.source_amount,
+                minimum_pool_tokens_out: self.minimum_pool_tokens_out,
+            },
+            &self.remaining_accounts,
+        )
+    }
+}
+
+/// Withdraws `source_amount` of one side of the pool for pool tokens
+pub struct WithdrawSingleTokenBuilder<'a> {
+    keys: &'a PoolKeys,
+    user: Pubkey,
+    token_a: bool,
+    source_amount: u64,
+    maximum_pool_tokens_burned: u64,
+    remaining_accounts: Vec<AccountMeta>,
+}
+
+impl<'a> WithdrawSingleTokenBuilder<'a> {
+    pub fn new(keys: &'a PoolKeys, user: Pubkey, token_a: bool, source_amount: u64) -> Self {
+        Self {
+            keys,
+            user,
+            token_a,
+            source_amount,
+            maximum_pool_tokens_burned: u64::MAX,
+            remaining_accounts: vec![],
+        }
+    }
+
+    /// Fails the withdrawal when more pool tokens are burned
+    pub fn maximum_pool_tokens_burned(mut self, amount: u64) -> Self {
+        self.maximum_pool_tokens_burned = amount;
+        self
+    }
+
+    /// Extra accounts of the mint's transfer hook
+    pub fn remaining_accounts(mut self, accounts: Vec<AccountMeta>) -> Self {
+        self.remaining_accounts = accounts;
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let keys = self.keys;
+        let (_, source_mint, source_program) = keys.side(self.token_a);
+        instruction(
+            exchange::accounts::WithdrawSingleToken {
+                pool_authority: keys.authority,
+                pool: keys.pool,
+                pool_token_a_account: keys.token_a,
+                pool_token_b_account: keys.token_b,
+                user_source_token_account: associated_token_address(
+                    &self.user,
+                    &source_mint,
+                    &source_program,
+                ),
+                source_mint,
+                user_pool_token_receipt: associated_token_address(
+                    &self.user,
+                    &keys.mint,
+                    &anchor_spl::token_2022::ID,
+                ),
+                pool_mint: keys.mint,
+                pool_token_fee_account: keys.fee_account,
+                user: self.user,
+                system_program: system_program::ID,
+                token_program: anchor_spl::token_2022::ID,
+                source_token_program: source_program,
+                config: find_config_address().0,
+            },
+            exchange::instruction::WithdrawSingleTokenOut {
+                source_amount: self.source_amount,
+                maximum_pool_tokens_burned: self.maximum_pool_tokens_burned,
+            },
+            &self.remaining_accounts,
+        )
+    }
+}
+
+/// Returns the deepest pool of a registry through the return data. Every
+/// registered pool must be added, in the order of [`PoolRegistry::pools`]
+///
+/// [`PoolRegistry::pools`]: exchange::state::PoolRegistry::pools
+pub struct FindBestPoolBuilder {
+    registry: Pubkey,
+    pools: Vec<AccountMeta>,
+}
+
+impl FindBestPoolBuilder {
+    pub fn new(registry: Pubkey) -> Self {
+        Self {
+            registry,
+            pools: vec![],
+        }
+    }
+
+    pub fn pool(mut self, keys: &PoolKeys) -> Self {
+        self.pools.extend([
+            AccountMeta::new_readonly(keys.pool, false),
+            AccountMeta::new_readonly(keys.token_a, false),
+            AccountMeta::new_readonly(keys.token_b, false),
+        ]);
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        instruction(
+            exchange::accounts::FindBestPool {
+                registry: self.registry,
+            },
+            exchange::instruction::FindBestPool {},
+            &self.pools,
+        )
+    }
+}
+
+/// Returns what a swap would do through the return data, see [`crate::deserialize_swap_quote`]
+pub struct QuoteSwapBuilder<'a> {
+    keys: &'a PoolKeys,
+    a_to_b: bool,
+    source_amount: u64,
+}
+
+impl<'a> QuoteSwapBuilder<'a> {
+    pub fn new(keys: &'a PoolKeys, a_to_b: bool, source_amount: u64) -> Self {
+        Self {
+            keys,
+            a_to_b,
+            source_amount,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let keys = self.keys;
+        let (source, source_mint, _) = keys.side(self.a_to_b);
+        let (destination, destination_mint, _) = keys.side(!self.a_to_b);
+        instruction(
+            exchange::accounts::QuoteSwap {
+                pool: keys.pool,
+                config: find_config_address().0,
+                pool_source_token_account: source,
+                pool_destination_token_account: destination,
+                source_mint,
+                destination_mint,
+            },
+            exchange::instruction::QuoteSwap {
+                source_amount: self.source_amount,
+            },
+            &[],
+        )
+    }
+}
+
+/// Returns the pool tokens of a single token deposit, or of a withdrawal when built with
+/// [`QuoteSingleTokenBuilder::withdraw`], see [`crate::deserialize_liquidity_quote`]
+pub struct QuoteSingleTokenBuilder<'a> {
+    keys: &'a PoolKeys,
+    token_a: bool,
+    source_amount: u64,
+    withdraw: bool,
+}
+
+impl<'a> QuoteSingleTokenBuilder<'a> {
+    pub fn deposit(keys: &'a PoolKeys, token_a: bool, source_amount: u64) -> Self {
+        Self {
+            keys,
+            token_a,
+            source_amount,
+            withdraw: false,
+        }
+    }
+
+    pub fn withdraw(keys: &'a PoolKeys, token_a: bool, source_amount: u64) -> Self {
+        Self {
+            keys,
+            token_a,
+            source_amount,
+            withdraw: true,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let keys = self.keys;
+        let (_, source_mint, _) = keys.side(self.token_a);
+        if self.withdraw {
+            instruction(
+                exchange::accounts::QuoteWithdrawSingle {
+                    pool: keys.pool,
+                    pool_token_a_account: keys.token_a,
+                    pool_token_b_account: keys.token_b,
+                    source_mint,
+                    pool_mint: keys.mint,
+                    config: find_config_address().0,
+                },
+                exchange::instruction::QuoteWithdrawSingle {
+                    source_amount: self.source_amount,
+                },
+                &[],
+            )
+        } else {
+            instruction(
+                exchange::accounts::QuoteDepositSingle {
+                    pool: keys.pool,
+                    pool_token_a_account: keys.token_a,
+                    pool_token_b_account: keys.token_b,
+                    source_mint,
+                    pool_mint: keys.mint,
+                    config: find_config_address().0,
+                },
+                exchange::instruction::QuoteDepositSingle {
+                    source_amount: self.source_amount,
+                },
+                &[],
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::pubkey::Pubkey;
+
+    fn keys() -> PoolKeys {
+        let pool = Pubkey::new_unique();
+        PoolKeys {
+            pool,
+            authority: find_pool_authority_address(&pool).0,
+            creator: Pubkey::new_unique(),
+            token_a: Pubkey::new_unique(),
+            token_b: Pubkey::new_unique(),
+            token_a_mint: Pubkey::new_unique(),
+            token_b_mint: Pubkey::new_unique(),
+            mint: find_pool_mint_address(&pool).0,
+            fee_account: get_pool_fee_account_address(&pool),
+            token_a_program: anchor_spl::token::ID,
+            token_b_program: anchor_spl::token_2022::ID,
+        }
+    }
+
+    #[test]
+    fn swap_flips_the_sides() {
+        let keys = keys();
+        let user = Pubkey::new_unique();
+
+        let a_to_b = SwapBuilder::new(&keys, user, true, 100).instruction();
+        let b_to_a = SwapBuilder::new(&keys, user, false, 100).instruction();
+        // pool source and destination vaults follow the authority, pool and config
+        assert_eq!(a_to_b.accounts[3].pubkey, keys.token_a);
+        assert_eq!(a_to_b.accounts[4].pubkey, keys.token_b);
+        assert_eq!(b_to_a.accounts[3].pubkey, keys.token_b);
+        assert_eq!(b_to_a.accounts[4].pubkey, keys.token_a);
+        assert_eq!(
+            a_to_b.accounts[7].pubkey,
+            associated_token_address(&user, &keys.token_a_mint, &anchor_spl::token::ID)
+        );
+        assert_eq!(
+            b_to_a.accounts[7].pubkey,
+            associated_token_address(&user, &keys.token_b_mint, &anchor_spl::token_2022::ID)
+        );
+    }
+
+    #[test]
+    fn swap_without_host_fee_account_passes_the_program_id() {
+        let keys = keys();
+        let host = Pubkey::new_unique();
+
+        let swap = SwapBuilder::new(&keys, Pubkey::new_unique(), true, 100).instruction();
+        assert_eq!(swap.accounts[11].pubkey, exchange::ID);
+
+        let swap = SwapBuilder::new(&keys, Pubkey::new_unique(), true, 100)
+            .host_fee_account(host)
+            .instruction();
+        assert_eq!(swap.accounts[11].pubkey, host);
+    }
+
+    #[test]
+    fn find_best_pool_appends_the_pool_triples() {
+        let (first, second) = (keys(), keys());
+        let find_best_pool = FindBestPoolBuilder::new(Pubkey::new_unique())
+            .pool(&first)
+            .pool(&second)
+            .instruction();
+
+        let remaining: Vec<_> = find_best_pool.accounts[1..]
+            .iter()
+            .map(|account| account.pubkey)
+            .collect();
+        assert_eq!(
+            remaining,
+            [
+                first.pool,
+                first.token_a,
+                first.token_b,
+                second.pool,
+                second.token_a,
+                second.token_b
+            ]
+        );
+    }
+
+    #[test]
+    fn close_pool_sends_the_vaults_to_the_receiver() {
+        let keys = keys();
+        let (admin, receiver) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let close_pool = ClosePoolBuilder::new(&keys, 2, admin, receiver).instruction();
+
+        let account = |pubkey: Pubkey| {
+            close_pool
+                .accounts
+                .iter()
+                .find(|account| account.pubkey == pubkey)
+                .unwrap()
+        };
+        assert!(account(keys.pool).is_writable);
+        assert!(account(receiver).is_writable);
+        assert!(account(admin).is_signer);
+        assert!(
+            account(find_registry_address(&keys.token_a_mint, &keys.token_b_mint, 2).0).is_writable
+        );
+        assert!(
+            account(associated_token_address(
+                &receiver,
+                &keys.token_a_mint,
+                &anchor_spl::token::ID
+            ))
+            .is_writable
+        );
+        assert!(
+            account(associated_token_address(
+                &receiver,
+                &keys.token_b_mint,
+                &anchor_spl::token_2022::ID
+            ))
+            .is_writable
+        );
+    }
+
+    #[test]
+    fn close_baseline_pool_passes_the_program_id_for_missing_accounts() {
+        let keys = keys();
+        let (creator, receiver) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let close_pool = ClosePoolBuilder::new(&keys, 0, creator, receiver)
+            .pool_mint_program(anchor_spl::token::ID)
+            .without_registry()
+            .without_locked_liquidity()
+            .instruction();
+        // the registry follows the pool, the locked liquidity the fee account
+        assert_eq!(close_pool.accounts[3].pubkey, exchange::ID);
+        assert_eq!(close_pool.accounts[10].pubkey, exchange::ID);
+        assert_eq!(close_pool.accounts[15].pubkey, anchor_spl::token::ID);
+    }
+
+    #[test]
+    fn quote_instructions_are_read_only() {
+        let keys = keys();
+        let instructions = [
+            QuoteSwapBuilder::new(&keys, true, 100).instruction(),
+            QuoteSingleTokenBuilder::deposit(&keys, true, 100).instruction(),
+            QuoteSingleTokenBuilder::withdraw(&keys, false, 100).instruction(),
+        ];
+
+        for instruction in instructions {
+            assert!(instruction
+                .accounts
+                .iter()
+                .all(|account| !account.is_writable && !account.is_signer));
+        }
+    }
+}
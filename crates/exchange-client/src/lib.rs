@@ -0,0 +1,20 @@
+//! Off-chain client for the exchange program.
+//!
+//! Builds every instruction from typed builders, derives the program
+//! addresses and fetches pool state through any [`AccountFetcher`].
+
+pub mod accounts;
+pub mod error;
+pub mod instructions;
+pub mod pda;
+pub mod rpc;
+
+pub use accounts::*;
+pub use error::*;
+pub use instructions::*;
+pub use pda::*;
+pub use rpc::*;
+
+pub use exchange::state::{Config, DynamicFeeConfig, Fee, Pool, PoolRegistry};
+pub use exchange::ID as PROGRAM_ID;
+pub use exchange_math::quote;
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use exchange::constants::{
+    AUTHORITY, CONFIG, LOCKED_LIQUIDITY, POOL_MINT, PREFIX, REGISTRY, VAULT,
+};
+
+pub fn find_config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG], &exchange::ID)
+}
+
+/// Program data account of the upgradeable program, whose authority initializes the config
+pub fn find_program_data_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[exchange::ID.as_ref()], &bpf_loader_upgradeable::id())
+}
+
+/// Pool of the sorted mint pair created by `creator`
+pub fn find_pool_address(
+    token_a_mint: &Pubkey,
+    token_b_mint: &Pubkey,
+    creator: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX,
+            token_a_mint.as_ref(),
+            token_b_mint.as_ref(),
+            creator.as_ref(),
+        ],
+        &exchange::ID,
+    )
+}
+
+/// Authority owning the vaults and the pool mint
+pub fn find_pool_authority_address(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PREFIX, pool.as_ref(), AUTHORITY], &exchange::ID)
+}
+
+pub fn find_pool_mint_address(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PREFIX, pool.as_ref(), POOL_MINT], &exchange::ID)
+}
+
+/// Vault of the pool holding `mint`
+pub fn find_vault_address(pool: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PREFIX, pool.as_ref(), VAULT, mint.as_ref()],
+        &exchange::ID,
+    )
+}
+
+/// Pool token account holding the minimum liquidity locked on initialize
+pub fn find_locked_liquidity_address(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PREFIX, pool.as_ref(), LOCKED_LIQUIDITY], &exchange::ID)
+}
+
+pub fn find_registry_address(
+    token_a_mint: &Pubkey,
+    token_b_mint: &Pubkey,
+    fee_tier: u16,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            REGISTRY,
+            token_a_mint.as_ref(),
+            token_b_mint.as_ref(),
+            &fee_tier.to_le_bytes(),
+        ],
+        &exchange::ID,
+    )
+}
+
+/// Associated Token-2022 account of the pool authority receiving the owner fees
+pub fn get_pool_fee_account_address(pool: &Pubkey) -> Pubkey {
+    let (authority, _) = find_pool_authority_address(pool);
+    let (mint, _) = find_pool_mint_address(pool);
+    get_associated_token_address_with_program_id(&authority, &mint, &anchor_spl::token_2022::ID)
+}
+
+/// Canonical order of a mint pair, as pools and registries expect it
+pub fn sort_mints(mint: Pubkey, other_mint: Pubkey) -> (Pubkey, Pubkey) {
+    if mint <= other_mint {
+        (mint, other_mint)
+    } else {
+        (other_mint, mint)
+    }
+}
@@ -0,0 +1,260 @@
+use anchor_lang::prelude::Pubkey;
+use exchange::state::{Config, Pool, PoolRegistry};
+use solana_sdk::account::Account;
+
+use crate::accounts::*;
+use crate::error::{ClientError, Result};
+use crate::pda::{find_config_address, find_registry_address};
+
+/// Source of on-chain accounts, implemented over an RPC client or a local mock
+pub trait AccountFetcher {
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    fn get_account(&self, address: &Pubkey) -> std::result::Result<Option<Account>, Self::Error>;
+
+    fn get_multiple_accounts(
+        &self,
+        addresses: &[Pubkey],
+    ) -> std::result::Result<Vec<Option<Account>>, Self::Error> {
+        addresses
+            .iter()
+            .map(|address| self.get_account(address))
+            .collect()
+    }
+}
+
+#[cfg(feature = "rpc-client")]
+impl AccountFetcher for solana_rpc_client::rpc_client::RpcClient {
+    type Error = solana_rpc_client_api::client_error::Error;
+
+    fn get_account(&self, address: &Pubkey) -> std::result::Result<Option<Account>, Self::Error> {
+        Ok(self
+            .get_account_with_commitment(address, self.commitment())?
+            .value)
+    }
+
+    fn get_multiple_accounts(
+        &self,
+        addresses: &[Pubkey],
+    ) -> std::result::Result<Vec<Option<Account>>, Self::Error> {
+        solana_rpc_client::rpc_client::RpcClient::get_multiple_accounts(self, addresses)
+    }
+}
+
+/// Fetches and decodes the exchange accounts
+pub struct ExchangeClient<R> {
+    rpc: R,
+}
+
+impl<R: AccountFetcher> ExchangeClient<R> {
+    pub fn new(rpc: R) -> Self {
+        Self { rpc }
+    }
+
+    pub fn rpc(&self) -> &R {
+        &self.rpc
+    }
+
+    /// Account at `address`, failing when it doesn't exist
+    pub fn fetch_account(&self, address: &Pubkey) -> Result<Account> {
+        self.rpc
+            .get_account(address)
+            .map_err(|error| ClientError::Rpc(error.into()))?
+            .ok_or(ClientError::AccountNotFound(*address))
+    }
+
+    /// Account at `address` owned by the exchange program
+    fn fetch_program_account(&self, address: &Pubkey) -> Result<Account> {
+        let account = self.fetch_account(address)?;
+        if account.owner != exchange::ID {
+            return Err(ClientError::InvalidOwner(*address, account.owner));
+        }
+        Ok(account)
+    }
+
+    pub fn fetch_pool(&self, pool: &Pubkey) -> Result<Pool> {
+        deserialize_pool(&self.fetch_program_account(pool)?.data)
+    }
+
+    pub fn fetch_config(&self) -> Result<Config> {
+        deserialize_config(&self.fetch_program_account(&find_config_address().0)?.data)
+    }
+
+    pub fn fetch_registry(
+        &self,
+        token_a_mint: &Pubkey,
+        token_b_mint: &Pubkey,
+        fee_tier: u16,
+    ) -> Result<PoolRegistry> {
+        let (registry, _) = find_registry_address(token_a_mint, token_b_mint, fee_tier);
+        deserialize_registry(&self.fetch_program_account(&registry)?.data)
+    }
+
+    /// Pool state with the token programs read from the owners of its mints
+    pub fn fetch_pool_keys(&self, pool: &Pubkey) -> Result<PoolKeys> {
+        let state = self.fetch_pool(pool)?;
+        let accounts = self
+            .rpc
+            .get_multiple_accounts(&[state.token_a_mint, state.token_b_mint])
+            .map_err(|error| ClientError::Rpc(error.into()))?;
+
+        let token_program = |mint: &Pubkey, index: usize| {
+            accounts
+                .get(index)
+                .and_then(Option::as_ref)
+                .map(|account| account.owner)
+                .ok_or(ClientError::AccountNotFound(*mint))
+        };
+        let token_a_program = token_program(&state.token_a_mint, 0)?;
+        let token_b_program = token_program(&state.token_b_mint, 1)?;
+
+        Ok(PoolKeys::new(
+            *pool,
+            &state,
+            token_a_program,
+            token_b_program,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pda::{find_pool_address, find_pool_mint_address, find_vault_address};
+    use anchor_lang::Discriminator;
+    use exchange::state::{DynamicFee, Fee};
+    use std::collections::HashMap;
+    use std::convert::Infallible;
+
+    #[derive(Default)]
+    struct MockFetcher {
+        accounts: HashMap<Pubkey, Account>,
+    }
+
+    impl MockFetcher {
+        fn add(&mut self, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+            let account = Account {
+                lamports: 1,
+                data,
+                owner,
+                executable: false,
+                rent_epoch: 0,
+            };
+            self.accounts.insert(address, account);
+        }
+    }
+
+    impl AccountFetcher for MockFetcher {
+        type Error = Infallible;
+
+        fn get_account(
+            &self,
+            address: &Pubkey,
+        ) -> std::result::Result<Option<Account>, Infallible> {
+            Ok(self.accounts.get(address).cloned())
+        }
+    }
+
+    fn pool_state(token_a_mint: Pubkey, token_b_mint: Pubkey, creator: Pubkey) -> (Pubkey, Pool) {
+        let (pool, bump) = find_pool_address(&token_a_mint, &token_b_mint, &creator);
+        let state = Pool {
+            bump,
+            token_a: find_vault_address(&pool, &token_a_mint).0,
+            token_b: find_vault_address(&pool, &token_b_mint).0,
+            token_a_mint,
+            token_b_mint,
+            mint: find_pool_mint_address(&pool).0,
+            creator,
+            fee_account: crate::pda::get_pool_fee_account_address(&pool),
+            fees: Fee {
+                trade_fee_numerator: 30,
+                trade_fee_denominator: 10_000,
+                owner_trade_fee_numerator: 5,
+                owner_trade_fee_denominator: 10_000,
+                owner_withdraw_fee_numerator: 0,
+                owner_withdraw_fee_denomiator: 10_000,
+            },
+            fee_tier: 0,
+            protocol_fees_token_a: 0,
+            protocol_fees_token_b: 0,
+            dynamic_fee: DynamicFee::default(),
+            version: Pool::VERSION,
+            ..bytemuck::Zeroable::zeroed()
+        };
+        (pool, state)
+    }
+
+    fn serialize(state: &Pool) -> Vec<u8> {
+        let mut data = Pool::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(state));
+        data
+    }
+
+    #[test]
+    fn fetch_pool_keys_reads_the_token_programs_of_the_mints() {
+        let (token_a_mint, token_b_mint) =
+            crate::pda::sort_mints(Pubkey::new_unique(), Pubkey::new_unique());
+        let (pool, state) = pool_state(token_a_mint, token_b_mint, Pubkey::new_unique());
+
+        let mut rpc = MockFetcher::default();
+        rpc.add(pool, exchange::ID, serialize(&state));
+        rpc.add(token_a_mint, anchor_spl::token::ID, vec![]);
+        rpc.add(token_b_mint, anchor_spl::token_2022::ID, vec![]);
+
+        let client = ExchangeClient::new(rpc);
+        let fetched = client.fetch_pool(&pool).unwrap();
+        assert_eq!(fetched.fees.trade_fee_numerator, 30);
+
+        let keys = client.fetch_pool_keys(&pool).unwrap();
+        assert_eq!(keys.token_a, state.token_a);
+        assert_eq!(keys.mint, state.mint);
+        assert_eq!(keys.token_a_program, anchor_spl::token::ID);
+        assert_eq!(keys.token_b_program, anchor_spl::token_2022::ID);
+    }
+
+    #[test]
+    fn fetch_missing_or_foreign_accounts_fails() {
+        let (token_a_mint, token_b_mint) =
+            crate::pda::sort_mints(Pubkey::new_unique(), Pubkey::new_unique());
+        let (pool, state) = pool_state(token_a_mint, token_b_mint, Pubkey::new_unique());
+
+        let mut rpc = MockFetcher::default();
+        rpc.add(pool, exchange::ID, serialize(&state));
+        let foreign = Pubkey::new_unique();
+        rpc.add(foreign, anchor_spl::token::ID, serialize(&state));
+        let client = ExchangeClient::new(rpc);
+
+        assert!(matches!(
+            client.fetch_pool(&foreign),
+            Err(ClientError::InvalidOwner(address, _)) if address == foreign
+        ));
+        assert!(matches!(
+            client.fetch_config(),
+            Err(ClientError::AccountNotFound(_))
+        ));
+        // the mints were never added
+        assert!(matches!(
+            client.fetch_pool_keys(&pool),
+            Err(ClientError::AccountNotFound(mint)) if mint == token_a_mint
+        ));
+    }
+
+    #[test]
+    fn deserialize_rejects_other_accounts() {
+        let (_, state) = pool_state(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let data = serialize(&state);
+
+        assert!(deserialize_pool(&data).is_ok());
+        assert!(matches!(
+            deserialize_config(&data),
+            Err(ClientError::Deserialize(_))
+        ));
+        // the fees of the zero-copy pool are laid out as their Borsh encoding
+        let fee = deserialize_fee(&data[8 + std::mem::offset_of!(Pool, fees)..]).unwrap();
+        assert_eq!(fee.owner_trade_fee_numerator, 5);
+    }
+}
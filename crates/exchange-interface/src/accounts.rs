@@ -0,0 +1,207 @@
+//! Accounts of the instructions, in the order the program expects them.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::AccountMeta;
+
+/// An account of an instruction, or an optional one passed as the program id when missing
+trait CpiAccount<'info> {
+    fn account_meta(&self, is_writable: bool, is_signer: bool) -> AccountMeta;
+    fn account_info(&self) -> Option<AccountInfo<'info>>;
+}
+
+impl<'info> CpiAccount<'info> for AccountInfo<'info> {
+    fn account_meta(&self, is_writable: bool, is_signer: bool) -> AccountMeta {
+        AccountMeta {
+            pubkey: *self.key,
+            is_signer,
+            is_writable,
+        }
+    }
+
+    fn account_info(&self) -> Option<AccountInfo<'info>> {
+        Some(self.clone())
+    }
+}
+
+impl<'info> CpiAccount<'info> for Option<AccountInfo<'info>> {
+    fn account_meta(&self, is_writable: bool, is_signer: bool) -> AccountMeta {
+        match self {
+            Some(account) => account.account_meta(is_writable, is_signer),
+            None => AccountMeta::new_readonly(crate::ID, false),
+        }
+    }
+
+    fn account_info(&self) -> Option<AccountInfo<'info>> {
+        self.clone()
+    }
+}
+
+macro_rules! access {
+    (read) => {
+        (false, false)
+    };
+    (mut) => {
+        (true, false)
+    };
+    (signer) => {
+        (true, true)
+    };
+}
+
+macro_rules! cpi_accounts {
+    (
+        $(#[$doc:meta])*
+        $name:ident {
+            $($(#[$field_doc:meta])* $field:ident: $ty:ty => $access:tt,)*
+        }
+    ) => {
+        $(#[$doc])*
+        #[derive(Clone)]
+        pub struct $name<'info> {
+            $($(#[$field_doc])* pub $field: $ty,)*
+        }
+
+        impl ToAccountMetas for $name<'_> {
+            fn to_account_metas(&self, _is_signer: Option<bool>) -> Vec<AccountMeta> {
+                vec![$({
+                    let (is_writable, is_signer) = access!($access);
+                    CpiAccount::account_meta(&self.$field, is_writable, is_signer)
+                },)*]
+            }
+        }
+
+        impl<'info> ToAccountInfos<'info> for $name<'info> {
+            fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+                [$(CpiAccount::account_info(&self.$field),)*]
+                    .into_iter()
+                    .flatten()
+                    .collect()
+            }
+        }
+    };
+}
+
+cpi_accounts!(
+    /// Accounts of `swap`, the pool vaults ordered by the trade direction
+    Swap {
+        pool_authority: AccountInfo<'info> => read,
+        pool: AccountInfo<'info> => mut,
+        config: AccountInfo<'info> => read,
+        pool_source_token_account: AccountInfo<'info> => mut,
+        pool_destination_token_account: AccountInfo<'info> => mut,
+        source_mint: AccountInfo<'info> => read,
+        destination_mint: AccountInfo<'info> => read,
+        user_source_token_account: AccountInfo<'info> => mut,
+        user_destination_token_account: AccountInfo<'info> => mut,
+        pool_mint: AccountInfo<'info> => mut,
+        pool_token_fee_account: AccountInfo<'info> => mut,
+        /// Pool token account of the front-end hosting the swap, takes a share of the owner fee.
+        /// A declared account, not the first remaining account as in earlier versions
+        host_fee_account: Option<AccountInfo<'info>> => mut,
+        user: AccountInfo<'info> => signer,
+        creator: AccountInfo<'info> => read,
+        system_program: AccountInfo<'info> => read,
+        /// Token program of the pool mint
+        token_program: AccountInfo<'info> => read,
+        source_token_program: AccountInfo<'info> => read,
+        destination_token_program: AccountInfo<'info> => read,
+    }
+);
+
+cpi_accounts!(
+    /// Accounts of `deposit_all_tokens_in`
+    DepositAllTokens {
+        pool_authority: AccountInfo<'info> => read,
+        pool: AccountInfo<'info> => read,
+        pool_token_a_account: AccountInfo<'info> => mut,
+        pool_token_b_account: AccountInfo<'info> => mut,
+        token_a_mint: AccountInfo<'info> => read,
+        token_b_mint: AccountInfo<'info> => read,
+        user_token_a_account: AccountInfo<'info> => mut,
+        user_token_b_account: AccountInfo<'info> => mut,
+        user_pool_token_receipt: AccountInfo<'info> => mut,
+        pool_mint: AccountInfo<'info> => mut,
+        pool_token_fee_account: AccountInfo<'info> => read,
+        user: AccountInfo<'info> => signer,
+        creator: AccountInfo<'info> => read,
+        system_program: AccountInfo<'info> => read,
+        token_program: AccountInfo<'info> => read,
+        token_a_program: AccountInfo<'info> => read,
+        token_b_program: AccountInfo<'info> => read,
+    }
+);
+
+cpi_accounts!(
+    /// Accounts of `deposit_single_token`
+    DepositSingleToken {
+        pool_authority: AccountInfo<'info> => read,
+        pool: AccountInfo<'info> => mut,
+        pool_token_a_account: AccountInfo<'info> => mut,
+        pool_token_b_account: AccountInfo<'info> => mut,
+        user_source_token_account: AccountInfo<'info> => mut,
+        source_mint: AccountInfo<'info> => read,
+        user_pool_token_receipt: AccountInfo<'info> => mut,
+        pool_mint: AccountInfo<'info> => mut,
+        user: AccountInfo<'info> => signer,
+        system_program: AccountInfo<'info> => read,
+        token_program: AccountInfo<'info> => read,
+        source_token_program: AccountInfo<'info> => read,
+        config: AccountInfo<'info> => read,
+    }
+);
+
+cpi_accounts!(
+    /// Accounts of `withdraw_single_token_out`
+    WithdrawSingleToken {
+        pool_authority: AccountInfo<'info> => read,
+        pool: AccountInfo<'info> => mut,
+        pool_token_a_account: AccountInfo<'info> => mut,
+        pool_token_b_account: AccountInfo<'info> => mut,
+        user_source_token_account: AccountInfo<'info> => mut,
+        source_mint: AccountInfo<'info> => read,
+        user_pool_token_receipt: AccountInfo<'info> => mut,
+        pool_mint: AccountInfo<'info> => mut,
+        pool_token_fee_account: AccountInfo<'info> => mut,
+        user: AccountInfo<'info> => signer,
+        system_program: AccountInfo<'info> => read,
+        token_program: AccountInfo<'info> => read,
+        source_token_program: AccountInfo<'info> => read,
+        config: AccountInfo<'info> => read,
+    }
+);
+
+cpi_accounts!(
+    /// Accounts of `quote_swap`
+    QuoteSwap {
+        pool: AccountInfo<'info> => read,
+        config: AccountInfo<'info> => read,
+        pool_source_token_account: AccountInfo<'info> => read,
+        pool_destination_token_account: AccountInfo<'info> => read,
+        source_mint: AccountInfo<'info> => read,
+        destination_mint: AccountInfo<'info> => read,
+    }
+);
+
+cpi_accounts!(
+    /// Accounts of `quote_deposit_single`
+    QuoteDepositSingle {
+        pool: AccountInfo<'info> => read,
+        pool_token_a_account: AccountInfo<'info> => read,
+        pool_token_b_account: AccountInfo<'info> => read,
+        source_mint: AccountInfo<'info> => read,
+        pool_mint: AccountInfo<'info> => read,
+        config: AccountInfo<'info> => read,
+    }
+);
+
+cpi_accounts!(
+    /// Accounts of `quote_withdraw_single`
+    QuoteWithdrawSingle {
+        pool: AccountInfo<'info> => read,
+        pool_token_a_account: AccountInfo<'info> => read,
+        pool_token_b_account: AccountInfo<'info> => read,
+        source_mint: AccountInfo<'info> => read,
+        pool_mint: AccountInfo<'info> => read,
+        config: AccountInfo<'info> => read,
+    }
+);
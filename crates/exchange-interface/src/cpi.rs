@@ -0,0 +1,106 @@
+//! Invokes the exchange from another program.
+//!
+//! Remaining accounts of the context are appended after the declared ones, for
+//! the transfer hooks of the pool's mints.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::InstructionData;
+
+use crate::quote::{read_quote, LiquidityQuote, SwapQuote};
+use crate::{accounts, instruction};
+
+fn invoke<'info, T: ToAccountMetas + ToAccountInfos<'info>>(
+    ctx: CpiContext<'_, '_, '_, 'info, T>,
+    data: impl InstructionData,
+) -> Result<()> {
+    let instruction = Instruction {
+        program_id: crate::ID,
+        accounts: ctx.to_account_metas(None),
+        data: data.data(),
+    };
+    invoke_signed(&instruction, &ctx.to_account_infos(), ctx.signer_seeds).map_err(Into::into)
+}
+
+pub fn swap<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, accounts::Swap<'info>>,
+    source_amount: u64,
+    minimum_amount_out: u64,
+) -> Result<()> {
+    invoke(
+        ctx,
+        instruction::Swap {
+            source_amount,
+            minimum_amount_out,
+        },
+    )
+}
+
+pub fn deposit_all_tokens_in<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, accounts::DepositAllTokens<'info>>,
+    pool_tokens: u64,
+    max_token_a: u64,
+    max_token_b: u64,
+) -> Result<()> {
+    invoke(
+        ctx,
+        instruction::DepositAllTokensIn {
+            pool_tokens,
+            max_token_a,
+            max_token_b,
+        },
+    )
+}
+
+pub fn deposit_single_token<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, accounts::DepositSingleToken<'info>>,
+    source_amount: u64,
+    minimum_pool_tokens_out: u64,
+) -> Result<()> {
+    invoke(
+        ctx,
+        instruction::DepositSingleToken {
+            source_amount,
+            minimum_pool_tokens_out,
+        },
+    )
+}
+
+pub fn withdraw_single_token_out<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, accounts::WithdrawSingleToken<'info>>,
+    source_amount: u64,
+    maximum_pool_tokens_burned: u64,
+) -> Result<()> {
+    invoke(
+        ctx,
+        instruction::WithdrawSingleTokenOut {
+            source_amount,
+            maximum_pool_tokens_burned,
+        },
+    )
+}
+
+pub fn quote_swap<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, accounts::QuoteSwap<'info>>,
+    source_amount: u64,
+) -> Result<SwapQuote> {
+    invoke(ctx, instruction::QuoteSwap { source_amount })?;
+    read_quote()
+}
+
+pub fn quote_deposit_single<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, accounts::QuoteDepositSingle<'info>>,
+    source_amount: u64,
+) -> Result<LiquidityQuote> {
+    invoke(ctx, instruction::QuoteDepositSingle { source_amount })?;
+    read_quote()
+}
+
+pub fn quote_withdraw_single<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, accounts::QuoteWithdrawSingle<'info>>,
+    source_amount: u64,
+) -> Result<LiquidityQuote> {
+    invoke(ctx, instruction::QuoteWithdrawSingle { source_amount })?;
+    read_quote()
+}
@@ -0,0 +1,65 @@
+//! Instruction data, the discriminator followed by the Borsh encoded arguments.
+
+use anchor_lang::prelude::*;
+use anchor_lang::{Discriminator, InstructionData};
+
+macro_rules! instruction_data {
+    ($(#[$doc:meta])* $name:ident = $discriminator:expr, { $($field:ident: $ty:ty),* $(,)? }) => {
+        $(#[$doc])*
+        #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+        pub struct $name {
+            $(pub $field: $ty,)*
+        }
+
+        impl Discriminator for $name {
+            const DISCRIMINATOR: [u8; 8] = $discriminator;
+        }
+
+        impl InstructionData for $name {}
+    };
+}
+
+instruction_data!(
+    /// `swap`, `source_amount` of the source token for at least `minimum_amount_out` of
+    /// the destination token
+    Swap = [248, 198, 158, 145, 225, 117, 135, 200],
+    { source_amount: u64, minimum_amount_out: u64 }
+);
+
+instruction_data!(
+    /// `deposit_all_tokens_in`, both tokens for `pool_tokens`
+    DepositAllTokensIn = [54, 14, 240, 165, 229, 226, 192, 173],
+    { pool_tokens: u64, max_token_a: u64, max_token_b: u64 }
+);
+
+instruction_data!(
+    /// `deposit_single_token`, `source_amount` of one token for at least
+    /// `minimum_pool_tokens_out` pool tokens
+    DepositSingleToken = [166, 136, 166, 47, 199, 192, 86, 169],
+    { source_amount: u64, minimum_pool_tokens_out: u64 }
+);
+
+instruction_data!(
+    /// `withdraw_single_token_out`, at most `maximum_pool_tokens_burned` pool tokens
+    /// for `source_amount` of one token
+    WithdrawSingleTokenOut = [133, 129, 91, 13, 38, 238, 248, 82],
+    { source_amount: u64, maximum_pool_tokens_burned: u64 }
+);
+
+instruction_data!(
+    /// `quote_swap`, returns a [`crate::quote::SwapQuote`]
+    QuoteSwap = [20, 139, 100, 190, 67, 4, 13, 141],
+    { source_amount: u64 }
+);
+
+instruction_data!(
+    /// `quote_deposit_single`, returns a [`crate::quote::LiquidityQuote`]
+    QuoteDepositSingle = [25, 164, 177, 194, 238, 217, 95, 17],
+    { source_amount: u64 }
+);
+
+instruction_data!(
+    /// `quote_withdraw_single`, returns a [`crate::quote::LiquidityQuote`]
+    QuoteWithdrawSingle = [13, 170, 68, 31, 31, 105, 116, 0],
+    { source_amount: u64 }
+);
@@ -0,0 +1,399 @@
+//! Interface of the exchange program for programs calling it through CPI.
+//!
+//! Depends on `anchor-lang` alone, so callers don't build the program, its
+//! curve math or `anchor-spl`. What is here is kept stable across program
+//! upgrades:
+//!
+//! - the program id, the seeds of the config and the pool authority
+//! - the discriminators and the Borsh layout of the instruction data
+//! - the order, mutability and signers of the instruction accounts
+//! - the return data of the quote instructions
+//!
+//! New instructions and new fields of the return data may be added, existing
+//! ones are never reordered or removed. Accounts passed after the declared
+//! ones are forwarded to the mints' transfer hooks.
+
+pub mod accounts;
+pub mod cpi;
+pub mod instruction;
+pub mod pda;
+pub mod quote;
+
+use anchor_lang::prelude::*;
+
+declare_id!("HndsTUfB2AZbQifHN9WdKMMQqXghGVwmak2gy3oyzwqV");
+
+/// Exchange program, for `Program<'info, Exchange>` accounts
+#[derive(Clone)]
+pub struct Exchange;
+
+impl Id for Exchange {
+    fn id() -> Pubkey {
+        ID
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{AnchorSerialize, Discriminator};
+
+    /// Accounts with unique keys backed by `storage`
+    fn account_infos(storage: &mut [(Pubkey, u64, Vec<u8>)]) -> Vec<AccountInfo<'_>> {
+        storage
+            .iter_mut()
+            .map(|(key, lamports, data)| {
+                AccountInfo::new(key, false, false, lamports, data, &ID, false, 0)
+            })
+            .collect()
+    }
+
+    fn storage(len: usize) -> Vec<(Pubkey, u64, Vec<u8>)> {
+        (0..len)
+            .map(|_| (Pubkey::new_unique(), 0, vec![]))
+            .collect()
+    }
+
+    #[test]
+    fn program_id_matches_the_program() {
+        assert_eq!(ID, exchange::ID);
+        assert_eq!(pda::find_config_address(), {
+            Pubkey::find_program_address(&[exchange::constants::CONFIG], &exchange::ID)
+        });
+    }
+
+    #[test]
+    fn instruction_data_matches_the_program() {
+        use exchange::instruction as program;
+
+        fn assert_data<I: Discriminator + AnchorSerialize, P: Discriminator + AnchorSerialize>(
+            interface: I,
+            program: P,
+        ) {
+            assert_eq!(I::DISCRIMINATOR, P::DISCRIMINATOR);
+            assert_eq!(
+                interface.try_to_vec().unwrap(),
+                program.try_to_vec().unwrap()
+            );
+        }
+
+        assert_data(
+            instruction::Swap {
+                source_amount: 1,
+                minimum_amount_out: 2,
+            },
+            program::Swap {
+                source_amount: 1,
+                minimum_amount_out: 2,
+            },
+        );
+        assert_data(
+            instruction::DepositAllTokensIn {
+                pool_tokens: 1,
+                max_token_a: 2,
+                max_token_b: 3,
+            },
+            program::DepositAllTokensIn {
+                pool_tokens: 1,
+                max_token_a: 2,
+                max_token_b: 3,
+            },
+        );
+        assert_data(
+            instruction::DepositSingleToken {
+                source_amount: 1,
+                minimum_pool_tokens_out: 2,
+            },
+            program::DepositSingleToken {
+                source_amount: 1,
+                minimum_pool_tokens_out: 2,
+            },
+        );
+        assert_data(
+            instruction::WithdrawSingleTokenOut {
+                source_amount: 1,
+                maximum_pool_tokens_burned: 2,
+            },
+            program::WithdrawSingleTokenOut {
+                source_amount: 1,
+                maximum_pool_tokens_burned: 2,
+            },
+        );
+        assert_data(
+            instruction::QuoteSwap { source_amount: 1 },
+            program::QuoteSwap { source_amount: 1 },
+        );
+        assert_data(
+            instruction::QuoteDepositSingle { source_amount: 1 },
+            program::QuoteDepositSingle { source_amount: 1 },
+        );
+        assert_data(
+            instruction::QuoteWithdrawSingle { source_amount: 1 },
+            program::QuoteWithdrawSingle { source_amount: 1 },
+        );
+    }
+
+    #[test]
+    fn swap_accounts_match_the_program() {
+        let mut storage = storage(18);
+        let infos = account_infos(&mut storage);
+        let swap = |host: bool| accounts::Swap {
+            pool_authority: infos[0].clone(),
+            pool: infos[1].clone(),
+            config: infos[2].clone(),
+            pool_source_token_account: infos[3].clone(),
+            pool_destination_token_account: infos[4].clone(),
+            source_mint: infos[5].clone(),
+            destination_mint: infos[6].clone(),
+            user_source_token_account: infos[7].clone(),
+            user_destination_token_account: infos[8].clone(),
+            pool_mint: infos[9].clone(),
+            pool_token_fee_account: infos[10].clone(),
+            host_fee_account: host.then(|| infos[11].clone()),
+            user: infos[12].clone(),
+            creator: infos[13].clone(),
+            system_program: infos[14].clone(),
+            token_program: infos[15].clone(),
+            source_token_program: infos[16].clone(),
+            destination_token_program: infos[17].clone(),
+        };
+        let program = |host: bool| exchange::accounts::Swap {
+            pool_authority: *infos[0].key,
+            pool: *infos[1].key,
+            config: *infos[2].key,
+            pool_source_token_account: *infos[3].key,
+            pool_destination_token_account: *infos[4].key,
+            source_mint: *infos[5].key,
+            destination_mint: *infos[6].key,
+            user_source_token_account: *infos[7].key,
+            user_destination_token_account: *infos[8].key,
+            pool_mint: *infos[9].key,
+            pool_token_fee_account: *infos[10].key,
+            host_fee_account: host.then(|| *infos[11].key),
+            user: *infos[12].key,
+            creator: *infos[13].key,
+            system_program: *infos[14].key,
+            token_program: *infos[15].key,
+            source_token_program: *infos[16].key,
+            destination_token_program: *infos[17].key,
+        };
+
+        assert_eq!(
+            swap(true).to_account_metas(None),
+            program(true).to_account_metas(None)
+        );
+        assert_eq!(
+            swap(false).to_account_metas(None),
+            program(false).to_account_metas(None)
+        );
+        // the missing host fee account has no account info to pass
+        assert_eq!(swap(false).to_account_infos().len(), 17);
+    }
+
+    #[test]
+    fn liquidity_accounts_match_the_program() {
+        let mut storage = storage(17);
+        let infos = account_infos(&mut storage);
+        let key = |index: usize| *infos[index].key;
+
+        let deposit_all = accounts::DepositAllTokens {
+            pool_authority: infos[0].clone(),
+            pool: infos[1].clone(),
+            pool_token_a_account: infos[2].clone(),
+            pool_token_b_account: infos[3].clone(),
+            token_a_mint: infos[4].clone(),
+            token_b_mint: infos[5].clone(),
+            user_token_a_account: infos[6].clone(),
+            user_token_b_account: infos[7].clone(),
+            user_pool_token_receipt: infos[8].clone(),
+            pool_mint: infos[9].clone(),
+            pool_token_fee_account: infos[10].clone(),
+            user: infos[11].clone(),
+            creator: infos[12].clone(),
+            system_program: infos[13].clone(),
+            token_program: infos[14].clone(),
+            token_a_program: infos[15].clone(),
+            token_b_program: infos[16].clone(),
+        };
+        let program = exchange::accounts::DepositAllTokens {
+            pool_authority: key(0),
+            pool: key(1),
+            pool_token_a_account: key(2),
+            pool_token_b_account: key(3),
+            token_a_mint: key(4),
+            token_b_mint: key(5),
+            user_token_a_account: key(6),
+            user_token_b_account: key(7),
+            user_pool_token_receipt: key(8),
+            pool_mint: key(9),
+            pool_token_fee_account: key(10),
+            user: key(11),
+            creator: key(12),
+            system_program: key(13),
+            token_program: key(14),
+            token_a_program: key(15),
+            token_b_program: key(16),
+        };
+        assert_eq!(
+            deposit_all.to_account_metas(None),
+            program.to_account_metas(None)
+        );
+
+        let deposit_single = accounts::DepositSingleToken {
+            pool_authority: infos[0].clone(),
+            pool: infos[1].clone(),
+            pool_token_a_account: infos[2].clone(),
+            pool_token_b_account: infos[3].clone(),
+            user_source_token_account: infos[4].clone(),
+            source_mint: infos[5].clone(),
+            user_pool_token_receipt: infos[6].clone(),
+            pool_mint: infos[7].clone(),
+            user: infos[8].clone(),
+            system_program: infos[9].clone(),
+            token_program: infos[10].clone(),
+            source_token_program: infos[11].clone(),
+            config: infos[12].clone(),
+        };
+        let program = exchange::accounts::DepositSingleToken {
+            pool_authority: key(0),
+            pool: key(1),
+            pool_token_a_account: key(2),
+            pool_token_b_account: key(3),
+            user_source_token_account: key(4),
+            source_mint: key(5),
+            user_pool_token_receipt: key(6),
+            pool_mint: key(7),
+            user: key(8),
+            system_program: key(9),
+            token_program: key(10),
+            source_token_program: key(11),
+            config: key(12),
+        };
+        assert_eq!(
+            deposit_single.to_account_metas(None),
+            program.to_account_metas(None)
+        );
+
+        let withdraw_single = accounts::WithdrawSingleToken {
+            pool_authority: infos[0].clone(),
+            pool: infos[1].clone(),
+            pool_token_a_account: infos[2].clone(),
+            pool_token_b_account: infos[3].clone(),
+            user_source_token_account: infos[4].clone(),
+            source_mint: infos[5].clone(),
+            user_pool_token_receipt: infos[6].clone(),
+            pool_mint: infos[7].clone(),
+            pool_token_fee_account: infos[8].clone(),
+            user: infos[9].clone(),
+            system_program: infos[10].clone(),
+            token_program: infos[11].clone(),
+            source_token_program: infos[12].clone(),
+            config: infos[13].clone(),
+        };
+        let program = exchange::accounts::WithdrawSingleToken {
+            pool_authority: key(0),
+            pool: key(1),
+            pool_token_a_account: key(2),
+            pool_token_b_account: key(3),
+            user_source_token_account: key(4),
+            source_mint: key(5),
+            user_pool_token_receipt: key(6),
+            pool_mint: key(7),
+            pool_token_fee_account: key(8),
+            user: key(9),
+            system_program: key(10),
+            token_program: key(11),
+            source_token_program: key(12),
+            config: key(13),
+        };
+        assert_eq!(
+            withdraw_single.to_account_metas(None),
+            program.to_account_metas(None)
+        );
+    }
+
+    #[test]
+    fn quote_accounts_and_return_data_match_the_program() {
+        let mut storage = storage(6);
+        let infos = account_infos(&mut storage);
+        let key = |index: usize| *infos[index].key;
+
+        let quote_swap = accounts::QuoteSwap {
+            pool: infos[0].clone(),
+            config: infos[1].clone(),
+            pool_source_token_account: infos[2].clone(),
+            pool_destination_token_account: infos[3].clone(),
+            source_mint: infos[4].clone(),
+            destination_mint: infos[5].clone(),
+        };
+        let program = exchange::accounts::QuoteSwap {
+            pool: key(0),
+            config: key(1),
+            pool_source_token_account: key(2),
+            pool_destination_token_account: key(3),
+            source_mint: key(4),
+            destination_mint: key(5),
+        };
+        assert_eq!(
+            quote_swap.to_account_metas(None),
+            program.to_account_metas(None)
+        );
+
+        let quote_single = accounts::QuoteDepositSingle {
+            pool: infos[0].clone(),
+            pool_token_a_account: infos[1].clone(),
+            pool_token_b_account: infos[2].clone(),
+            source_mint: infos[3].clone(),
+            pool_mint: infos[4].clone(),
+            config: infos[5].clone(),
+        };
+        let program = exchange::accounts::QuoteDepositSingle {
+            pool: key(0),
+            pool_token_a_account: key(1),
+            pool_token_b_account: key(2),
+            source_mint: key(3),
+            pool_mint: key(4),
+            config: key(5),
+        };
+        assert_eq!(
+            quote_single.to_account_metas(None),
+            program.to_account_metas(None)
+        );
+
+        let swap_quote = exchange::quote::SwapQuote {
+            source_amount: 1,
+            destination_amount: 2,
+            trade_fee: 3,
+            owner_fee: 4,
+            protocol_fee: 5,
+            price_impact_bps: 0,
+        };
+        let data = swap_quote.try_to_vec().unwrap();
+        assert_eq!(
+            quote::deserialize_quote::<quote::SwapQuote>(&data[..33]).unwrap(),
+            quote::SwapQuote {
+                source_amount: 1,
+                destination_amount: 2,
+                trade_fee: 3,
+                owner_fee: 4,
+                protocol_fee: 5,
+                price_impact_bps: 0,
+            }
+        );
+
+        let liquidity_quote = exchange::quote::LiquidityQuote {
+            pool_tokens: 7,
+            price_impact_bps: 8,
+        };
+        assert_eq!(
+            quote::deserialize_quote::<quote::LiquidityQuote>(
+                &liquidity_quote.try_to_vec().unwrap()
+            )
+            .unwrap(),
+            quote::LiquidityQuote {
+                pool_tokens: 7,
+                price_impact_bps: 8,
+            }
+        );
+    }
+}
@@ -0,0 +1,14 @@
+use anchor_lang::prelude::Pubkey;
+
+pub const PREFIX: &[u8] = b"pool";
+pub const AUTHORITY: &[u8] = b"authority";
+pub const CONFIG: &[u8] = b"config";
+
+pub fn find_config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG], &crate::ID)
+}
+
+/// Signs for the vaults and the pool mint of `pool`
+pub fn find_pool_authority_address(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PREFIX, pool.as_ref(), AUTHORITY], &crate::ID)
+}
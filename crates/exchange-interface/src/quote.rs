@@ -0,0 +1,44 @@
+//! Return data of the quote instructions.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::get_return_data;
+
+/// Returned by `quote_swap`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapQuote {
+    /// Source tokens sent by the user, transfer fee included
+    pub source_amount: u64,
+    /// Destination tokens received by the user, net of the transfer fee
+    pub destination_amount: u64,
+    pub trade_fee: u64,
+    pub owner_fee: u64,
+    /// Share of the trade fee set aside for the protocol
+    pub protocol_fee: u64,
+    pub price_impact_bps: u64,
+}
+
+/// Returned by `quote_deposit_single` and `quote_withdraw_single`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LiquidityQuote {
+    /// Pool tokens minted on a deposit, or burned on a withdrawal
+    pub pool_tokens: u64,
+    pub price_impact_bps: u64,
+}
+
+/// Deserializes return data, which the runtime stores without its trailing zeros
+pub fn deserialize_quote<T: AnchorDeserialize>(data: &[u8]) -> Result<T> {
+    // quotes are plain u64 fields, so their Borsh size is their memory size
+    let mut data = data.to_vec();
+    data.resize(std::mem::size_of::<T>().max(data.len()), 0);
+    T::try_from_slice(&data).map_err(Into::into)
+}
+
+/// Quote returned by the exchange in the last CPI
+pub fn read_quote<T: AnchorDeserialize>() -> Result<T> {
+    match get_return_data() {
+        Some((program_id, data)) if program_id == crate::ID => deserialize_quote(&data),
+        // all zero return data is dropped altogether
+        None => deserialize_quote(&[]),
+        Some(_) => Err(ErrorCode::InvalidProgramId.into()),
+    }
+}
@@ -0,0 +1,201 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use exchange_math::constant_product::{
+    calculate_initial_pool_tokens, calculate_owner_fee_pool_tokens,
+};
+use exchange_math::quote::{quote_deposit, quote_deposit_all, quote_swap, quote_withdraw};
+use exchange_math::uint::U256;
+use exchange_math::TradeFees;
+use libfuzzer_sys::fuzz_target;
+
+const FEE_DENOMINATOR: u64 = 10_000;
+
+#[derive(Arbitrary, Debug)]
+enum Instruction {
+    Swap { token_a: bool, amount: u64 },
+    DepositAll { pool_tokens: u64 },
+    DepositSingle { token_a: bool, amount: u64 },
+    WithdrawSingle { token_a: bool, amount: u64 },
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    token_a: u64,
+    token_b: u64,
+    trade_fee_numerator: u16,
+    owner_trade_fee_numerator: u16,
+    protocol_fee_numerator: u16,
+    instructions: Vec<Instruction>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Pool {
+    token_a: u64,
+    token_b: u64,
+    supply: u64,
+}
+
+impl Pool {
+    fn reserves(&mut self, token_a: bool) -> (&mut u64, &mut u64) {
+        if token_a {
+            (&mut self.token_a, &mut self.token_b)
+        } else {
+            (&mut self.token_b, &mut self.token_a)
+        }
+    }
+
+    /// A_new * B_new * P^2 >= A * B * P_new^2, i.e. sqrt(A * B) / P did not shrink
+    fn value_kept(&self, before: &Pool) -> bool {
+        U256::from(self.token_a)
+            * U256::from(self.token_b)
+            * U256::from(before.supply)
+            * U256::from(before.supply)
+            >= U256::from(before.token_a)
+                * U256::from(before.token_b)
+                * U256::from(self.supply)
+                * U256::from(self.supply)
+    }
+}
+
+/// Applies the quotes of the program instructions to the vaults and the pool
+/// mint, returning `None` where the instruction would fail
+fn execute(
+    pool: &Pool,
+    fee: &TradeFees,
+    protocol_fee_numerator: u64,
+    instruction: &Instruction,
+) -> Option<Pool> {
+    let mut pool = *pool;
+    match *instruction {
+        Instruction::Swap { token_a, amount } => {
+            let supply = pool.supply;
+            let (source, destination) = pool.reserves(token_a);
+            let quote = quote_swap(
+                amount,
+                *source,
+                *destination,
+                fee,
+                protocol_fee_numerator,
+                FEE_DENOMINATOR,
+            )
+            .ok()?;
+            if amount == 0 || quote.destination_amount == 0 {
+                return None;
+            }
+
+            let new_source = source
+                .checked_add(quote.source_amount)?
+                .checked_sub(quote.protocol_fee)?;
+            let pool_tokens = calculate_owner_fee_pool_tokens(
+                quote.owner_fee as u128,
+                new_source as u128,
+                supply as u128,
+            )
+            .ok()?;
+
+            *source = new_source;
+            *destination -= quote.destination_amount;
+            pool.supply = pool.supply.checked_add(u64::try_from(pool_tokens).ok()?)?;
+        }
+        Instruction::DepositAll { pool_tokens } => {
+            let quote =
+                quote_deposit_all(pool_tokens, pool.supply, pool.token_a, pool.token_b).ok()?;
+
+            pool.token_a = pool.token_a.checked_add(quote.token_a_amount)?;
+            pool.token_b = pool.token_b.checked_add(quote.token_b_amount)?;
+            pool.supply = pool.supply.checked_add(pool_tokens)?;
+        }
+        Instruction::DepositSingle { token_a, amount } => {
+            let supply = pool.supply;
+            let (source, other) = pool.reserves(token_a);
+            let quote = quote_deposit(
+                amount,
+                *source,
+                *other,
+                supply,
+                fee,
+                protocol_fee_numerator,
+                FEE_DENOMINATOR,
+            )
+            .ok()?;
+            if amount == 0 {
+                return None;
+            }
+
+            *source = source
+                .checked_add(amount)?
+                .checked_sub(quote.protocol_fee)?;
+            pool.supply = pool.supply.checked_add(quote.pool_tokens)?;
+        }
+        Instruction::WithdrawSingle { token_a, amount } => {
+            let supply = pool.supply;
+            let (destination, other) = pool.reserves(token_a);
+            let quote = quote_withdraw(
+                amount,
+                *destination,
+                *other,
+                supply,
+                fee,
+                protocol_fee_numerator,
+                FEE_DENOMINATOR,
+            )
+            .ok()?;
+            if amount == 0 || quote.pool_tokens >= supply {
+                return None;
+            }
+
+            *destination = destination
+                .checked_sub(amount)?
+                .checked_sub(quote.protocol_fee)?;
+            pool.supply -= quote.pool_tokens;
+        }
+    }
+
+    Some(pool)
+}
+
+fuzz_target!(|input: Input| {
+    if input.token_a == 0 || input.token_b == 0 {
+        return;
+    }
+
+    let trade_fee_numerator = input.trade_fee_numerator as u64 % FEE_DENOMINATOR;
+    let owner_trade_fee_numerator = input.owner_trade_fee_numerator as u64 % FEE_DENOMINATOR;
+    let protocol_fee_numerator = input.protocol_fee_numerator as u64 % FEE_DENOMINATOR;
+    let fee = TradeFees {
+        trade_fee_numerator,
+        trade_fee_denominator: FEE_DENOMINATOR,
+        owner_trade_fee_numerator,
+        owner_trade_fee_denominator: FEE_DENOMINATOR,
+    };
+
+    let Ok(supply) = calculate_initial_pool_tokens(input.token_a as u128, input.token_b as u128)
+    else {
+        return;
+    };
+    let Ok(supply) = u64::try_from(supply) else {
+        return;
+    };
+    if supply == 0 {
+        return;
+    }
+    let mut pool = Pool {
+        token_a: input.token_a,
+        token_b: input.token_b,
+        supply,
+    };
+
+    for instruction in &input.instructions {
+        let before = pool;
+        if let Some(after) = execute(&pool, &fee, protocol_fee_numerator, instruction) {
+            pool = after;
+        }
+
+        assert!(pool.token_a > 0 && pool.token_b > 0 && pool.supply > 0);
+        assert!(
+            pool.value_kept(&before),
+            "{instruction:?} drained value from {before:?} to {pool:?}"
+        );
+    }
+});
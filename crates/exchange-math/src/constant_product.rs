@@ -0,0 +1,603 @@
+use crate::uint::U256;
+use crate::{CurveError, Result, RoundDirection, TradeFees};
+
+// Constant product swap : (A+A') * (B-B') = invariant
+pub fn calculate_swap_amounts(
+    source_amount: u128,
+    pool_source_amount: u128,
+    pool_destination_amount: u128,
+    fee: &TradeFees,
+) -> Result<(u128, u128, u128, u128, u128, u128)> {
+    // Calculate the fee
+    let trading_fee = calculate_fee(
+        source_amount,
+        fee.trade_fee_numerator,
+        fee.trade_fee_denominator,
+    )
+    .ok_or(CurveError::NumeralOverflow)?;
+
+    let owner_fee = calculate_fee(
+        source_amount,
+        fee.owner_trade_fee_numerator,
+        fee.owner_trade_fee_denominator,
+    )
+    .ok_or(CurveError::NumeralOverflow)?;
+
+    let total_fee = trading_fee
+        .checked_add(owner_fee)
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    let source_amount_after_fee = source_amount
+        .checked_sub(total_fee)
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    // invariant = (A*B)
+    let invariant = pool_source_amount
+        .checked_mul(pool_destination_amount)
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    // A + A'
+    let total_source_amount = pool_source_amount
+        .checked_add(source_amount_after_fee)
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    // B - B' = invariant/(A+A');
+    let (total_destination_amount, total_source_amount) =
+        checked_ceil_div(invariant, total_source_amount).ok_or(CurveError::NumeralOverflow)?;
+
+    // B' = B - invariant/(A+A')
+    let swapped_destination_amount = pool_destination_amount
+        .checked_sub(total_destination_amount)
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    // A' = total_source - A
+    let swapped_source_amount_with_fee = total_source_amount
+        .checked_sub(pool_source_amount)
+        .unwrap()
+        .checked_add(total_fee)
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    let new_pool_source_amount = pool_source_amount
+        .checked_add(swapped_source_amount_with_fee)
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    let new_pool_destination_amount = pool_destination_amount
+        .checked_sub(swapped_destination_amount)
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    Ok((
+        new_pool_source_amount,
+        new_pool_destination_amount,
+        swapped_source_amount_with_fee,
+        swapped_destination_amount,
+        owner_fee,
+        trading_fee,
+    ))
+}
+
+/*
+    P ~ sqrt(A * B)
+    P_new = [ P * sqrt((A' +  A) * (B' + B)) / sqrt(A * B) ]
+    P' = P_new - P
+    P' = [ P * sqrt((A' + A) * (B' + B)) / sqrt(A * B) ] - P
+    P' = P * [ sqrt([(A'+ A) * (B' + B)] / A * B ) - 1 ]
+
+    When deposit single token, B' = 0
+
+    P' = P * [sqrt((A'+ A) / A) - 1]
+*/
+
+pub fn calculate_pool_tokens_propotional_to_single_token_deposit(
+    source_amount: u128,
+    pool_source_amount: u128,
+    pool_supply: u128,
+    round_direction: RoundDirection,
+) -> Result<u128> {
+    let new_pool_source_amount = pool_source_amount
+        .checked_add(source_amount)
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    let new_pool_supply = calculate_new_pool_supply(
+        pool_supply,
+        pool_source_amount,
+        new_pool_source_amount,
+        round_direction,
+    )?;
+
+    new_pool_supply
+        .checked_sub(pool_supply)
+        .ok_or(CurveError::NumeralOverflow)
+}
+
+/*
+    P ~ sqrt(A * B)
+    P_new = [ P * sqrt((A -  A) * (B - B')) / sqrt(A * B) ]
+    P' = P - P_new
+    P' = P - [ P * sqrt((A - A') * (B - B')) / sqrt(A * B) ]
+    P' = P * [ 1 - sqrt([(A - A') * (B - B')] / A * B ) ]
+
+    When withdraw single token, B' = 0
+
+    P' = P * [ 1 - sqrt((A - A') / A) ]
+*/
+
+pub fn calculate_pool_tokens_propotional_to_single_token_redeemed(
+    source_amount: u128,
+    pool_source_amount: u128,
+    pool_supply: u128,
+    round_direction: RoundDirection,
+) -> Result<u128> {
+    let new_pool_source_amount = pool_source_amount
+        .checked_sub(source_amount)
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    // burning more pool tokens leaves a smaller supply, so round it the other way
+    let new_pool_supply = calculate_new_pool_supply(
+        pool_supply,
+        pool_source_amount,
+        new_pool_source_amount,
+        match round_direction {
+            RoundDirection::Floor => RoundDirection::Ceiling,
+            RoundDirection::Ceiling => RoundDirection::Floor,
+        },
+    )?;
+
+    pool_supply
+        .checked_sub(new_pool_supply)
+        .ok_or(CurveError::NumeralOverflow)
+}
+
+/*
+    The owner fee F is already part of the new source reserve A_new, so it is
+    valued as a single token deposit on top of A_new - F
+
+    P' = P * [sqrt(A_new / (A_new - F)) - 1]
+*/
+pub fn calculate_owner_fee_pool_tokens(
+    owner_fee: u128,
+    new_pool_source_amount: u128,
+    pool_supply: u128,
+) -> Result<u128> {
+    let pool_source_amount = new_pool_source_amount
+        .checked_sub(owner_fee)
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    // the liquidity providers are never diluted by the fee
+    calculate_pool_tokens_propotional_to_single_token_deposit(
+        owner_fee,
+        pool_source_amount,
+        pool_supply,
+        RoundDirection::Floor,
+    )
+}
+
+/*
+    Using min_pool_token_amount to calculate the token_a and token_b it represents in the pool
+
+    token_a = (P_min / P) * P_token_a
+    token_b = (P_min / P) * P_token_b
+
+*/
+pub fn calculate_trade_tokens_propotional_to_pool_tokens(
+    min_pool_token_amount: u128,
+    pool_token_supply: u128,
+    pool_token_a: u128,
+    pool_token_b: u128,
+    round_direction: RoundDirection,
+) -> Result<(u128, u128)> {
+    let token_a = min_pool_token_amount
+        .checked_mul(pool_token_a)
+        .ok_or(CurveError::NumeralOverflow)?;
+    let token_a = div_rounded(token_a, pool_token_supply, round_direction)?;
+
+    let token_b = min_pool_token_amount
+        .checked_mul(pool_token_b)
+        .ok_or(CurveError::NumeralOverflow)?;
+    let token_b = div_rounded(token_b, pool_token_supply, round_direction)?;
+
+    Ok((token_a, token_b))
+}
+
+/// Initial pool token supply, the geometric mean sqrt(A * B) of the deposited reserves
+pub fn calculate_initial_pool_tokens(token_a_amount: u128, token_b_amount: u128) -> Result<u128> {
+    let invariant = token_a_amount
+        .checked_mul(token_b_amount)
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    u128::try_from(U256::from(invariant).integer_sqrt()).map_err(|_| CurveError::ConversionFailure)
+}
+
+/// Fee charged on `source_amount`, rounded up as the user pays it
+pub fn calculate_fee(
+    source_amount: u128,
+    fee_numerator: u64,
+    fee_denominator: u64,
+) -> Option<u128> {
+    if fee_numerator == 0 || source_amount == 0 {
+        return Some(0);
+    }
+
+    let fee = source_amount.checked_mul(fee_numerator as u128)?;
+    div_rounded(fee, fee_denominator as u128, RoundDirection::Ceiling).ok()
+}
+
+/*
+    Price of the source token in destination tokens p = B / A
+
+    move = |p_new - p| / p = |1 - (B_new * A) / (B * A_new)|
+*/
+pub fn calculate_price_move_bps(
+    pool_source_amount: u128,
+    pool_destination_amount: u128,
+    new_pool_source_amount: u128,
+    new_pool_destination_amount: u128,
+) -> Result<u128> {
+    let price = pool_destination_amount
+        .checked_mul(new_pool_source_amount)
+        .ok_or(CurveError::NumeralOverflow)?;
+    let new_price = new_pool_destination_amount
+        .checked_mul(pool_source_amount)
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    // move * 10_000 rounded to the nearest bps, (2 * move * 10_000 + p) / 2p
+    let price_move = U256::from(price.abs_diff(new_price)) * U256::from(20_000u64);
+    let price = U256::from(price) * U256::from(2u8);
+    let price_move_bps = (price_move + price / 2)
+        .checked_div(price)
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    u128::try_from(price_move_bps).map_err(|_| CurveError::ConversionFailure)
+}
+
+/// Share of a fee routed elsewhere, e.g. the protocol or the swap host
+pub fn calculate_fee_share(
+    fee: u128,
+    share_numerator: u64,
+    share_denominator: u64,
+) -> Option<u128> {
+    if share_numerator == 0 {
+        return Some(0);
+    }
+
+    fee.checked_mul(share_numerator as u128)?
+        .checked_div(share_denominator as u128)
+}
+
+/*
+    Ceiling division that also lowers the divisor to the smallest one giving
+    the same quotient, so rounding the quotient up doesn't take more than needed:
+    400 / 32 = 12.5 becomes 400 / 31 = 13. Dividing by a larger number fails
+    rather than returning 1.
+*/
+fn checked_ceil_div(dividend: u128, mut divisor: u128) -> Option<(u128, u128)> {
+    let mut quotient = dividend.checked_div(divisor)?;
+    if quotient == 0 {
+        return None;
+    }
+
+    if dividend.checked_rem(divisor)? > 0 {
+        quotient = quotient.checked_add(1)?;
+        divisor = dividend.checked_div(quotient)?;
+        if dividend.checked_rem(quotient)? > 0 {
+            divisor = divisor.checked_add(1)?;
+        }
+    }
+    Some((quotient, divisor))
+}
+
+fn div_rounded(
+    numerator: u128,
+    denominator: u128,
+    round_direction: RoundDirection,
+) -> Result<u128> {
+    let quotient = numerator
+        .checked_div(denominator)
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    match round_direction {
+        RoundDirection::Ceiling if quotient * denominator != numerator => {
+            quotient.checked_add(1).ok_or(CurveError::NumeralOverflow)
+        }
+        _ => Ok(quotient),
+    }
+}
+
+/*
+    Single token deposits and withdrawals keep P_new / P = sqrt(A_new / A), so
+    P_new = sqrt(A_new * P^2 / A), settled on exact integers since an
+    approximated square root can be off by more than a token on large supplies
+*/
+fn calculate_new_pool_supply(
+    pool_supply: u128,
+    pool_source_amount: u128,
+    new_pool_source_amount: u128,
+    round_direction: RoundDirection,
+) -> Result<u128> {
+    let pool_supply = U256::from(pool_supply);
+    let pool_source_amount = U256::from(pool_source_amount);
+
+    let numerator = pool_supply
+        .checked_mul(pool_supply)
+        .and_then(|value| value.checked_mul(U256::from(new_pool_source_amount)))
+        .ok_or(CurveError::NumeralOverflow)?;
+    let (quotient, remainder) = numerator
+        .checked_div(pool_source_amount)
+        .zip(numerator.checked_rem(pool_source_amount))
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    let new_pool_supply_squared = match round_direction {
+        RoundDirection::Ceiling if !remainder.is_zero() => quotient + 1,
+        _ => quotient,
+    };
+    let mut new_pool_supply = new_pool_supply_squared.integer_sqrt();
+    if round_direction == RoundDirection::Ceiling
+        && new_pool_supply * new_pool_supply != new_pool_supply_squared
+    {
+        new_pool_supply += U256::one();
+    }
+
+    u128::try_from(new_pool_supply).map_err(|_| CurveError::ConversionFailure)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn fee(trade_fee_numerator: u64, owner_trade_fee_numerator: u64) -> TradeFees {
+        TradeFees {
+            trade_fee_numerator,
+            trade_fee_denominator: 10_000,
+            owner_trade_fee_numerator,
+            owner_trade_fee_denominator: 10_000,
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    enum Operation {
+        DepositSingle { token_a: bool, amount: u128 },
+        WithdrawSingle { token_a: bool, amount: u128 },
+        DepositAll { pool_tokens: u128 },
+    }
+
+    fn operation() -> impl Strategy<Value = Operation> {
+        prop_oneof![
+            (any::<bool>(), 1u128..100_000_000)
+                .prop_map(|(token_a, amount)| Operation::DepositSingle { token_a, amount }),
+            (any::<bool>(), 1u128..100_000_000)
+                .prop_map(|(token_a, amount)| Operation::WithdrawSingle { token_a, amount }),
+            (1u128..100_000_000).prop_map(|pool_tokens| Operation::DepositAll { pool_tokens }),
+        ]
+    }
+
+    /// A_new * B_new * P^2 >= A * B * P_new^2, i.e. sqrt(A * B) / P did not shrink
+    fn lp_value_kept(before: (u128, u128, u128), after: (u128, u128, u128)) -> bool {
+        let (token_a, token_b, supply) = before;
+        let (new_token_a, new_token_b, new_supply) = after;
+
+        U256::from(new_token_a) * U256::from(new_token_b) * U256::from(supply) * U256::from(supply)
+            >= U256::from(token_a)
+                * U256::from(token_b)
+                * U256::from(new_supply)
+                * U256::from(new_supply)
+    }
+
+    proptest! {
+        #[test]
+        fn deposits_and_withdrawals_never_drain_dust(
+            token_a in 1_000u128..1_000_000_000,
+            token_b in 1_000u128..1_000_000_000,
+            supply in 1_000u128..1_000_000_000,
+            operations in prop::collection::vec(operation(), 1..32),
+        ) {
+            let (mut token_a, mut token_b, mut supply) = (token_a, token_b, supply);
+
+            for operation in operations {
+                let before = (token_a, token_b, supply);
+                match operation {
+                    Operation::DepositSingle { token_a: is_a, amount } => {
+                        let reserve = if is_a { &mut token_a } else { &mut token_b };
+                        let Ok(minted) = calculate_pool_tokens_propotional_to_single_token_deposit(
+                            amount,
+                            *reserve,
+                            supply,
+                            RoundDirection::Floor,
+                        ) else {
+                            continue;
+                        };
+                        *reserve += amount;
+                        supply += minted;
+                    }
+                    Operation::WithdrawSingle { token_a: is_a, amount } => {
+                        let reserve = if is_a { &mut token_a } else { &mut token_b };
+                        if amount >= *reserve {
+                            continue;
+                        }
+                        let Ok(burned) = calculate_pool_tokens_propotional_to_single_token_redeemed(
+                            amount,
+                            *reserve,
+                            supply,
+                            RoundDirection::Ceiling,
+                        ) else {
+                            continue;
+                        };
+                        if burned >= supply {
+                            continue;
+                        }
+                        *reserve -= amount;
+                        supply -= burned;
+                    }
+                    Operation::DepositAll { pool_tokens } => {
+                        let (paid_a, paid_b) = calculate_trade_tokens_propotional_to_pool_tokens(
+                            pool_tokens,
+                            supply,
+                            token_a,
+                            token_b,
+                            RoundDirection::Ceiling,
+                        )
+                        .unwrap();
+                        token_a += paid_a;
+                        token_b += paid_b;
+                        supply += pool_tokens;
+                    }
+                }
+
+                prop_assert!(lp_value_kept(before, (token_a, token_b, supply)));
+            }
+        }
+
+        #[test]
+        fn swap_keeps_invariant_and_fee_bounds(
+            pool_source_amount in 1_000u128..1_000_000_000,
+            pool_destination_amount in 1_000u128..1_000_000_000,
+            source_amount in 1u128..1_000_000_000,
+            trade_fee_numerator in 0u64..1_000,
+            owner_trade_fee_numerator in 0u64..1_000,
+        ) {
+            let fee = fee(trade_fee_numerator, owner_trade_fee_numerator);
+            let Ok((
+                new_pool_source_amount,
+                new_pool_destination_amount,
+                swapped_source_amount,
+                swapped_destination_amount,
+                owner_fee,
+                trading_fee,
+            )) = calculate_swap_amounts(
+                source_amount,
+                pool_source_amount,
+                pool_destination_amount,
+                &fee,
+            ) else {
+                return Ok(());
+            };
+
+            prop_assert!(
+                new_pool_source_amount * new_pool_destination_amount
+                    >= pool_source_amount * pool_destination_amount
+            );
+            prop_assert!(swapped_source_amount <= source_amount);
+            prop_assert!(swapped_destination_amount < pool_destination_amount);
+
+            // fees are the rate rounded up, and nothing when the rate is zero
+            for (fee, numerator) in [
+                (trading_fee, trade_fee_numerator),
+                (owner_fee, owner_trade_fee_numerator),
+            ] {
+                let exact = source_amount * numerator as u128;
+                prop_assert!(fee * 10_000 >= exact);
+                prop_assert!(fee * 10_000 < exact + 10_000);
+            }
+            prop_assert!(trading_fee + owner_fee <= swapped_source_amount);
+        }
+
+        #[test]
+        fn swap_round_trip_is_not_profitable(
+            pool_source_amount in 1_000u128..1_000_000_000,
+            pool_destination_amount in 1_000u128..1_000_000_000,
+            source_amount in 1u128..1_000_000_000,
+            trade_fee_numerator in 0u64..1_000,
+            owner_trade_fee_numerator in 0u64..1_000,
+        ) {
+            let fee = fee(trade_fee_numerator, owner_trade_fee_numerator);
+            let Ok((new_pool_source_amount, new_pool_destination_amount, swapped_source_amount, swapped_destination_amount, _, _)) =
+                calculate_swap_amounts(source_amount, pool_source_amount, pool_destination_amount, &fee)
+            else {
+                return Ok(());
+            };
+            let Ok((_, _, _, returned_amount, _, _)) = calculate_swap_amounts(
+                swapped_destination_amount,
+                new_pool_destination_amount,
+                new_pool_source_amount,
+                &fee,
+            ) else {
+                return Ok(());
+            };
+
+            prop_assert!(returned_amount <= swapped_source_amount);
+        }
+
+        #[test]
+        fn single_token_round_trip_is_not_profitable(
+            pool_source_amount in 1_000u128..1_000_000_000,
+            pool_supply in 1_000u128..1_000_000_000,
+            source_amount in 1u128..1_000_000_000,
+        ) {
+            let minted = calculate_pool_tokens_propotional_to_single_token_deposit(
+                source_amount,
+                pool_source_amount,
+                pool_supply,
+                RoundDirection::Floor,
+            )
+            .unwrap();
+            let burned = calculate_pool_tokens_propotional_to_single_token_redeemed(
+                source_amount,
+                pool_source_amount + source_amount,
+                pool_supply + minted,
+                RoundDirection::Ceiling,
+            )
+            .unwrap();
+
+            prop_assert!(burned >= minted);
+        }
+
+        #[test]
+        fn deposit_all_pays_at_least_the_pool_share(
+            pool_token_a in 1u128..1_000_000_000,
+            pool_token_b in 1u128..1_000_000_000,
+            pool_supply in 1u128..1_000_000_000,
+            pool_tokens in 1u128..1_000_000_000,
+        ) {
+            let (token_a, token_b) = calculate_trade_tokens_propotional_to_pool_tokens(
+                pool_tokens,
+                pool_supply,
+                pool_token_a,
+                pool_token_b,
+                RoundDirection::Ceiling,
+            )
+            .unwrap();
+
+            prop_assert!(token_a * pool_supply >= pool_tokens * pool_token_a);
+            prop_assert!(token_b * pool_supply >= pool_tokens * pool_token_b);
+            prop_assert!((token_a - 1) * pool_supply < pool_tokens * pool_token_a);
+            prop_assert!((token_b - 1) * pool_supply < pool_tokens * pool_token_b);
+        }
+
+        #[test]
+        fn swap_never_decreases_lp_value(
+            pool_source_amount in 1_000u128..1_000_000_000,
+            pool_destination_amount in 1_000u128..1_000_000_000,
+            pool_supply in 1_000u128..1_000_000_000,
+            source_amount in 1u128..1_000_000_000,
+            trade_fee_numerator in 0u64..1_000,
+            owner_trade_fee_numerator in 0u64..1_000,
+        ) {
+            let fee = fee(trade_fee_numerator, owner_trade_fee_numerator);
+            let Ok((new_pool_source_amount, new_pool_destination_amount, _, _, owner_fee, _)) =
+                calculate_swap_amounts(
+                    source_amount,
+                    pool_source_amount,
+                    pool_destination_amount,
+                    &fee,
+                )
+            else {
+                return Ok(());
+            };
+
+            let owner_pool_tokens = calculate_owner_fee_pool_tokens(
+                owner_fee,
+                new_pool_source_amount,
+                pool_supply,
+            )
+            .unwrap();
+
+            prop_assert!(lp_value_kept(
+                (pool_source_amount, pool_destination_amount, pool_supply),
+                (
+                    new_pool_source_amount,
+                    new_pool_destination_amount,
+                    pool_supply + owner_pool_tokens,
+                ),
+            ));
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use core::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveError {
+    /// An intermediate amount overflowed or went below zero
+    NumeralOverflow,
+    /// A result doesn't fit the integer it is returned as
+    ConversionFailure,
+}
+
+impl fmt::Display for CurveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CurveError::NumeralOverflow => f.write_str("numeral overflow"),
+            CurveError::ConversionFailure => f.write_str("conversion failure"),
+        }
+    }
+}
+
+pub type Result<T> = core::result::Result<T, CurveError>;
@@ -0,0 +1,10 @@
+/// Fees charged on the source amount of a swap, both rounded up
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TradeFees {
+    /// Kept in the pool for the liquidity providers
+    pub trade_fee_numerator: u64,
+    pub trade_fee_denominator: u64,
+    /// Minted as pool tokens to the pool fee account
+    pub owner_trade_fee_numerator: u64,
+    pub owner_trade_fee_denominator: u64,
+}
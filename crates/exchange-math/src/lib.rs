@@ -0,0 +1,24 @@
+//! Constant product curve math of the exchange program.
+//!
+//! Plain integer math without Anchor or Solana types, shared by the program
+//! and by off-chain quoters so a quote is exactly what the program executes.
+
+#![cfg_attr(not(test), no_std)]
+
+pub mod constant_product;
+mod error;
+mod fee;
+pub mod quote;
+pub mod uint;
+
+pub use error::*;
+pub use fee::*;
+
+/// Rounding of curve conversions, always in favour of the pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundDirection {
+    /// The user receives the amount
+    Floor,
+    /// The user pays the amount
+    Ceiling,
+}
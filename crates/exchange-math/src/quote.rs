@@ -0,0 +1,332 @@
+//! Quotes of the pool instructions.
+//!
+//! Reserves are the liquidity provider reserves, i.e. the vault amounts less
+//! the protocol fees held in them, and amounts in are what the vault receives
+//! after any transfer fee of the mint.
+
+use crate::constant_product::*;
+use crate::{CurveError, Result, RoundDirection, TradeFees};
+
+/// Outcome of a swap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapQuote {
+    /// Source tokens taken from the user, fees included, at most the amount in
+    pub source_amount: u64,
+    /// Destination tokens sent to the user, the swap fails when zero
+    pub destination_amount: u64,
+    pub trade_fee: u64,
+    pub owner_fee: u64,
+    /// Share of the trade fee set aside for the protocol
+    pub protocol_fee: u64,
+    /// Move of the pool price caused by the swap
+    pub price_impact_bps: u64,
+}
+
+/// Outcome of a single token deposit or withdrawal
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LiquidityQuote {
+    /// Pool tokens minted on a deposit, or burned on a withdrawal
+    pub pool_tokens: u64,
+    /// Fees charged on the half of the amount traded against the other side
+    pub trade_fee: u64,
+    pub owner_fee: u64,
+    /// Share of the trade fee set aside for the protocol
+    pub protocol_fee: u64,
+    pub price_impact_bps: u64,
+}
+
+/// Tokens paid for a deposit of both tokens
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DepositAllQuote {
+    pub token_a_amount: u64,
+    pub token_b_amount: u64,
+}
+
+fn to_u64(amount: u128) -> Result<u64> {
+    u64::try_from(amount).map_err(|_| CurveError::ConversionFailure)
+}
+
+pub fn quote_swap(
+    source_amount: u64,
+    pool_source_amount: u64,
+    pool_destination_amount: u64,
+    fees: &TradeFees,
+    protocol_fee_numerator: u64,
+    protocol_fee_denominator: u64,
+) -> Result<SwapQuote> {
+    let (
+        new_pool_source_amount,
+        new_pool_destination_amount,
+        swapped_source_amount,
+        swapped_destination_amount,
+        owner_fee,
+        trade_fee,
+    ) = calculate_swap_amounts(
+        source_amount as u128,
+        pool_source_amount as u128,
+        pool_destination_amount as u128,
+        fees,
+    )?;
+
+    let protocol_fee =
+        calculate_fee_share(trade_fee, protocol_fee_numerator, protocol_fee_denominator)
+            .ok_or(CurveError::NumeralOverflow)?;
+    let new_pool_source_amount = new_pool_source_amount
+        .checked_sub(protocol_fee)
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    let price_move_bps = calculate_price_move_bps(
+        pool_source_amount as u128,
+        pool_destination_amount as u128,
+        new_pool_source_amount,
+        new_pool_destination_amount,
+    )?;
+
+    Ok(SwapQuote {
+        source_amount: to_u64(swapped_source_amount)?,
+        destination_amount: to_u64(swapped_destination_amount)?,
+        trade_fee: to_u64(trade_fee)?,
+        owner_fee: to_u64(owner_fee)?,
+        protocol_fee: to_u64(protocol_fee)?,
+        price_impact_bps: u64::try_from(price_move_bps).unwrap_or(u64::MAX),
+    })
+}
+
+/*
+    A single token deposit or withdrawal is half a swap against the other side
+    of the pool, so half of the amount pays the trade and owner fees like a swap
+    would. Both fees stay in the pool for the liquidity providers, except the
+    protocol share of the trade fee. Otherwise a single token deposit followed
+    by a single token withdrawal of the other token would be a free swap.
+*/
+fn single_token_fees(
+    amount: u64,
+    fees: &TradeFees,
+    protocol_fee_numerator: u64,
+    protocol_fee_denominator: u64,
+) -> Result<(u128, u128, u128)> {
+    let half_amount = amount as u128 / 2;
+    let trade_fee = calculate_fee(
+        half_amount,
+        fees.trade_fee_numerator,
+        fees.trade_fee_denominator,
+    )
+    .ok_or(CurveError::NumeralOverflow)?;
+    let owner_fee = calculate_fee(
+        half_amount,
+        fees.owner_trade_fee_numerator,
+        fees.owner_trade_fee_denominator,
+    )
+    .ok_or(CurveError::NumeralOverflow)?;
+    let protocol_fee =
+        calculate_fee_share(trade_fee, protocol_fee_numerator, protocol_fee_denominator)
+            .ok_or(CurveError::NumeralOverflow)?;
+
+    Ok((trade_fee, owner_fee, protocol_fee))
+}
+
+/// Deposit of `source_amount` on the source side, the other side left as is
+pub fn quote_deposit(
+    source_amount: u64,
+    pool_source_amount: u64,
+    pool_other_amount: u64,
+    pool_supply: u64,
+    fees: &TradeFees,
+    protocol_fee_numerator: u64,
+    protocol_fee_denominator: u64,
+) -> Result<LiquidityQuote> {
+    let (trade_fee, owner_fee, protocol_fee) = single_token_fees(
+        source_amount,
+        fees,
+        protocol_fee_numerator,
+        protocol_fee_denominator,
+    )?;
+    let source_amount_after_fees = (source_amount as u128)
+        .checked_sub(trade_fee)
+        .and_then(|amount| amount.checked_sub(owner_fee))
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    let pool_tokens = calculate_pool_tokens_propotional_to_single_token_deposit(
+        source_amount_after_fees,
+        pool_source_amount as u128,
+        pool_supply as u128,
+        RoundDirection::Floor,
+    )?;
+
+    let new_pool_source_amount = (pool_source_amount as u128 + source_amount as u128)
+        .checked_sub(protocol_fee)
+        .ok_or(CurveError::NumeralOverflow)?;
+    let price_move_bps = calculate_price_move_bps(
+        pool_source_amount as u128,
+        pool_other_amount as u128,
+        new_pool_source_amount,
+        pool_other_amount as u128,
+    )?;
+
+    Ok(LiquidityQuote {
+        pool_tokens: to_u64(pool_tokens)?,
+        trade_fee: to_u64(trade_fee)?,
+        owner_fee: to_u64(owner_fee)?,
+        protocol_fee: to_u64(protocol_fee)?,
+        price_impact_bps: u64::try_from(price_move_bps).unwrap_or(u64::MAX),
+    })
+}
+
+/// Withdrawal of `destination_amount` from the destination side, the pool
+/// tokens burned also pay the fees, which stay in the pool
+pub fn quote_withdraw(
+    destination_amount: u64,
+    pool_destination_amount: u64,
+    pool_other_amount: u64,
+    pool_supply: u64,
+    fees: &TradeFees,
+    protocol_fee_numerator: u64,
+    protocol_fee_denominator: u64,
+) -> Result<LiquidityQuote> {
+    let (trade_fee, owner_fee, protocol_fee) = single_token_fees(
+        destination_amount,
+        fees,
+        protocol_fee_numerator,
+        protocol_fee_denominator,
+    )?;
+    let redeemed_amount = (destination_amount as u128)
+        .checked_add(trade_fee)
+        .and_then(|amount| amount.checked_add(owner_fee))
+        .ok_or(CurveError::NumeralOverflow)?;
+
+    let pool_tokens = calculate_pool_tokens_propotional_to_single_token_redeemed(
+        redeemed_amount,
+        pool_destination_amount as u128,
+        pool_supply as u128,
+        RoundDirection::Ceiling,
+    )?;
+
+    let new_pool_destination_amount = (pool_destination_amount as u128)
+        .checked_sub(destination_amount as u128)
+        .and_then(|amount| amount.checked_sub(protocol_fee))
+        .ok_or(CurveError::NumeralOverflow)?;
+    let price_move_bps = calculate_price_move_bps(
+        pool_destination_amount as u128,
+        pool_other_amount as u128,
+        new_pool_destination_amount,
+        pool_other_amount as u128,
+    )?;
+
+    Ok(LiquidityQuote {
+        pool_tokens: to_u64(pool_tokens)?,
+        trade_fee: to_u64(trade_fee)?,
+        owner_fee: to_u64(owner_fee)?,
+        protocol_fee: to_u64(protocol_fee)?,
+        price_impact_bps: u64::try_from(price_move_bps).unwrap_or(u64::MAX),
+    })
+}
+
+/// Deposit of both tokens for `pool_tokens`, which leaves the price unchanged
+pub fn quote_deposit_all(
+    pool_tokens: u64,
+    pool_supply: u64,
+    pool_token_a_amount: u64,
+    pool_token_b_amount: u64,
+) -> Result<DepositAllQuote> {
+    let (token_a_amount, token_b_amount) = calculate_trade_tokens_propotional_to_pool_tokens(
+        pool_tokens as u128,
+        pool_supply as u128,
+        pool_token_a_amount as u128,
+        pool_token_b_amount as u128,
+        RoundDirection::Ceiling,
+    )?;
+
+    Ok(DepositAllQuote {
+        token_a_amount: to_u64(token_a_amount)?,
+        token_b_amount: to_u64(token_b_amount)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEES: TradeFees = TradeFees {
+        trade_fee_numerator: 30,
+        trade_fee_denominator: 10_000,
+        owner_trade_fee_numerator: 5,
+        owner_trade_fee_denominator: 10_000,
+    };
+
+    #[test]
+    fn quote_swap_returns_amounts_fees_and_price_impact() {
+        let quote = quote_swap(10_000, 1_000_000, 1_000_000, &FEES, 1, 2).unwrap();
+
+        assert_eq!(
+            quote,
+            SwapQuote {
+                source_amount: 10_000,
+                destination_amount: 9_866,
+                trade_fee: 30,
+                owner_fee: 5,
+                protocol_fee: 15,
+                price_impact_bps: 197,
+            }
+        );
+    }
+
+    #[test]
+    fn quote_single_token_round_trip_burns_what_was_minted() {
+        let deposit = quote_deposit(10_000, 1_000_000, 1_000_000, 1_000_000, &FEES, 1, 2).unwrap();
+        let withdraw = quote_withdraw(
+            10_000,
+            1_010_000 - deposit.protocol_fee,
+            1_000_000,
+            1_000_000 + deposit.pool_tokens,
+            &FEES,
+            1,
+            2,
+        )
+        .unwrap();
+
+        // half of the amount pays the fees of a swap
+        assert_eq!(deposit.trade_fee, 15);
+        assert_eq!(deposit.owner_fee, 3);
+        assert_eq!(deposit.protocol_fee, 7);
+        assert_eq!(deposit.pool_tokens, 4_978);
+        assert!(withdraw.pool_tokens > deposit.pool_tokens);
+        assert_eq!(deposit.price_impact_bps, 99);
+        assert_eq!(withdraw.price_impact_bps, 100);
+    }
+
+    #[test]
+    fn quote_single_token_deposit_then_withdrawal_pays_the_swap_fees() {
+        let deposit = quote_deposit(10_000, 1_000_000, 1_000_000, 1_000_000, &FEES, 0, 1).unwrap();
+        let supply = 1_000_000 + deposit.pool_tokens;
+
+        // largest amount of token B the minted pool tokens can withdraw
+        let (mut low, mut high) = (0u64, 1_000_000);
+        while low < high {
+            let amount = (low + high).div_ceil(2);
+            match quote_withdraw(amount, 1_000_000, 1_010_000, supply, &FEES, 0, 1) {
+                Ok(quote) if quote.pool_tokens <= deposit.pool_tokens => low = amount,
+                _ => high = amount - 1,
+            }
+        }
+
+        // the fees of the round trip are at least those of the swap
+        let swap = quote_swap(10_000, 1_000_000, 1_000_000, &FEES, 0, 1).unwrap();
+        assert!(low <= swap.destination_amount);
+    }
+
+    #[test]
+    fn quote_past_the_reserves_fails() {
+        assert_eq!(
+            quote_withdraw(1_000_001, 1_000_000, 1_000_000, 1_000_000, &FEES, 0, 1),
+            Err(CurveError::NumeralOverflow)
+        );
+        assert_eq!(
+            quote_deposit_all(1, 1, u64::MAX, u64::MAX).map(|quote| quote.token_a_amount),
+            Ok(u64::MAX)
+        );
+        assert_eq!(
+            quote_deposit_all(2, 1, u64::MAX, u64::MAX),
+            Err(CurveError::ConversionFailure)
+        );
+    }
+}
@@ -0,0 +1,11 @@
+//! 256 bit integer of the curve math, also used to check its invariants
+
+#![allow(
+    clippy::assign_op_pattern,
+    clippy::ptr_offset_with_cast,
+    clippy::manual_div_ceil
+)]
+
+uint::construct_uint! {
+    pub struct U256(4);
+}
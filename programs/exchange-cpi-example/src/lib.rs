@@ -0,0 +1,189 @@
+#![allow(unexpected_cfgs)]
+
+//! Swaps and deposits into the exchange through CPI, passing their slippage
+//! limits on to the exchange.
+
+use anchor_lang::prelude::*;
+use exchange_interface::{accounts as exchange_accounts, cpi as exchange_cpi, Exchange};
+
+declare_id!("HZ4ubUn6nEM4ubqJoVitvHjTJLBJZjz6tmWtf7qZbpxE");
+
+#[program]
+pub mod exchange_cpi_example {
+    use super::*;
+
+    /// Swaps `source_amount` and fails unless `minimum_amount_out` is received
+    pub fn swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExchangeSwap<'info>>,
+        source_amount: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        let accounts = &ctx.accounts;
+        let swap = exchange_accounts::Swap {
+            pool_authority: accounts.pool_authority.to_account_info(),
+            pool: accounts.pool.to_account_info(),
+            config: accounts.config.to_account_info(),
+            pool_source_token_account: accounts.pool_source_token_account.to_account_info(),
+            pool_destination_token_account: accounts
+                .pool_destination_token_account
+                .to_account_info(),
+            source_mint: accounts.source_mint.to_account_info(),
+            destination_mint: accounts.destination_mint.to_account_info(),
+            user_source_token_account: accounts.user_source_token_account.to_account_info(),
+            user_destination_token_account: accounts
+                .user_destination_token_account
+                .to_account_info(),
+            pool_mint: accounts.pool_mint.to_account_info(),
+            pool_token_fee_account: accounts.pool_token_fee_account.to_account_info(),
+            host_fee_account: None,
+            user: accounts.user.to_account_info(),
+            creator: accounts.creator.to_account_info(),
+            system_program: accounts.system_program.to_account_info(),
+            token_program: accounts.token_program.to_account_info(),
+            source_token_program: accounts.source_token_program.to_account_info(),
+            destination_token_program: accounts.destination_token_program.to_account_info(),
+        };
+        // transfer hook accounts are forwarded as they came
+        let swap_context = CpiContext::new(accounts.exchange_program.to_account_info(), swap)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        exchange_cpi::swap(swap_context, source_amount, minimum_amount_out)
+    }
+
+    /// Deposits `source_amount` and fails unless `minimum_pool_tokens` are minted
+    pub fn deposit<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExchangeDeposit<'info>>,
+        source_amount: u64,
+        minimum_pool_tokens: u64,
+    ) -> Result<()> {
+        let accounts = &ctx.accounts;
+        let deposit = exchange_accounts::DepositSingleToken {
+            pool_authority: accounts.pool_authority.to_account_info(),
+            pool: accounts.pool.to_account_info(),
+            pool_token_a_account: accounts.pool_token_a_account.to_account_info(),
+            pool_token_b_account: accounts.pool_token_b_account.to_account_info(),
+            user_source_token_account: accounts.user_source_token_account.to_account_info(),
+            source_mint: accounts.source_mint.to_account_info(),
+            user_pool_token_receipt: accounts.user_pool_token_receipt.to_account_info(),
+            pool_mint: accounts.pool_mint.to_account_info(),
+            user: accounts.user.to_account_info(),
+            system_program: accounts.system_program.to_account_info(),
+            token_program: accounts.token_program.to_account_info(),
+            source_token_program: accounts.source_token_program.to_account_info(),
+            config: accounts.config.to_account_info(),
+        };
+        let deposit_context = CpiContext::new(accounts.exchange_program.to_account_info(), deposit)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        exchange_cpi::deposit_single_token(deposit_context, source_amount, minimum_pool_tokens)
+    }
+}
+
+#[derive(Accounts)]
+pub struct ExchangeSwap<'info> {
+    /// CHECK: Checked by the exchange
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    #[account(mut)]
+    pub pool: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    #[account(mut)]
+    pub pool_source_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    #[account(mut)]
+    pub pool_destination_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    pub source_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    pub destination_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    #[account(mut)]
+    pub user_source_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    #[account(mut)]
+    pub user_destination_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    #[account(mut)]
+    pub pool_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    #[account(mut)]
+    pub pool_token_fee_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Checked by the exchange
+    pub creator: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Checked by the exchange
+    pub token_program: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    pub source_token_program: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    pub destination_token_program: UncheckedAccount<'info>,
+
+    pub exchange_program: Program<'info, Exchange>,
+}
+
+#[derive(Accounts)]
+pub struct ExchangeDeposit<'info> {
+    /// CHECK: Checked by the exchange
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    #[account(mut)]
+    pub pool: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    #[account(mut)]
+    pub pool_token_a_account: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    #[account(mut)]
+    pub pool_token_b_account: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    #[account(mut)]
+    pub user_source_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    pub source_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    #[account(mut)]
+    pub user_pool_token_receipt: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    #[account(mut)]
+    pub pool_mint: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Checked by the exchange
+    pub token_program: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    pub source_token_program: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by the exchange
+    pub config: UncheckedAccount<'info>,
+
+    pub exchange_program: Program<'info, Exchange>,
+}
@@ -0,0 +1,168 @@
+//! Compute unit baseline checked by `tests/compute_units.rs`.
+//!
+//! Every measured instruction is compared to its entry in `compute_units.txt`,
+//! the benchmark fails when one uses more than [`THRESHOLD_PERCENT`] over it,
+//! has no entry, or when an entry is no longer measured. Run the benchmark
+//! with `UPDATE_COMPUTE_UNITS=1` to rewrite it with the measured usage, and
+//! check the new baseline in with the change that moved it.
+//!
+//! Reference entries, see [`ComputeUnits::cheaper_than`], are measured once
+//! on an older build of the program and kept as they are by the update.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Regression tolerated over the baseline before the benchmark fails
+pub const THRESHOLD_PERCENT: u64 = 5;
+
+const HEADER: &str = "\
+# Compute units per instruction, checked by tests/compute_units.rs
+# Rewrite with: UPDATE_COMPUTE_UNITS=1 cargo test --test compute_units
+";
+
+pub fn baseline_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("compute_units.txt")
+}
+
+/// Usage measured by the benchmark against the checked-in baseline
+pub struct ComputeUnits {
+    baseline: BTreeMap<String, u64>,
+    measured: BTreeMap<String, u64>,
+    /// (measured instruction, reference entry it must stay below)
+    cheaper: Vec<(String, String)>,
+}
+
+impl ComputeUnits {
+    pub fn load() -> Self {
+        let baseline = std::fs::read_to_string(baseline_path()).unwrap_or_default();
+        Self {
+            baseline: parse(&baseline),
+            measured: BTreeMap::new(),
+            cheaper: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, name: &str, units: u64) {
+        let previous = self.measured.insert(name.to_string(), units);
+        assert!(previous.is_none(), "{name} is measured twice");
+    }
+
+    /// Requires `name` to use fewer units than `reference`, an entry of the
+    /// baseline measured on an older build of the program rather than by the
+    /// benchmark, e.g. on a layout it no longer has
+    pub fn cheaper_than(&mut self, name: &str, reference: &str) {
+        self.cheaper.push((name.to_string(), reference.to_string()));
+    }
+
+    fn is_reference(&self, name: &str) -> bool {
+        self.cheaper.iter().any(|(_, reference)| reference == name)
+    }
+
+    /// Rewrites the baseline when `UPDATE_COMPUTE_UNITS` is set, otherwise
+    /// panics on the instructions that regressed beyond the threshold or
+    /// that the baseline doesn't match
+    pub fn finish(self) {
+        for (name, units) in &self.measured {
+            match self.baseline.get(name) {
+                Some(baseline) => println!("{name}: {units} (baseline {baseline})"),
+                None => println!("{name}: {units} (no baseline)"),
+            }
+        }
+
+        if std::env::var_os("UPDATE_COMPUTE_UNITS").is_some() {
+            let mut entries = self.measured.clone();
+            for (name, units) in &self.baseline {
+                if self.is_reference(name) {
+                    entries.insert(name.clone(), *units);
+                }
+            }
+            std::fs::write(baseline_path(), render(&entries)).unwrap();
+            return;
+        }
+
+        let missing: Vec<&str> = self
+            .measured
+            .keys()
+            .chain(self.baseline.keys())
+            .filter(|name| {
+                !self.baseline.contains_key(*name)
+                    || !(self.measured.contains_key(*name) || self.is_reference(name))
+            })
+            .map(String::as_str)
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "{} is out of date, rewrite it with UPDATE_COMPUTE_UNITS=1: {}",
+            baseline_path().display(),
+            missing.join(", ")
+        );
+        // the update keeps references as they are, they are measured by hand
+        let references: Vec<&str> = self
+            .cheaper
+            .iter()
+            .map(|(_, reference)| reference.as_str())
+            .filter(|reference| !self.baseline.contains_key(*reference))
+            .collect();
+        assert!(
+            references.is_empty(),
+            "{} lacks the reference entries {}, measure them on the build they refer to",
+            baseline_path().display(),
+            references.join(", ")
+        );
+
+        let regressions: Vec<String> = self
+            .measured
+            .iter()
+            .filter_map(|(name, units)| {
+                let baseline = self.baseline[name];
+                regressed(baseline, *units).then(|| format!("{name}: {baseline} -> {units}"))
+            })
+            .collect();
+        assert!(
+            regressions.is_empty(),
+            "compute units regressed more than {THRESHOLD_PERCENT}% over {}:\n{}",
+            baseline_path().display(),
+            regressions.join("\n")
+        );
+
+        for (name, reference) in &self.cheaper {
+            let (units, reference_units) = (self.measured[name], self.baseline[reference]);
+            assert!(
+                units < reference_units,
+                "{name} uses {units} units, no fewer than the {reference_units} of {reference}"
+            );
+        }
+    }
+}
+
+/// Whether `units` is over the threshold above `baseline`
+pub fn regressed(baseline: u64, units: u64) -> bool {
+    units * 100 > baseline * (100 + THRESHOLD_PERCENT)
+}
+
+/// `name units` lines, `#` starting a comment
+fn parse(baseline: &str) -> BTreeMap<String, u64> {
+    baseline
+        .lines()
+        .map(|line| line.split('#').next().unwrap().trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (name, units) = line
+                .split_once(char::is_whitespace)
+                .unwrap_or_else(|| panic!("invalid baseline line {line:?}"));
+            let units = units
+                .trim()
+                .parse()
+                .unwrap_or_else(|_| panic!("invalid baseline line {line:?}"));
+            (name.to_string(), units)
+        })
+        .collect()
+}
+
+fn render(measured: &BTreeMap<String, u64>) -> String {
+    let mut baseline = HEADER.to_string();
+    for (name, units) in measured {
+        baseline.push_str(&format!("{name} {units}\n"));
+    }
+    baseline
+}
@@ -0,0 +1,1428 @@
+//! In-process harness for the exchange program.
+//!
+//! The suite runs the SBF build of the program under `solana-program-test`,
+//! so build it first with `anchor build` or
+//! `cargo build-sbf --manifest-path programs/exchange/Cargo.toml`.
+//! `SBF_OUT_DIR` overrides where `exchange.so` is looked up, next to
+//! `exchange_cpi_example.so` of the sample program calling it through CPI.
+//!
+//! Every `ExchangeError` the program can return has a failing case in `tests/`,
+//! except `ConversionFailure`, which guards values that always fit.
+
+use std::path::Path;
+
+pub mod compute_units;
+
+use anchor_lang::{
+    AccountDeserialize, AnchorDeserialize, AnchorSerialize, Discriminator, InstructionData,
+    ToAccountMetas,
+};
+use anchor_spl::associated_token::{
+    get_associated_token_address_with_program_id, spl_associated_token_account,
+};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{ExtensionType, StateWithExtensions},
+    state::{Account as TokenAccount, Mint},
+};
+use exchange::constants::{
+    AUTHORITY, CONFIG, LOCKED_LIQUIDITY, POOL_MINT, PREFIX, REGISTRY, VAULT,
+};
+use exchange::state::{
+    BaselinePool, Config, DynamicFeeConfig, Fee, LegacyDynamicFee, LegacyPool, Pool,
+    PoolLayout,
+};
+use solana_program_test::{
+    find_file, read_file, BanksClientError, ProgramTest, ProgramTestContext,
+};
+use solana_sdk::{
+    account::Account,
+    bpf_loader,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    clock::Clock,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+    transaction::{Transaction, TransactionError},
+};
+
+pub const FEE_DENOMINATOR: u64 = 10_000;
+
+/// 0.3% trade fee and 0.05% owner fee
+pub fn default_fees() -> Fee {
+    Fee {
+        trade_fee_numerator: 30,
+        trade_fee_denominator: FEE_DENOMINATOR,
+        owner_trade_fee_numerator: 5,
+        owner_trade_fee_denominator: FEE_DENOMINATOR,
+        owner_withdraw_fee_numerator: 0,
+        owner_withdraw_fee_denomiator: FEE_DENOMINATOR,
+    }
+}
+
+pub fn config_address() -> Pubkey {
+    Pubkey::find_program_address(&[CONFIG], &exchange::ID).0
+}
+
+pub fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[exchange::ID.as_ref()], &bpf_loader_upgradeable::id()).0
+}
+
+pub fn pool_address(token_a_mint: &Pubkey, token_b_mint: &Pubkey, creator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            PREFIX,
+            token_a_mint.as_ref(),
+            token_b_mint.as_ref(),
+            creator.as_ref(),
+        ],
+        &exchange::ID,
+    )
+    .0
+}
+
+pub fn pool_authority_address(pool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[PREFIX, pool.as_ref(), AUTHORITY], &exchange::ID).0
+}
+
+pub fn pool_mint_address(pool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[PREFIX, pool.as_ref(), POOL_MINT], &exchange::ID).0
+}
+
+pub fn vault_address(pool: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[PREFIX, pool.as_ref(), VAULT, mint.as_ref()],
+        &exchange::ID,
+    )
+    .0
+}
+
+pub fn locked_liquidity_address(pool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[PREFIX, pool.as_ref(), LOCKED_LIQUIDITY], &exchange::ID).0
+}
+
+pub fn registry_address(token_a_mint: &Pubkey, token_b_mint: &Pubkey, fee_tier: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            REGISTRY,
+            token_a_mint.as_ref(),
+            token_b_mint.as_ref(),
+            &fee_tier.to_le_bytes(),
+        ],
+        &exchange::ID,
+    )
+    .0
+}
+
+pub fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: exchange::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn initialize_config(admin: &Pubkey) -> Instruction {
+    instruction(
+        exchange::accounts::InitializeConfig {
+            config: config_address(),
+            admin: *admin,
+            program: exchange::ID,
+            program_data: program_data_address(),
+            system_program: system_program::id(),
+        },
+        exchange::instruction::InitializeConfig {},
+    )
+}
+
+pub fn add_fee_tier(
+    admin: &Pubkey,
+    fees: Fee,
+    dynamic_fee: Option<DynamicFeeConfig>,
+) -> Instruction {
+    instruction(
+        exchange::accounts::AddFeeTier {
+            config: config_address(),
+            admin: *admin,
+        },
+        exchange::instruction::AddFeeTier { fees, dynamic_fee },
+    )
+}
+
+pub fn disable_fee_tier(admin: &Pubkey, fee_tier: u16) -> Instruction {
+    instruction(
+        exchange::accounts::DisableFeeTier {
+            config: config_address(),
+            admin: *admin,
+        },
+        exchange::instruction::DisableFeeTier { fee_tier },
+    )
+}
+
+pub fn unregister_pool(admin: &Pubkey, registry: &Pubkey, pool: &Pubkey) -> Instruction {
+    instruction(
+        exchange::accounts::UnregisterPool {
+            config: config_address(),
+            registry: *registry,
+            admin: *admin,
+        },
+        exchange::instruction::UnregisterPool { pool: *pool },
+    )
+}
+
+pub fn set_protocol_fee(
+    admin: &Pubkey,
+    protocol_fee_numerator: u64,
+    protocol_fee_denominator: u64,
+    treasury: Pubkey,
+) -> Instruction {
+    instruction(
+        exchange::accounts::SetProtocolFee {
+            config: config_address(),
+            admin: *admin,
+        },
+        exchange::instruction::SetProtocolFee {
+            protocol_fee_numerator,
+            protocol_fee_denominator,
+            treasury,
+        },
+    )
+}
+
+pub fn set_host_fee(
+    admin: &Pubkey,
+    host_fee_numerator: u64,
+    host_fee_denominator: u64,
+) -> Instruction {
+    instruction(
+        exchange::accounts::SetHostFee {
+            config: config_address(),
+            admin: *admin,
+        },
+        exchange::instruction::SetHostFee {
+            host_fee_numerator,
+            host_fee_denominator,
+        },
+    )
+}
+
+/// Asserts that the transaction failed with the Anchor error `error`
+pub fn assert_error(result: Result<(), BanksClientError>, error: impl Into<u32>) {
+    let code = error.into();
+    match result.expect_err("transaction should fail").unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(actual)) => {
+            assert_eq!(actual, code)
+        }
+        error => panic!("expected custom error {code}, got {error:?}"),
+    }
+}
+
+/// (vault, mint, user account, token program) of one side of a pool
+type Side = (Pubkey, Pubkey, Pubkey, Pubkey);
+
+/// Addresses of a pool and of the creator's initial liquidity
+pub struct TestPool {
+    pub creator: Keypair,
+    pub creator_token_a: Pubkey,
+    pub creator_token_b: Pubkey,
+    pub fee_tier: u16,
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub fee_account: Pubkey,
+    pub locked_liquidity: Pubkey,
+    pub registry: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub token_a: Pubkey,
+    pub token_b: Pubkey,
+    pub token_a_program: Pubkey,
+    pub token_b_program: Pubkey,
+    /// Token-2022, SPL Token for pools of the first deployment
+    pub pool_mint_program: Pubkey,
+}
+
+/// A trader and liquidity provider of a pool
+pub struct TestUser {
+    pub keypair: Keypair,
+    pub token_a: Pubkey,
+    pub token_b: Pubkey,
+    pub pool_tokens: Pubkey,
+}
+
+impl TestPool {
+    pub fn creator_pool_tokens(&self) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            &self.creator.pubkey(),
+            &self.mint,
+            &self.pool_mint_program,
+        )
+    }
+
+    pub fn initialize_accounts(&self) -> exchange::accounts::InitializePool {
+        exchange::accounts::InitializePool {
+            pool: self.pool,
+            registry: self.registry,
+            config: config_address(),
+            pool_authority: self.authority,
+            token_a: self.token_a,
+            token_b: self.token_b,
+            token_a_mint: self.token_a_mint,
+            token_b_mint: self.token_b_mint,
+            creator_token_a: self.creator_token_a,
+            creator_token_b: self.creator_token_b,
+            pool_mint: self.mint,
+            user_pool_token_receipt: self.creator_pool_tokens(),
+            pool_fee_account: self.fee_account,
+            locked_liquidity: self.locked_liquidity,
+            creator: self.creator.pubkey(),
+            system_program: system_program::id(),
+            token_program: spl_token_2022::id(),
+            token_a_program: self.token_a_program,
+            token_b_program: self.token_b_program,
+            associated_token_program: spl_associated_token_account::id(),
+        }
+    }
+
+    pub fn initialize(
+        &self,
+        initial_token_a_amount: u64,
+        initial_token_b_amount: u64,
+    ) -> Instruction {
+        instruction(
+            self.initialize_accounts(),
+            exchange::instruction::Initialize {
+                fee_tier: self.fee_tier,
+                transfer_hook_programs: vec![],
+                initial_token_a_amount,
+                initial_token_b_amount,
+            },
+        )
+    }
+
+    /// Accounts of a swap from token A to B, or from B to A
+    pub fn swap_accounts(&self, user: &TestUser, a_to_b: bool) -> exchange::accounts::Swap {
+        let (source, destination) = self.sides(user, a_to_b);
+        exchange::accounts::Swap {
+            pool_authority: self.authority,
+            pool: self.pool,
+            config: config_address(),
+            pool_source_token_account: source.0,
+            pool_destination_token_account: destination.0,
+            source_mint: source.1,
+            destination_mint: destination.1,
+            user_source_token_account: source.2,
+            user_destination_token_account: destination.2,
+            pool_mint: self.mint,
+            pool_token_fee_account: self.fee_account,
+            host_fee_account: None,
+            user: user.keypair.pubkey(),
+            creator: self.creator.pubkey(),
+            system_program: system_program::id(),
+            token_program: self.pool_mint_program,
+            source_token_program: source.3,
+            destination_token_program: destination.3,
+        }
+    }
+
+    pub fn swap(&self, user: &TestUser, a_to_b: bool, source_amount: u64) -> Instruction {
+        self.swap_with_minimum(user, a_to_b, source_amount, 0)
+    }
+
+    /// Swap failing when less than `minimum_amount_out` is received
+    pub fn swap_with_minimum(
+        &self,
+        user: &TestUser,
+        a_to_b: bool,
+        source_amount: u64,
+        minimum_amount_out: u64,
+    ) -> Instruction {
+        instruction(
+            self.swap_accounts(user, a_to_b),
+            exchange::instruction::Swap {
+                source_amount,
+                minimum_amount_out,
+            },
+        )
+    }
+
+    pub fn deposit_all(
+        &self,
+        user: &TestUser,
+        pool_tokens: u64,
+        max_token_a: u64,
+        max_token_b: u64,
+    ) -> Instruction {
+        instruction(
+            exchange::accounts::DepositAllTokens {
+                pool_authority: self.authority,
+                pool: self.pool,
+                pool_token_a_account: self.token_a,
+                pool_token_b_account: self.token_b,
+                token_a_mint: self.token_a_mint,
+                token_b_mint: self.token_b_mint,
+                user_token_a_account: user.token_a,
+                user_token_b_account: user.token_b,
+                user_pool_token_receipt: user.pool_tokens,
+                pool_mint: self.mint,
+                pool_token_fee_account: self.fee_account,
+                user: user.keypair.pubkey(),
+                creator: self.creator.pubkey(),
+                system_program: system_program::id(),
+                token_program: self.pool_mint_program,
+                token_a_program: self.token_a_program,
+                token_b_program: self.token_b_program,
+            },
+            exchange::instruction::DepositAllTokensIn {
+                pool_tokens,
+                max_token_a,
+                max_token_b,
+            },
+        )
+    }
+
+    pub fn deposit_single_accounts(
+        &self,
+        user: &TestUser,
+        token_a: bool,
+    ) -> exchange::accounts::DepositSingleToken {
+        let (source, _) = self.sides(user, token_a);
+        exchange::accounts::DepositSingleToken {
+            pool_authority: self.authority,
+            pool: self.pool,
+            pool_token_a_account: self.token_a,
+            pool_token_b_account: self.token_b,
+            user_source_token_account: source.2,
+            source_mint: source.1,
+            user_pool_token_receipt: user.pool_tokens,
+            pool_mint: self.mint,
+            user: user.keypair.pubkey(),
+            system_program: system_program::id(),
+            token_program: self.pool_mint_program,
+            source_token_program: source.3,
+            config: config_address(),
+        }
+    }
+
+    pub fn deposit_single(
+        &self,
+        user: &TestUser,
+        token_a: bool,
+        source_amount: u64,
+    ) -> Instruction {
+        self.deposit_single_with_minimum(user, token_a, source_amount, 0)
+    }
+
+    /// Single token deposit failing when fewer than `minimum_pool_tokens_out` are minted
+    pub fn deposit_single_with_minimum(
+        &self,
+        user: &TestUser,
+        token_a: bool,
+        source_amount: u64,
+        minimum_pool_tokens_out: u64,
+    ) -> Instruction {
+        instruction(
+            self.deposit_single_accounts(user, token_a),
+            exchange::instruction::DepositSingleToken {
+                source_amount,
+                minimum_pool_tokens_out,
+            },
+        )
+    }
+
+    pub fn withdraw_single(
+        &self,
+        user: &TestUser,
+        token_a: bool,
+        source_amount: u64,
+    ) -> Instruction {
+        self.withdraw_single_with_maximum(user, token_a, source_amount, u64::MAX)
+    }
+
+    /// Single token withdrawal failing when more than `maximum_pool_tokens_burned` are burned
+    pub fn withdraw_single_with_maximum(
+        &self,
+        user: &TestUser,
+        token_a: bool,
+        source_amount: u64,
+        maximum_pool_tokens_burned: u64,
+    ) -> Instruction {
+        let (source, _) = self.sides(user, token_a);
+        instruction(
+            exchange::accounts::WithdrawSingleToken {
+                pool_authority: self.authority,
+                pool: self.pool,
+                pool_token_a_account: self.token_a,
+                pool_token_b_account: self.token_b,
+                user_source_token_account: source.2,
+                source_mint: source.1,
+                user_pool_token_receipt: user.pool_tokens,
+                pool_mint: self.mint,
+                pool_token_fee_account: self.fee_account,
+                user: user.keypair.pubkey(),
+                system_program: system_program::id(),
+                token_program: self.pool_mint_program,
+                source_token_program: source.3,
+                config: config_address(),
+            },
+            exchange::instruction::WithdrawSingleTokenOut {
+                source_amount,
+                maximum_pool_tokens_burned,
+            },
+        )
+    }
+
+    pub fn quote_swap(&self, a_to_b: bool, source_amount: u64) -> Instruction {
+        let (source, destination) = self.vault_sides(a_to_b);
+        instruction(
+            exchange::accounts::QuoteSwap {
+                pool: self.pool,
+                config: config_address(),
+                pool_source_token_account: source.0,
+                pool_destination_token_account: destination.0,
+                source_mint: source.1,
+                destination_mint: destination.1,
+            },
+            exchange::instruction::QuoteSwap { source_amount },
+        )
+    }
+
+    pub fn quote_deposit_single(&self, token_a: bool, source_amount: u64) -> Instruction {
+        let (source, _) = self.vault_sides(token_a);
+        instruction(
+            exchange::accounts::QuoteDepositSingle {
+                pool: self.pool,
+                pool_token_a_account: self.token_a,
+                pool_token_b_account: self.token_b,
+                source_mint: source.1,
+                pool_mint: self.mint,
+                config: config_address(),
+            },
+            exchange::instruction::QuoteDepositSingle { source_amount },
+        )
+    }
+
+    pub fn quote_withdraw_single(&self, token_a: bool, source_amount: u64) -> Instruction {
+        let (source, _) = self.vault_sides(token_a);
+        instruction(
+            exchange::accounts::QuoteWithdrawSingle {
+                pool: self.pool,
+                pool_token_a_account: self.token_a,
+                pool_token_b_account: self.token_b,
+                source_mint: source.1,
+                pool_mint: self.mint,
+                config: config_address(),
+            },
+            exchange::instruction::QuoteWithdrawSingle { source_amount },
+        )
+    }
+
+    /// Swap through the sample program, failing under `minimum_amount_out`
+    pub fn cpi_swap(
+        &self,
+        user: &TestUser,
+        a_to_b: bool,
+        source_amount: u64,
+        minimum_amount_out: u64,
+    ) -> Instruction {
+        let swap = self.swap_accounts(user, a_to_b);
+        Instruction {
+            program_id: exchange_cpi_example::ID,
+            accounts: exchange_cpi_example::accounts::ExchangeSwap {
+                pool_authority: swap.pool_authority,
+                pool: swap.pool,
+                config: swap.config,
+                pool_source_token_account: swap.pool_source_token_account,
+                pool_destination_token_account: swap.pool_destination_token_account,
+                source_mint: swap.source_mint,
+                destination_mint: swap.destination_mint,
+                user_source_token_account: swap.user_source_token_account,
+                user_destination_token_account: swap.user_destination_token_account,
+                pool_mint: swap.pool_mint,
+                pool_token_fee_account: swap.pool_token_fee_account,
+                user: swap.user,
+                creator: swap.creator,
+                system_program: swap.system_program,
+                token_program: swap.token_program,
+                source_token_program: swap.source_token_program,
+                destination_token_program: swap.destination_token_program,
+                exchange_program: exchange::ID,
+            }
+            .to_account_metas(None),
+            data: exchange_cpi_example::instruction::Swap {
+                source_amount,
+                minimum_amount_out,
+            }
+            .data(),
+        }
+    }
+
+    /// Single token deposit through the sample program, failing under `minimum_pool_tokens`
+    pub fn cpi_deposit(
+        &self,
+        user: &TestUser,
+        token_a: bool,
+        source_amount: u64,
+        minimum_pool_tokens: u64,
+    ) -> Instruction {
+        let deposit = self.deposit_single_accounts(user, token_a);
+        Instruction {
+            program_id: exchange_cpi_example::ID,
+            accounts: exchange_cpi_example::accounts::ExchangeDeposit {
+                pool_authority: deposit.pool_authority,
+                pool: deposit.pool,
+                pool_token_a_account: deposit.pool_token_a_account,
+                pool_token_b_account: deposit.pool_token_b_account,
+                user_source_token_account: deposit.user_source_token_account,
+                source_mint: deposit.source_mint,
+                user_pool_token_receipt: deposit.user_pool_token_receipt,
+                pool_mint: deposit.pool_mint,
+                user: deposit.user,
+                system_program: deposit.system_program,
+                token_program: deposit.token_program,
+                source_token_program: deposit.source_token_program,
+                config: deposit.config,
+                exchange_program: exchange::ID,
+            }
+            .to_account_metas(None),
+            data: exchange_cpi_example::instruction::Deposit {
+                source_amount,
+                minimum_pool_tokens,
+            }
+            .data(),
+        }
+    }
+
+    pub fn migrate(&self, payer: &Pubkey) -> Instruction {
+        instruction(
+            exchange::accounts::MigratePool {
+                pool: self.pool,
+                payer: *payer,
+                system_program: system_program::id(),
+            },
+            exchange::instruction::MigratePool {},
+        )
+    }
+
+    pub fn collect_protocol_fees(
+        &self,
+        treasury: &Pubkey,
+        treasury_token_a_account: &Pubkey,
+        treasury_token_b_account: &Pubkey,
+    ) -> Instruction {
+        instruction(
+            exchange::accounts::CollectProtocolFees {
+                config: config_address(),
+                pool_authority: self.authority,
+                pool: self.pool,
+                pool_token_a_account: self.token_a,
+                pool_token_b_account: self.token_b,
+                token_a_mint: self.token_a_mint,
+                token_b_mint: self.token_b_mint,
+                treasury_token_a_account: *treasury_token_a_account,
+                treasury_token_b_account: *treasury_token_b_account,
+                treasury: *treasury,
+                token_a_program: self.token_a_program,
+                token_b_program: self.token_b_program,
+            },
+            exchange::instruction::CollectProtocolFees {},
+        )
+    }
+
+    pub fn close_accounts(
+        &self,
+        authority: &Pubkey,
+        receiver: &Pubkey,
+        receiver_token_a_account: &Pubkey,
+        receiver_token_b_account: &Pubkey,
+    ) -> exchange::accounts::ClosePool {
+        exchange::accounts::ClosePool {
+            config: config_address(),
+            pool_authority: self.authority,
+            pool: self.pool,
+            registry: Some(self.registry),
+            pool_token_a_account: self.token_a,
+            pool_token_b_account: self.token_b,
+            token_a_mint: self.token_a_mint,
+            token_b_mint: self.token_b_mint,
+            pool_mint: self.mint,
+            pool_token_fee_account: self.fee_account,
+            locked_liquidity: Some(self.locked_liquidity),
+            receiver_token_a_account: *receiver_token_a_account,
+            receiver_token_b_account: *receiver_token_b_account,
+            receiver: *receiver,
+            authority: *authority,
+            token_program: self.pool_mint_program,
+            token_a_program: self.token_a_program,
+            token_b_program: self.token_b_program,
+        }
+    }
+
+    pub fn close(
+        &self,
+        authority: &Pubkey,
+        receiver: &Pubkey,
+        receiver_token_a_account: &Pubkey,
+        receiver_token_b_account: &Pubkey,
+    ) -> Instruction {
+        instruction(
+            self.close_accounts(
+                authority,
+                receiver,
+                receiver_token_a_account,
+                receiver_token_b_account,
+            ),
+            exchange::instruction::ClosePool {},
+        )
+    }
+
+    /// (vault, mint) of the token A and token B sides, swapped when `token_a` is false
+    fn vault_sides(&self, token_a: bool) -> ((Pubkey, Pubkey), (Pubkey, Pubkey)) {
+        let a = (self.token_a, self.token_a_mint);
+        let b = (self.token_b, self.token_b_mint);
+        if token_a {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+
+    /// Token A and token B sides, swapped when `token_a` is false
+    fn sides(&self, user: &TestUser, token_a: bool) -> (Side, Side) {
+        let a = (
+            self.token_a,
+            self.token_a_mint,
+            user.token_a,
+            self.token_a_program,
+        );
+        let b = (
+            self.token_b,
+            self.token_b_mint,
+            user.token_b,
+            self.token_b_program,
+        );
+        if token_a {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+}
+
+/// Instruction returning the deepest of `pools` in the registry
+pub fn find_best_pool(registry: &Pubkey, pools: &[&TestPool]) -> Instruction {
+    let mut find_best_pool = instruction(
+        exchange::accounts::FindBestPool {
+            registry: *registry,
+        },
+        exchange::instruction::FindBestPool {},
+    );
+    for pool in pools {
+        find_best_pool.accounts.extend([
+            solana_sdk::instruction::AccountMeta::new_readonly(pool.pool, false),
+            solana_sdk::instruction::AccountMeta::new_readonly(pool.token_a, false),
+            solana_sdk::instruction::AccountMeta::new_readonly(pool.token_b, false),
+        ]);
+    }
+    find_best_pool
+}
+
+/// Runtime with the exchange program deployed through the upgradeable loader
+pub struct TestExchange {
+    pub context: ProgramTestContext,
+    /// Upgrade authority of the program and admin of the config
+    pub admin: Keypair,
+}
+
+impl TestExchange {
+    /// Starts the runtime without the config
+    pub async fn start_without_config() -> Self {
+        let admin = Keypair::new();
+        let mut program_test = ProgramTest::default();
+        program_test.prefer_bpf(false);
+        program_test.add_program("exchange", exchange::ID, solana_program_test::processor!(native_exchange));
+        program_test.add_program("exchange_cpi_example", exchange_cpi_example::ID, solana_program_test::processor!(native_example));
+
+        // the config checks the upgrade authority, so the program needs its program data
+        let program_file = find_file("exchange_dummy.so").unwrap_or_else(|| {
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../../../target/deploy/exchange.so")
+        });
+        let mut program_data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: Some(admin.pubkey()),
+        })
+        .unwrap();
+        program_data.extend_from_slice(&std::fs::read(program_file).unwrap_or_default());
+        let program = bincode::serialize(&UpgradeableLoaderState::Program {
+            programdata_address: program_data_address(),
+        })
+        .unwrap();
+
+        let rent = Rent::default();
+        program_test.add_account(
+            program_data_address(),
+            Account {
+                lamports: rent.minimum_balance(program_data.len()),
+                data: program_data,
+                owner: bpf_loader_upgradeable::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+        let _ = program;
+        program_test.add_account(
+            admin.pubkey(),
+            Account {
+                lamports: 1_000 * LAMPORTS_PER_SOL,
+                ..Account::default()
+            },
+        );
+
+        let context = program_test.start_with_context().await;
+        Self { context, admin }
+    }
+
+    /// Starts the runtime with the config created and fee tier 0 set to [`default_fees`]
+    pub async fn start() -> Self {
+        let mut exchange = Self::start_without_config().await;
+        let admin = exchange.admin.insecure_clone();
+        exchange
+            .process(
+                &[
+                    initialize_config(&admin.pubkey()),
+                    add_fee_tier(&admin.pubkey(), default_fees(), None),
+                ],
+                &[&admin],
+            )
+            .await
+            .unwrap();
+
+        exchange
+    }
+
+    /// Sends `instructions` paid by the payer, with room for the compute of `initialize`
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+
+        let mut all_instructions =
+            vec![ComputeBudgetInstruction::set_compute_unit_limit(1_400_000)];
+        all_instructions.extend_from_slice(instructions);
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend(
+            signers
+                .iter()
+                .filter(|signer| signer.pubkey() != self.context.payer.pubkey()),
+        );
+
+        let transaction = Transaction::new_signed_with_payer(
+            &all_instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    /// Compute units consumed by `instructions`, the 150 of the compute budget instruction included
+    pub async fn compute_units(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<u64, BanksClientError> {
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let mut all_instructions =
+            vec![ComputeBudgetInstruction::set_compute_unit_limit(1_400_000)];
+        all_instructions.extend_from_slice(instructions);
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend(
+            signers
+                .iter()
+                .filter(|signer| signer.pubkey() != self.context.payer.pubkey()),
+        );
+        let transaction = Transaction::new_signed_with_payer(
+            &all_instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+
+        let result = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await?;
+        result.result?;
+
+        Ok(result
+            .metadata
+            .map_or(0, |metadata| metadata.compute_units_consumed))
+    }
+
+    /// Pool returned by `find_best_pool`
+    pub async fn find_best_pool(
+        &mut self,
+        registry: &Pubkey,
+        pools: &[&TestPool],
+    ) -> Result<Pubkey, BanksClientError> {
+        let return_data = self
+            .return_data(find_best_pool(registry, pools))
+            .await?
+            .expect("find_best_pool returns the pool");
+        Ok(Pubkey::try_from(return_data.as_slice()).unwrap())
+    }
+
+    /// Return data of `quote_swap`, `quote_deposit_single` or `quote_withdraw_single`
+    pub async fn quote<T: AnchorDeserialize>(
+        &mut self,
+        quote: Instruction,
+    ) -> Result<T, BanksClientError> {
+        // the runtime drops trailing zeros of the return data, quotes are plain u64 fields
+        let mut return_data = self.return_data(quote).await?.unwrap_or_default();
+        return_data.resize(std::mem::size_of::<T>(), 0);
+        Ok(T::try_from_slice(&return_data).unwrap())
+    }
+
+    /// Simulates `instruction` alone, paid by the payer, and returns its return data
+    ///
+    /// Nothing is committed, so the same read-only instruction can be sent again.
+    pub async fn return_data(
+        &mut self,
+        instruction: Instruction,
+    ) -> Result<Option<Vec<u8>>, BanksClientError> {
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.context.payer.pubkey()),
+            &[&self.context.payer],
+            blockhash,
+        );
+
+        let result = self
+            .context
+            .banks_client
+            .simulate_transaction(transaction)
+            .await?;
+        if let Some(result) = result.result {
+            result?;
+        }
+
+        Ok(result
+            .simulation_details
+            .and_then(|details| details.return_data)
+            .map(|return_data| return_data.data))
+    }
+
+    /// Waits for a new blockhash, so that repeating a transaction doesn't deduplicate it
+    pub async fn refresh_blockhash(&mut self) {
+        self.context.last_blockhash = self
+            .context
+            .banks_client
+            .get_latest_blockhash()
+            .await
+            .unwrap();
+        self.context.get_new_latest_blockhash().await.unwrap();
+    }
+
+    /// Moves the clock `seconds` forward, for the dynamic fees to decay
+    pub async fn advance_clock(&mut self, seconds: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn account(&mut self, address: &Pubkey) -> Option<Account> {
+        self.context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+    }
+
+    /// Rewrites `pool` in the Borsh layout deployed before it was versioned, rent included
+    pub async fn unversion_pool(&mut self, pool: &Pubkey) {
+        let mut account = self.account(pool).await.expect("pool exists");
+        let state = Pool::try_deserialize(&mut account.data.as_slice()).unwrap();
+        let legacy = LegacyPool {
+            bump: state.bump,
+            token_a: state.token_a,
+            token_b: state.token_b,
+            token_a_mint: state.token_a_mint,
+            token_b_mint: state.token_b_mint,
+            mint: state.mint,
+            creator: state.creator,
+            fee_account: state.fee_account,
+            fees: state.fees,
+            fee_tier: state.fee_tier,
+            protocol_fees_token_a: state.protocol_fees_token_a,
+            protocol_fees_token_b: state.protocol_fees_token_b,
+            dynamic_fee: LegacyDynamicFee {
+                enabled: state.dynamic_fee.is_enabled(),
+                config: state.dynamic_fee.config,
+                volatility_accumulator: state.dynamic_fee.volatility_reference,
+                last_update_timestamp: state.dynamic_fee.reference_timestamp,
+            },
+            transfer_hook_programs: state.transfer_hook_programs().to_vec(),
+            layout: PoolLayout::default(),
+        };
+
+        // the layout was the last field, older pools end right before it
+        let mut data = Pool::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        data.truncate(data.len() - PoolLayout::MAX_SIZE);
+        data.resize(LegacyPool::UNVERSIONED_SIZE, 0);
+        account.lamports = Rent::default().minimum_balance(data.len());
+        account.data = data;
+        self.context.set_account(pool, &account.into());
+    }
+
+    pub async fn anchor_account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let account = self.account(address).await.expect("account exists");
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn token_amount(&mut self, address: &Pubkey) -> u64 {
+        let account = self.account(address).await.expect("token account exists");
+        StateWithExtensions::<TokenAccount>::unpack(&account.data)
+            .unwrap()
+            .base
+            .amount
+    }
+
+    pub async fn mint_supply(&mut self, address: &Pubkey) -> u64 {
+        let account = self.account(address).await.expect("mint exists");
+        StateWithExtensions::<Mint>::unpack(&account.data)
+            .unwrap()
+            .base
+            .supply
+    }
+
+    /// New keypair holding 10 SOL
+    pub async fn create_funded_keypair(&mut self) -> Keypair {
+        let keypair = Keypair::new();
+        let transfer = system_instruction::transfer(
+            &self.context.payer.pubkey(),
+            &keypair.pubkey(),
+            10 * LAMPORTS_PER_SOL,
+        );
+        self.process(&[transfer], &[]).await.unwrap();
+
+        keypair
+    }
+
+    /// Creates a mint of `token_program` with the payer as mint and freeze
+    /// authority, `extensions` initialize the `extension_types` before the mint
+    pub async fn create_mint_with_extensions(
+        &mut self,
+        mint: &Keypair,
+        token_program: &Pubkey,
+        extension_types: &[ExtensionType],
+        extensions: Vec<Instruction>,
+    ) -> Pubkey {
+        let payer = self.context.payer.pubkey();
+        let space = ExtensionType::try_calculate_account_len::<Mint>(extension_types).unwrap();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+
+        let mut instructions = vec![system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            token_program,
+        )];
+        instructions.extend(extensions);
+        instructions.push(
+            spl_token_2022::instruction::initialize_mint2(
+                token_program,
+                &mint.pubkey(),
+                &payer,
+                Some(&payer),
+                6,
+            )
+            .unwrap(),
+        );
+        self.process(&instructions, &[mint]).await.unwrap();
+
+        mint.pubkey()
+    }
+
+    /// Two plain mints of `token_program` in canonical order
+    pub async fn create_mint_pair(&mut self, token_program: &Pubkey) -> (Pubkey, Pubkey) {
+        let mut mints = [Keypair::new(), Keypair::new()];
+        mints.sort_by_key(|mint| mint.pubkey());
+        let [mint_a, mint_b] = mints;
+
+        (
+            self.create_mint_with_extensions(&mint_a, token_program, &[], vec![])
+                .await,
+            self.create_mint_with_extensions(&mint_b, token_program, &[], vec![])
+                .await,
+        )
+    }
+
+    /// Associated token account of `owner`, funded with `amount` by the mint authority
+    pub async fn create_token_account(
+        &mut self,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        token_program: &Pubkey,
+        amount: u64,
+    ) -> Pubkey {
+        let payer = self.context.payer.pubkey();
+        let address = get_associated_token_address_with_program_id(owner, mint, token_program);
+
+        let mut instructions = vec![
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &payer,
+                owner,
+                mint,
+                token_program,
+            ),
+        ];
+        if amount > 0 {
+            instructions.push(
+                spl_token_2022::instruction::mint_to(
+                    token_program,
+                    mint,
+                    &address,
+                    &payer,
+                    &[],
+                    amount,
+                )
+                .unwrap(),
+            );
+        }
+        self.process(&instructions, &[]).await.unwrap();
+
+        address
+    }
+
+    /// Derives the pool of a new creator holding `amount` of both mints, without initializing it
+    pub async fn prepare_pool(
+        &mut self,
+        token_a_mint: &Pubkey,
+        token_a_program: &Pubkey,
+        token_b_mint: &Pubkey,
+        token_b_program: &Pubkey,
+        fee_tier: u16,
+        amount: u64,
+    ) -> TestPool {
+        let creator = self.create_funded_keypair().await;
+        let creator_token_a = self
+            .create_token_account(token_a_mint, &creator.pubkey(), token_a_program, amount)
+            .await;
+        let creator_token_b = self
+            .create_token_account(token_b_mint, &creator.pubkey(), token_b_program, amount)
+            .await;
+
+        let pool = pool_address(token_a_mint, token_b_mint, &creator.pubkey());
+        let authority = pool_authority_address(&pool);
+        let mint = pool_mint_address(&pool);
+
+        TestPool {
+            creator,
+            creator_token_a,
+            creator_token_b,
+            fee_tier,
+            pool,
+            authority,
+            mint,
+            fee_account: get_associated_token_address_with_program_id(
+                &authority,
+                &mint,
+                &spl_token_2022::id(),
+            ),
+            locked_liquidity: locked_liquidity_address(&pool),
+            registry: registry_address(token_a_mint, token_b_mint, fee_tier),
+            token_a_mint: *token_a_mint,
+            token_b_mint: *token_b_mint,
+            token_a: vault_address(&pool, token_a_mint),
+            token_b: vault_address(&pool, token_b_mint),
+            token_a_program: *token_a_program,
+            token_b_program: *token_b_program,
+            pool_mint_program: spl_token_2022::id(),
+        }
+    }
+
+    /// Initializes a pool of the mints on fee tier 0 with the given liquidity
+    pub async fn create_pool_with_mints(
+        &mut self,
+        token_a_mint: &Pubkey,
+        token_b_mint: &Pubkey,
+        token_a_amount: u64,
+        token_b_amount: u64,
+    ) -> TestPool {
+        let pool = self
+            .prepare_pool(
+                token_a_mint,
+                &spl_token::id(),
+                token_b_mint,
+                &spl_token::id(),
+                0,
+                token_a_amount.max(token_b_amount),
+            )
+            .await;
+        self.process(
+            &[pool.initialize(token_a_amount, token_b_amount)],
+            &[&pool.creator],
+        )
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    /// Initializes a pool of two new SPL Token mints with 1_000_000 of each
+    pub async fn create_pool(&mut self) -> TestPool {
+        let (token_a_mint, token_b_mint) = self.create_mint_pair(&spl_token::id()).await;
+        self.create_pool_with_mints(&token_a_mint, &token_b_mint, 1_000_000, 1_000_000)
+            .await
+    }
+
+    /// Like [`TestExchange::create_pool`], on a new fee tier with `dynamic_fee`
+    pub async fn create_pool_with_dynamic_fee(
+        &mut self,
+        dynamic_fee: DynamicFeeConfig,
+    ) -> TestPool {
+        let admin = self.admin.insecure_clone();
+        self.process(
+            &[add_fee_tier(&admin.pubkey(), default_fees(), Some(dynamic_fee))],
+            &[&admin],
+        )
+        .await
+        .unwrap();
+        let config: Config = self.anchor_account(&config_address()).await;
+        let fee_tier = (config.fee_tiers.len() - 1) as u16;
+
+        let (token_a_mint, token_b_mint) = self.create_mint_pair(&spl_token::id()).await;
+        let pool = self
+            .prepare_pool(
+                &token_a_mint,
+                &spl_token::id(),
+                &token_b_mint,
+                &spl_token::id(),
+                fee_tier,
+                1_000_000,
+            )
+            .await;
+        self.process(&[pool.initialize(1_000_000, 1_000_000)], &[&pool.creator])
+            .await
+            .unwrap();
+
+        pool
+    }
+
+    /// Pool as the first deployment created it: SPL Token mints out of canonical
+    /// order, SPL Token pool mint of 9 decimals, vaults and fee account owned by
+    /// the pool authority, no registry and no locked liquidity, every pool token
+    /// held by the creator and the pool in the 313 byte layout
+    pub async fn create_baseline_pool(&mut self) -> TestPool {
+        const INITIAL_POOL_TOKEN_SUPPLY: u64 = 1_000_000_000;
+        let (token_b_mint, token_a_mint) = self.create_mint_pair(&spl_token::id()).await;
+        let creator = self.create_funded_keypair().await;
+        let (pool, bump) = Pubkey::find_program_address(
+            &[
+                PREFIX,
+                token_a_mint.as_ref(),
+                token_b_mint.as_ref(),
+                creator.pubkey().as_ref(),
+            ],
+            &exchange::ID,
+        );
+        let authority = pool_authority_address(&pool);
+
+        let creator_token_a = self
+            .create_token_account(&token_a_mint, &creator.pubkey(), &spl_token::id(), 0)
+            .await;
+        let creator_token_b = self
+            .create_token_account(&token_b_mint, &creator.pubkey(), &spl_token::id(), 0)
+            .await;
+        let token_a = self
+            .create_token_account(&token_a_mint, &authority, &spl_token::id(), 1_000_000)
+            .await;
+        let token_b = self
+            .create_token_account(&token_b_mint, &authority, &spl_token::id(), 1_000_000)
+            .await;
+
+        // minted by the payer, then handed over to the pool authority as initialize did
+        let mint = Keypair::new();
+        let payer = self.context.payer.pubkey();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        self.process(
+            &[
+                system_instruction::create_account(
+                    &payer,
+                    &mint.pubkey(),
+                    rent.minimum_balance(spl_token::state::Mint::LEN),
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::id(),
+                ),
+                spl_token::instruction::initialize_mint2(
+                    &spl_token::id(),
+                    &mint.pubkey(),
+                    &payer,
+                    Some(&payer),
+                    9,
+                )
+                .unwrap(),
+            ],
+            &[&mint],
+        )
+        .await
+        .unwrap();
+        let mint = mint.pubkey();
+        self.create_token_account(
+            &mint,
+            &creator.pubkey(),
+            &spl_token::id(),
+            INITIAL_POOL_TOKEN_SUPPLY,
+        )
+        .await;
+        let fee_account = self
+            .create_token_account(&mint, &authority, &spl_token::id(), 0)
+            .await;
+        let set_authority = |authority_type| {
+            spl_token::instruction::set_authority(
+                &spl_token::id(),
+                &mint,
+                Some(&authority),
+                authority_type,
+                &payer,
+                &[],
+            )
+            .unwrap()
+        };
+        self.process(
+            &[
+                set_authority(spl_token::instruction::AuthorityType::MintTokens),
+                set_authority(spl_token::instruction::AuthorityType::FreezeAccount),
+            ],
+            &[],
+        )
+        .await
+        .unwrap();
+
+        let baseline = BaselinePool {
+            bump,
+            token_a,
+            token_b,
+            token_a_mint,
+            token_b_mint,
+            mint,
+            creator: creator.pubkey(),
+            fee_account,
+            fees: default_fees(),
+        };
+        let mut data = Pool::DISCRIMINATOR.to_vec();
+        baseline.serialize(&mut data).unwrap();
+        data.resize(LegacyPool::BASELINE_SIZE, 0);
+        let account = Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: exchange::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.context.set_account(&pool, &account.into());
+
+        TestPool {
+            creator,
+            creator_token_a,
+            creator_token_b,
+            fee_tier: 0,
+            pool,
+            authority,
+            mint,
+            fee_account,
+            locked_liquidity: locked_liquidity_address(&pool),
+            registry: registry_address(&token_a_mint, &token_b_mint, 0),
+            token_a_mint,
+            token_b_mint,
+            token_a,
+            token_b,
+            token_a_program: spl_token::id(),
+            token_b_program: spl_token::id(),
+            pool_mint_program: spl_token::id(),
+        }
+    }
+
+    /// New user of `pool` holding the given amounts and an empty pool token account
+    pub async fn create_user(
+        &mut self,
+        pool: &TestPool,
+        token_a_amount: u64,
+        token_b_amount: u64,
+    ) -> TestUser {
+        let keypair = self.create_funded_keypair().await;
+        let token_a = self
+            .create_token_account(
+                &pool.token_a_mint,
+                &keypair.pubkey(),
+                &pool.token_a_program,
+                token_a_amount,
+            )
+            .await;
+        let token_b = self
+            .create_token_account(
+                &pool.token_b_mint,
+                &keypair.pubkey(),
+                &pool.token_b_program,
+                token_b_amount,
+            )
+            .await;
+        let pool_tokens = self
+            .create_token_account(&pool.mint, &keypair.pubkey(), &pool.pool_mint_program, 0)
+            .await;
+
+        TestUser {
+            keypair,
+            token_a,
+            token_b,
+            pool_tokens,
+        }
+    }
+}
+
+fn native_exchange(
+    program_id: &Pubkey,
+    accounts: &[solana_sdk::account_info::AccountInfo],
+    data: &[u8],
+) -> solana_sdk::entrypoint::ProgramResult {
+    let mut accounts = accounts.to_vec();
+    for account in accounts.iter_mut() {
+        if *account.key == exchange::ID {
+            let state = solana_sdk::bpf_loader_upgradeable::UpgradeableLoaderState::Program {
+                programdata_address: program_data_address(),
+            };
+            let bytes: &'static mut [u8] = Box::leak(bincode::serialize(&state).unwrap().into_boxed_slice());
+            let lamports: &'static mut u64 = Box::leak(Box::new(account.lamports()));
+            let key: &'static Pubkey = Box::leak(Box::new(exchange::ID));
+            *account = solana_sdk::account_info::AccountInfo::new(
+                key, false, false, lamports, bytes, &solana_sdk::bpf_loader_upgradeable::ID, true, 0,
+            );
+        }
+    }
+    let accounts = Box::leak(Box::new(accounts));
+    exchange::entry(program_id, accounts, data)
+}
+
+fn native_example(
+    program_id: &Pubkey,
+    accounts: &[solana_sdk::account_info::AccountInfo],
+    data: &[u8],
+) -> solana_sdk::entrypoint::ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    exchange_cpi_example::entry(program_id, accounts, data)
+}
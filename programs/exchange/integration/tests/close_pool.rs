@@ -0,0 +1,305 @@
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use exchange::errors::ExchangeError;
+use exchange::state::{Pool, PoolRegistry};
+use exchange_integration::*;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// Burns every pool token of the creator, leaving only those held by the pool
+async fn burn_creator_pool_tokens(exchange: &mut TestExchange, pool: &TestPool) {
+    let amount = exchange.token_amount(&pool.creator_pool_tokens()).await;
+    exchange
+        .process(
+            &[spl_token_2022::instruction::burn(
+                &spl_token_2022::id(),
+                &pool.creator_pool_tokens(),
+                &pool.mint,
+                &pool.creator.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap()],
+            &[&pool.creator],
+        )
+        .await
+        .unwrap();
+}
+
+/// Token A and B accounts of a new receiver
+async fn create_receiver(
+    exchange: &mut TestExchange,
+    pool: &TestPool,
+) -> (Keypair, Pubkey, Pubkey) {
+    let receiver = Keypair::new();
+    let token_a = exchange
+        .create_token_account(
+            &pool.token_a_mint,
+            &receiver.pubkey(),
+            &pool.token_a_program,
+            0,
+        )
+        .await;
+    let token_b = exchange
+        .create_token_account(
+            &pool.token_b_mint,
+            &receiver.pubkey(),
+            &pool.token_b_program,
+            0,
+        )
+        .await;
+    (receiver, token_a, token_b)
+}
+
+#[tokio::test]
+async fn close_empty_pool_returns_rent_and_tokens() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    burn_creator_pool_tokens(&mut exchange, &pool).await;
+    let (receiver, receiver_token_a, receiver_token_b) =
+        create_receiver(&mut exchange, &pool).await;
+
+    let closed = [
+        pool.pool,
+        pool.token_a,
+        pool.token_b,
+        pool.fee_account,
+        pool.locked_liquidity,
+    ];
+    let mut rent = 0;
+    for address in closed {
+        rent += exchange.account(&address).await.unwrap().lamports;
+    }
+    let token_a_left = exchange.token_amount(&pool.token_a).await;
+    let token_b_left = exchange.token_amount(&pool.token_b).await;
+
+    exchange
+        .process(
+            &[pool.close(
+                &pool.creator.pubkey(),
+                &receiver.pubkey(),
+                &receiver_token_a,
+                &receiver_token_b,
+            )],
+            &[&pool.creator],
+        )
+        .await
+        .unwrap();
+
+    for address in closed {
+        assert!(exchange.account(&address).await.is_none());
+    }
+    assert_eq!(
+        exchange.account(&receiver.pubkey()).await.unwrap().lamports,
+        rent
+    );
+    assert_eq!(exchange.token_amount(&receiver_token_a).await, token_a_left);
+    assert_eq!(exchange.token_amount(&receiver_token_b).await, token_b_left);
+    assert_eq!(exchange.mint_supply(&pool.mint).await, 0);
+
+    let registry: PoolRegistry = exchange.anchor_account(&pool.registry).await;
+    assert!(!registry.pools.contains(&pool.pool));
+}
+
+#[tokio::test]
+async fn close_pool_as_admin_burns_the_owner_fees() {
+    let mut exchange = TestExchange::start().await;
+    let admin = exchange.admin.insecure_clone();
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 100_000, 0).await;
+    exchange
+        .process(&[pool.swap(&user, true, 100_000)], &[&user.keypair])
+        .await
+        .unwrap();
+    assert!(exchange.token_amount(&pool.fee_account).await > 0);
+    burn_creator_pool_tokens(&mut exchange, &pool).await;
+    let (receiver, receiver_token_a, receiver_token_b) =
+        create_receiver(&mut exchange, &pool).await;
+
+    exchange
+        .process(
+            &[pool.close(
+                &admin.pubkey(),
+                &receiver.pubkey(),
+                &receiver_token_a,
+                &receiver_token_b,
+            )],
+            &[&admin],
+        )
+        .await
+        .unwrap();
+
+    assert!(exchange.account(&pool.pool).await.is_none());
+    assert_eq!(exchange.mint_supply(&pool.mint).await, 0);
+}
+
+#[tokio::test]
+async fn close_pool_with_liquidity_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let (receiver, receiver_token_a, receiver_token_b) =
+        create_receiver(&mut exchange, &pool).await;
+
+    let result = exchange
+        .process(
+            &[pool.close(
+                &pool.creator.pubkey(),
+                &receiver.pubkey(),
+                &receiver_token_a,
+                &receiver_token_b,
+            )],
+            &[&pool.creator],
+        )
+        .await;
+    assert_error(result, ExchangeError::PoolNotEmpty);
+}
+
+#[tokio::test]
+async fn close_pool_by_other_signer_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    burn_creator_pool_tokens(&mut exchange, &pool).await;
+    let other = exchange.create_funded_keypair().await;
+    let (receiver, receiver_token_a, receiver_token_b) =
+        create_receiver(&mut exchange, &pool).await;
+
+    let result = exchange
+        .process(
+            &[pool.close(
+                &other.pubkey(),
+                &receiver.pubkey(),
+                &receiver_token_a,
+                &receiver_token_b,
+            )],
+            &[&other],
+        )
+        .await;
+    assert_error(result, ExchangeError::InvalidAuthority);
+}
+
+#[tokio::test]
+async fn close_pool_with_uncollected_protocol_fees_fails() {
+    let mut exchange = TestExchange::start().await;
+    let admin = exchange.admin.insecure_clone();
+    let treasury = exchange.create_funded_keypair().await;
+    exchange
+        .process(
+            &[set_protocol_fee(&admin.pubkey(), 1, 2, treasury.pubkey())],
+            &[&admin],
+        )
+        .await
+        .unwrap();
+
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 100_000, 0).await;
+    exchange
+        .process(&[pool.swap(&user, true, 100_000)], &[&user.keypair])
+        .await
+        .unwrap();
+    burn_creator_pool_tokens(&mut exchange, &pool).await;
+    let (receiver, receiver_token_a, receiver_token_b) =
+        create_receiver(&mut exchange, &pool).await;
+
+    let result = exchange
+        .process(
+            &[pool.close(
+                &pool.creator.pubkey(),
+                &receiver.pubkey(),
+                &receiver_token_a,
+                &receiver_token_b,
+            )],
+            &[&pool.creator],
+        )
+        .await;
+    assert_error(result, ExchangeError::ProtocolFeesNotCollected);
+
+    let treasury_token_a = exchange
+        .create_token_account(&pool.token_a_mint, &treasury.pubkey(), &spl_token::id(), 0)
+        .await;
+    let treasury_token_b = exchange
+        .create_token_account(&pool.token_b_mint, &treasury.pubkey(), &spl_token::id(), 0)
+        .await;
+    exchange
+        .process(
+            &[
+                pool.collect_protocol_fees(
+                    &treasury.pubkey(),
+                    &treasury_token_a,
+                    &treasury_token_b,
+                ),
+            ],
+            &[&treasury],
+        )
+        .await
+        .unwrap();
+    let state: Pool = exchange.anchor_account(&pool.pool).await;
+    assert_eq!(state.protocol_fees_token_a, 0);
+
+    exchange
+        .process(
+            &[pool.close(
+                &admin.pubkey(),
+                &receiver.pubkey(),
+                &receiver_token_a,
+                &receiver_token_b,
+            )],
+            &[&admin],
+        )
+        .await
+        .unwrap();
+    assert!(exchange.account(&pool.pool).await.is_none());
+}
+
+#[tokio::test]
+async fn close_baseline_pool_without_registry_or_locked_liquidity() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_baseline_pool().await;
+    assert!(pool.token_a_mint > pool.token_b_mint);
+    let payer = exchange.create_funded_keypair().await;
+    exchange
+        .process(&[pool.migrate(&payer.pubkey())], &[&payer])
+        .await
+        .unwrap();
+
+    let amount = exchange.token_amount(&pool.creator_pool_tokens()).await;
+    exchange
+        .process(
+            &[spl_token::instruction::burn(
+                &spl_token::id(),
+                &pool.creator_pool_tokens(),
+                &pool.mint,
+                &pool.creator.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap()],
+            &[&pool.creator],
+        )
+        .await
+        .unwrap();
+    let (receiver, receiver_token_a, receiver_token_b) =
+        create_receiver(&mut exchange, &pool).await;
+
+    let mut accounts = pool.close_accounts(
+        &pool.creator.pubkey(),
+        &receiver.pubkey(),
+        &receiver_token_a,
+        &receiver_token_b,
+    );
+    accounts.registry = None;
+    accounts.locked_liquidity = None;
+    exchange
+        .process(
+            &[instruction(accounts, exchange::instruction::ClosePool {})],
+            &[&pool.creator],
+        )
+        .await
+        .unwrap();
+
+    for address in [pool.pool, pool.token_a, pool.token_b, pool.fee_account] {
+        assert!(exchange.account(&address).await.is_none());
+    }
+    assert_eq!(exchange.token_amount(&receiver_token_a).await, 1_000_000);
+    assert_eq!(exchange.token_amount(&receiver_token_b).await, 1_000_000);
+    assert_eq!(exchange.mint_supply(&pool.mint).await, 0);
+}
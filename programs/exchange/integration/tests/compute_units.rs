@@ -0,0 +1,195 @@
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use exchange_integration::compute_units::ComputeUnits;
+use exchange_integration::*;
+use solana_sdk::signature::Signer;
+
+/// Runs every instruction once and checks its usage against `compute_units.txt`
+#[tokio::test]
+async fn compute_units_within_baseline() {
+    let mut units = ComputeUnits::load();
+    let mut exchange = TestExchange::start().await;
+    let admin = exchange.admin.insecure_clone();
+    let treasury = exchange.create_funded_keypair().await;
+
+    let measured = exchange
+        .compute_units(
+            &[set_protocol_fee(&admin.pubkey(), 1, 2, treasury.pubkey())],
+            &[&admin],
+        )
+        .await
+        .unwrap();
+    units.record("set_protocol_fee", measured);
+    let measured = exchange
+        .compute_units(&[set_host_fee(&admin.pubkey(), 1, 5)], &[&admin])
+        .await
+        .unwrap();
+    units.record("set_host_fee", measured);
+    let measured = exchange
+        .compute_units(
+            &[add_fee_tier(&admin.pubkey(), default_fees(), None)],
+            &[&admin],
+        )
+        .await
+        .unwrap();
+    units.record("add_fee_tier", measured);
+    let measured = exchange
+        .compute_units(&[disable_fee_tier(&admin.pubkey(), 1)], &[&admin])
+        .await
+        .unwrap();
+    units.record("disable_fee_tier", measured);
+
+    let (token_a_mint, token_b_mint) = exchange.create_mint_pair(&spl_token::id()).await;
+    let pool = exchange
+        .prepare_pool(
+            &token_a_mint,
+            &spl_token::id(),
+            &token_b_mint,
+            &spl_token::id(),
+            0,
+            1_000_000,
+        )
+        .await;
+    let measured = exchange
+        .compute_units(&[pool.initialize(1_000_000, 1_000_000)], &[&pool.creator])
+        .await
+        .unwrap();
+    units.record("initialize", measured);
+
+    let user = exchange.create_user(&pool, 100_000, 100_000).await;
+    let measured = exchange
+        .compute_units(&[pool.swap(&user, true, 10_000)], &[&user.keypair])
+        .await
+        .unwrap();
+    units.record("swap_a_to_b", measured);
+    let measured = exchange
+        .compute_units(&[pool.swap(&user, false, 10_000)], &[&user.keypair])
+        .await
+        .unwrap();
+    units.record("swap_b_to_a", measured);
+    // the *_borsh references are these swaps measured on the last build that
+    // deserialized the pool with Borsh, the zero-copy pool must stay cheaper
+    units.cheaper_than("swap_a_to_b", "swap_a_to_b_borsh");
+    units.cheaper_than("swap_b_to_a", "swap_b_to_a_borsh");
+    let measured = exchange
+        .compute_units(
+            &[pool.deposit_all(&user, 10_000, 20_000, 20_000)],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+    units.record("deposit_all_tokens_in", measured);
+    // both single token paths go through the U256 `integer_sqrt`
+    let measured = exchange
+        .compute_units(
+            &[pool.deposit_single(&user, true, 10_000)],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+    units.record("deposit_single_token", measured);
+    let measured = exchange
+        .compute_units(
+            &[pool.withdraw_single(&user, false, 1_000)],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+    units.record("withdraw_single_token_out", measured);
+
+    let measured = exchange
+        .compute_units(&[pool.quote_swap(true, 10_000)], &[])
+        .await
+        .unwrap();
+    units.record("quote_swap", measured);
+    let measured = exchange
+        .compute_units(&[pool.quote_deposit_single(true, 10_000)], &[])
+        .await
+        .unwrap();
+    units.record("quote_deposit_single", measured);
+    let measured = exchange
+        .compute_units(&[pool.quote_withdraw_single(false, 1_000)], &[])
+        .await
+        .unwrap();
+    units.record("quote_withdraw_single", measured);
+    let measured = exchange
+        .compute_units(&[find_best_pool(&pool.registry, &[&pool])], &[])
+        .await
+        .unwrap();
+    units.record("find_best_pool", measured);
+
+    let treasury_token_a = exchange
+        .create_token_account(&token_a_mint, &treasury.pubkey(), &spl_token::id(), 0)
+        .await;
+    let treasury_token_b = exchange
+        .create_token_account(&token_b_mint, &treasury.pubkey(), &spl_token::id(), 0)
+        .await;
+    let measured =
+        exchange
+            .compute_units(
+                &[pool.collect_protocol_fees(
+                    &treasury.pubkey(),
+                    &treasury_token_a,
+                    &treasury_token_b,
+                )],
+                &[&treasury],
+            )
+            .await
+            .unwrap();
+    units.record("collect_protocol_fees", measured);
+
+    exchange.unversion_pool(&pool.pool).await;
+    let payer = exchange.create_funded_keypair().await;
+    let measured = exchange
+        .compute_units(&[pool.migrate(&payer.pubkey())], &[&payer])
+        .await
+        .unwrap();
+    units.record("migrate_pool", measured);
+
+    let measured = exchange
+        .compute_units(
+            &[unregister_pool(&admin.pubkey(), &pool.registry, &pool.pool)],
+            &[&admin],
+        )
+        .await
+        .unwrap();
+    units.record("unregister_pool", measured);
+
+    // only the pool tokens held by the pool may be left for it to close
+    for (owner, pool_tokens) in [
+        (&pool.creator, pool.creator_pool_tokens()),
+        (&user.keypair, user.pool_tokens),
+    ] {
+        let amount = exchange.token_amount(&pool_tokens).await;
+        exchange
+            .process(
+                &[spl_token_2022::instruction::burn(
+                    &spl_token_2022::id(),
+                    &pool_tokens,
+                    &pool.mint,
+                    &owner.pubkey(),
+                    &[],
+                    amount,
+                )
+                .unwrap()],
+                &[owner],
+            )
+            .await
+            .unwrap();
+    }
+    let measured = exchange
+        .compute_units(
+            &[pool.close(
+                &admin.pubkey(),
+                &treasury.pubkey(),
+                &treasury_token_a,
+                &treasury_token_b,
+            )],
+            &[&admin],
+        )
+        .await
+        .unwrap();
+    units.record("close_pool", measured);
+
+    units.finish();
+}
@@ -0,0 +1,218 @@
+use exchange::errors::ExchangeError;
+use exchange::state::{Config, DynamicFeeConfig};
+use exchange_integration::*;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+#[tokio::test]
+async fn initialize_config_by_upgrade_authority() {
+    let mut exchange = TestExchange::start_without_config().await;
+    let admin = exchange.admin.insecure_clone();
+
+    exchange
+        .process(&[initialize_config(&admin.pubkey())], &[&admin])
+        .await
+        .unwrap();
+
+    let config: Config = exchange.anchor_account(&config_address()).await;
+    assert_eq!(config.admin, admin.pubkey());
+    assert_eq!(config.treasury, admin.pubkey());
+    assert_eq!(config.protocol_fee_numerator, 0);
+    assert_eq!(config.host_fee_numerator, 0);
+    assert!(config.fee_tiers.is_empty());
+}
+
+#[tokio::test]
+async fn initialize_config_by_other_fails() {
+    let mut exchange = TestExchange::start_without_config().await;
+    let other = exchange.create_funded_keypair().await;
+
+    let result = exchange
+        .process(&[initialize_config(&other.pubkey())], &[&other])
+        .await;
+    assert_error(result, ExchangeError::InvalidAuthority);
+}
+
+#[tokio::test]
+async fn add_and_disable_fee_tier() {
+    let mut exchange = TestExchange::start().await;
+    let admin = exchange.admin.insecure_clone();
+
+    let mut fees = default_fees();
+    fees.trade_fee_numerator = 100;
+    let dynamic_fee = DynamicFeeConfig {
+        min_fee_numerator: 10,
+        max_fee_numerator: 100,
+        max_volatility: 1_000,
+        decay_period: 600,
+    };
+    exchange
+        .process(
+            &[add_fee_tier(&admin.pubkey(), fees, Some(dynamic_fee))],
+            &[&admin],
+        )
+        .await
+        .unwrap();
+
+    let config: Config = exchange.anchor_account(&config_address()).await;
+    assert_eq!(config.fee_tiers.len(), 2);
+    assert_eq!(config.fee_tiers[1].fees.trade_fee_numerator, 100);
+    assert!(config.fee_tiers[1].enabled);
+    assert!(config.fee_tiers[1].dynamic_fee.is_some());
+
+    exchange
+        .process(&[disable_fee_tier(&admin.pubkey(), 1)], &[&admin])
+        .await
+        .unwrap();
+
+    let config: Config = exchange.anchor_account(&config_address()).await;
+    assert!(config.fee_tiers[0].enabled);
+    assert!(!config.fee_tiers[1].enabled);
+}
+
+#[tokio::test]
+async fn add_fee_tier_by_other_fails() {
+    let mut exchange = TestExchange::start().await;
+    let other = exchange.create_funded_keypair().await;
+
+    let result = exchange
+        .process(
+            &[add_fee_tier(&other.pubkey(), default_fees(), None)],
+            &[&other],
+        )
+        .await;
+    assert_error(result, ExchangeError::InvalidAuthority);
+}
+
+#[tokio::test]
+async fn add_invalid_fee_tier_fails() {
+    let mut exchange = TestExchange::start().await;
+    let admin = exchange.admin.insecure_clone();
+
+    let mut fees = default_fees();
+    fees.trade_fee_numerator = fees.trade_fee_denominator;
+    let result = exchange
+        .process(&[add_fee_tier(&admin.pubkey(), fees, None)], &[&admin])
+        .await;
+    assert_error(result, ExchangeError::InvalidFee);
+
+    // the dynamic fee can't charge more than the whole trade
+    let dynamic_fee = DynamicFeeConfig {
+        min_fee_numerator: 10,
+        max_fee_numerator: FEE_DENOMINATOR,
+        max_volatility: 1_000,
+        decay_period: 600,
+    };
+    let result = exchange
+        .process(
+            &[add_fee_tier(
+                &admin.pubkey(),
+                default_fees(),
+                Some(dynamic_fee),
+            )],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, ExchangeError::InvalidFee);
+}
+
+#[tokio::test]
+async fn add_fee_tier_past_max_fails() {
+    let mut exchange = TestExchange::start().await;
+    let admin = exchange.admin.insecure_clone();
+
+    // tier 0 is added on start
+    for trade_fee_numerator in 1..Config::MAX_FEE_TIERS as u64 {
+        let mut fees = default_fees();
+        fees.trade_fee_numerator = trade_fee_numerator;
+        exchange
+            .process(&[add_fee_tier(&admin.pubkey(), fees, None)], &[&admin])
+            .await
+            .unwrap();
+    }
+
+    let result = exchange
+        .process(
+            &[add_fee_tier(&admin.pubkey(), default_fees(), None)],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, ExchangeError::FeeTiersFull);
+}
+
+#[tokio::test]
+async fn disable_fee_tier_fails() {
+    let mut exchange = TestExchange::start().await;
+    let admin = exchange.admin.insecure_clone();
+    let other = exchange.create_funded_keypair().await;
+
+    let result = exchange
+        .process(&[disable_fee_tier(&admin.pubkey(), 1)], &[&admin])
+        .await;
+    assert_error(result, ExchangeError::InvalidFeeTier);
+
+    let result = exchange
+        .process(&[disable_fee_tier(&other.pubkey(), 0)], &[&other])
+        .await;
+    assert_error(result, ExchangeError::InvalidAuthority);
+}
+
+#[tokio::test]
+async fn set_protocol_and_host_fees() {
+    let mut exchange = TestExchange::start().await;
+    let admin = exchange.admin.insecure_clone();
+    let treasury = Pubkey::new_unique();
+
+    exchange
+        .process(
+            &[
+                set_protocol_fee(&admin.pubkey(), 1, 5, treasury),
+                set_host_fee(&admin.pubkey(), 1, 4),
+            ],
+            &[&admin],
+        )
+        .await
+        .unwrap();
+
+    let config: Config = exchange.anchor_account(&config_address()).await;
+    assert_eq!(config.treasury, treasury);
+    assert_eq!(config.protocol_fee_numerator, 1);
+    assert_eq!(config.protocol_fee_denominator, 5);
+    assert_eq!(config.host_fee_numerator, 1);
+    assert_eq!(config.host_fee_denominator, 4);
+}
+
+#[tokio::test]
+async fn set_invalid_protocol_and_host_fees_fails() {
+    let mut exchange = TestExchange::start().await;
+    let admin = exchange.admin.insecure_clone();
+    let other = Keypair::new();
+
+    let result = exchange
+        .process(
+            &[set_protocol_fee(&admin.pubkey(), 1, 0, admin.pubkey())],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, ExchangeError::InvalidFee);
+
+    let result = exchange
+        .process(&[set_host_fee(&admin.pubkey(), 5, 4)], &[&admin])
+        .await;
+    assert_error(result, ExchangeError::InvalidFee);
+
+    let result = exchange
+        .process(
+            &[set_protocol_fee(&other.pubkey(), 1, 5, other.pubkey())],
+            &[&other],
+        )
+        .await;
+    assert_error(result, ExchangeError::InvalidAuthority);
+
+    let result = exchange
+        .process(&[set_host_fee(&other.pubkey(), 1, 4)], &[&other])
+        .await;
+    assert_error(result, ExchangeError::InvalidAuthority);
+}
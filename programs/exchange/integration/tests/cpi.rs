@@ -0,0 +1,85 @@
+use exchange::errors::ExchangeError;
+use exchange::quote::{LiquidityQuote, SwapQuote};
+use exchange_integration::*;
+
+#[tokio::test]
+async fn swap_through_cpi() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 0).await;
+
+    let quote: SwapQuote = exchange.quote(pool.quote_swap(true, 10_000)).await.unwrap();
+    exchange
+        .process(
+            &[pool.cpi_swap(&user, true, 10_000, quote.destination_amount)],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(exchange.token_amount(&user.token_a).await, 0);
+    assert_eq!(
+        exchange.token_amount(&user.token_b).await,
+        quote.destination_amount
+    );
+}
+
+#[tokio::test]
+async fn swap_through_cpi_under_the_minimum_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 0).await;
+
+    let quote: SwapQuote = exchange.quote(pool.quote_swap(true, 10_000)).await.unwrap();
+    let result = exchange
+        .process(
+            &[pool.cpi_swap(&user, true, 10_000, quote.destination_amount + 1)],
+            &[&user.keypair],
+        )
+        .await;
+    assert_error(result, ExchangeError::SlippageExceeded);
+}
+
+#[tokio::test]
+async fn deposit_through_cpi() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 0, 10_000).await;
+
+    let quote: LiquidityQuote = exchange
+        .quote(pool.quote_deposit_single(false, 10_000))
+        .await
+        .unwrap();
+    exchange
+        .process(
+            &[pool.cpi_deposit(&user, false, 10_000, quote.pool_tokens)],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(exchange.token_amount(&user.token_b).await, 0);
+    assert_eq!(
+        exchange.token_amount(&user.pool_tokens).await,
+        quote.pool_tokens
+    );
+}
+
+#[tokio::test]
+async fn deposit_through_cpi_under_the_minimum_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 0, 10_000).await;
+
+    let quote: LiquidityQuote = exchange
+        .quote(pool.quote_deposit_single(false, 10_000))
+        .await
+        .unwrap();
+    let result = exchange
+        .process(
+            &[pool.cpi_deposit(&user, false, 10_000, quote.pool_tokens + 1)],
+            &[&user.keypair],
+        )
+        .await;
+    assert_error(result, ExchangeError::SlippageExceeded);
+}
@@ -0,0 +1,106 @@
+use anchor_spl::token::spl_token;
+use exchange::errors::ExchangeError;
+use exchange_integration::*;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn find_best_pool_picks_the_deepest() {
+    let mut exchange = TestExchange::start().await;
+    let (token_a_mint, token_b_mint) = exchange.create_mint_pair(&spl_token::id()).await;
+    let shallow = exchange
+        .create_pool_with_mints(&token_a_mint, &token_b_mint, 1_000_000, 1_000_000)
+        .await;
+    let deep = exchange
+        .create_pool_with_mints(&token_a_mint, &token_b_mint, 4_000_000, 1_000_000)
+        .await;
+    assert_eq!(shallow.registry, deep.registry);
+
+    let best = exchange
+        .find_best_pool(&deep.registry, &[&shallow, &deep])
+        .await
+        .unwrap();
+    assert_eq!(best, deep.pool);
+
+    // a swap moves liquidity but keeps the deeper pool ahead
+    let user = exchange.create_user(&shallow, 100_000, 0).await;
+    exchange
+        .process(&[shallow.swap(&user, true, 100_000)], &[&user.keypair])
+        .await
+        .unwrap();
+    let best = exchange
+        .find_best_pool(&deep.registry, &[&shallow, &deep])
+        .await
+        .unwrap();
+    assert_eq!(best, deep.pool);
+}
+
+#[tokio::test]
+async fn find_best_pool_of_empty_registry_fails() {
+    let mut exchange = TestExchange::start().await;
+    let admin = exchange.admin.insecure_clone();
+    let pool = exchange.create_pool().await;
+    exchange
+        .process(
+            &[unregister_pool(&admin.pubkey(), &pool.registry, &pool.pool)],
+            &[&admin],
+        )
+        .await
+        .unwrap();
+
+    let result = exchange
+        .find_best_pool(&pool.registry, &[])
+        .await
+        .map(|_| ());
+    assert_error(result, ExchangeError::NoLiquidPool);
+}
+
+#[tokio::test]
+async fn find_best_pool_missing_a_registered_pool_fails() {
+    let mut exchange = TestExchange::start().await;
+    let (token_a_mint, token_b_mint) = exchange.create_mint_pair(&spl_token::id()).await;
+    let shallow = exchange
+        .create_pool_with_mints(&token_a_mint, &token_b_mint, 1_000_000, 1_000_000)
+        .await;
+    let deep = exchange
+        .create_pool_with_mints(&token_a_mint, &token_b_mint, 4_000_000, 1_000_000)
+        .await;
+
+    let result = exchange
+        .find_best_pool(&shallow.registry, &[&shallow])
+        .await
+        .map(|_| ());
+    assert_error(result, ExchangeError::RegisteredPoolMissing);
+
+    // the registry order is part of the check
+    let result = exchange
+        .find_best_pool(&shallow.registry, &[&deep, &shallow])
+        .await
+        .map(|_| ());
+    assert_error(result, ExchangeError::PoolNotRegistered);
+}
+
+#[tokio::test]
+async fn find_best_pool_with_unregistered_pool_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let other_pool = exchange.create_pool().await;
+
+    let result = exchange
+        .find_best_pool(&pool.registry, &[&other_pool])
+        .await
+        .map(|_| ());
+    assert_error(result, ExchangeError::PoolNotRegistered);
+}
+
+#[tokio::test]
+async fn find_best_pool_with_wrong_vaults_fails() {
+    let mut exchange = TestExchange::start().await;
+    let mut pool = exchange.create_pool().await;
+    std::mem::swap(&mut pool.token_a, &mut pool.token_b);
+
+    let result = exchange
+        .find_best_pool(&pool.registry, &[&pool])
+        .await
+        .map(|_| ());
+    assert_error(result, ExchangeError::InvalidPoolTokenAccount);
+}
@@ -0,0 +1,341 @@
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        default_account_state, transfer_hook, BaseStateWithExtensions, ExtensionType,
+        StateWithExtensions,
+    },
+    state::{AccountState, Mint},
+};
+use anchor_spl::token_interface::spl_token_metadata_interface::state::TokenMetadata;
+use exchange::errors::ExchangeError;
+use exchange::state::{Pool, PoolRegistry};
+use exchange_integration::*;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+#[tokio::test]
+async fn initialize_pool() {
+    let mut exchange = TestExchange::start().await;
+    let (token_a_mint, token_b_mint) = exchange.create_mint_pair(&spl_token::id()).await;
+    let pool = exchange
+        .create_pool_with_mints(&token_a_mint, &token_b_mint, 4_000_000, 1_000_000)
+        .await;
+
+    let state: Pool = exchange.anchor_account(&pool.pool).await;
+    assert_eq!(state.token_a, pool.token_a);
+    assert_eq!(state.token_b, pool.token_b);
+    assert_eq!(state.token_a_mint, token_a_mint);
+    assert_eq!(state.token_b_mint, token_b_mint);
+    assert_eq!(state.mint, pool.mint);
+    assert_eq!(state.creator, pool.creator.pubkey());
+    assert_eq!(state.fee_account, pool.fee_account);
+    assert_eq!(state.fee_tier, 0);
+    assert_eq!(
+        state.fees.trade_fee_numerator,
+        default_fees().trade_fee_numerator
+    );
+
+    assert_eq!(exchange.token_amount(&pool.token_a).await, 4_000_000);
+    assert_eq!(exchange.token_amount(&pool.token_b).await, 1_000_000);
+    assert_eq!(exchange.token_amount(&pool.creator_token_a).await, 0);
+
+    // sqrt(4_000_000 * 1_000_000) with the minimum liquidity locked away
+    assert_eq!(exchange.mint_supply(&pool.mint).await, 2_000_000);
+    assert_eq!(
+        exchange.token_amount(&pool.locked_liquidity).await,
+        Pool::MINIMUM_LIQUIDITY
+    );
+    assert_eq!(
+        exchange.token_amount(&pool.creator_pool_tokens()).await,
+        2_000_000 - Pool::MINIMUM_LIQUIDITY
+    );
+
+    let registry: PoolRegistry = exchange.anchor_account(&pool.registry).await;
+    assert_eq!(registry.pools, vec![pool.pool]);
+
+    let mint_account = exchange.account(&pool.mint).await.unwrap();
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_account.data).unwrap();
+    let metadata = mint.get_variable_len_extension::<TokenMetadata>().unwrap();
+    let label = |mint: &Pubkey| mint.to_string()[..4].to_string();
+    assert_eq!(
+        metadata.symbol,
+        format!("{}-{}", label(&token_a_mint), label(&token_b_mint))
+    );
+    assert_eq!(metadata.name, format!("{} LP", metadata.symbol));
+}
+
+#[tokio::test]
+async fn initialize_pool_with_unsorted_mints_fails() {
+    let mut exchange = TestExchange::start().await;
+    let (token_a_mint, token_b_mint) = exchange.create_mint_pair(&spl_token::id()).await;
+    let pool = exchange
+        .prepare_pool(
+            &token_b_mint,
+            &spl_token::id(),
+            &token_a_mint,
+            &spl_token::id(),
+            0,
+            1_000_000,
+        )
+        .await;
+
+    let result = exchange
+        .process(&[pool.initialize(1_000_000, 1_000_000)], &[&pool.creator])
+        .await;
+    assert_error(result, ExchangeError::InvalidMintOrder);
+}
+
+#[tokio::test]
+async fn initialize_pool_without_liquidity_fails() {
+    let mut exchange = TestExchange::start().await;
+    let (token_a_mint, token_b_mint) = exchange.create_mint_pair(&spl_token::id()).await;
+    let pool = exchange
+        .prepare_pool(
+            &token_a_mint,
+            &spl_token::id(),
+            &token_b_mint,
+            &spl_token::id(),
+            0,
+            1_000_000,
+        )
+        .await;
+
+    let result = exchange
+        .process(&[pool.initialize(1_000_000, 0)], &[&pool.creator])
+        .await;
+    assert_error(result, ExchangeError::EmptyPoolTokenAccount);
+
+    // sqrt(1_000 * 1_000) only covers the locked minimum liquidity
+    let result = exchange
+        .process(&[pool.initialize(1_000, 1_000)], &[&pool.creator])
+        .await;
+    assert_error(result, ExchangeError::InsufficientInitialLiquidity);
+}
+
+#[tokio::test]
+async fn initialize_pool_with_unknown_or_disabled_fee_tier_fails() {
+    let mut exchange = TestExchange::start().await;
+    let admin = exchange.admin.insecure_clone();
+    let (token_a_mint, token_b_mint) = exchange.create_mint_pair(&spl_token::id()).await;
+
+    let pool = exchange
+        .prepare_pool(
+            &token_a_mint,
+            &spl_token::id(),
+            &token_b_mint,
+            &spl_token::id(),
+            1,
+            1_000_000,
+        )
+        .await;
+    let result = exchange
+        .process(&[pool.initialize(1_000_000, 1_000_000)], &[&pool.creator])
+        .await;
+    assert_error(result, ExchangeError::InvalidFeeTier);
+
+    exchange
+        .process(&[disable_fee_tier(&admin.pubkey(), 0)], &[&admin])
+        .await
+        .unwrap();
+    let pool = exchange
+        .prepare_pool(
+            &token_a_mint,
+            &spl_token::id(),
+            &token_b_mint,
+            &spl_token::id(),
+            0,
+            1_000_000,
+        )
+        .await;
+    let result = exchange
+        .process(&[pool.initialize(1_000_000, 1_000_000)], &[&pool.creator])
+        .await;
+    assert_error(result, ExchangeError::FeeTierDisabled);
+}
+
+#[tokio::test]
+async fn initialize_pool_with_transfer_hook() {
+    let mut exchange = TestExchange::start().await;
+    let hook_program = Pubkey::new_unique();
+
+    let mut mints = [Keypair::new(), Keypair::new()];
+    mints.sort_by_key(|mint| mint.pubkey());
+    let [hook_mint, plain_mint] = mints;
+    let hook_mint = exchange
+        .create_mint_with_extensions(
+            &hook_mint,
+            &spl_token_2022::id(),
+            &[ExtensionType::TransferHook],
+            vec![transfer_hook::instruction::initialize(
+                &spl_token_2022::id(),
+                &hook_mint.pubkey(),
+                None,
+                Some(hook_program),
+            )
+            .unwrap()],
+        )
+        .await;
+    let plain_mint = exchange
+        .create_mint_with_extensions(&plain_mint, &spl_token::id(), &[], vec![])
+        .await;
+
+    let pool = exchange
+        .prepare_pool(
+            &hook_mint,
+            &spl_token_2022::id(),
+            &plain_mint,
+            &spl_token::id(),
+            0,
+            1_000_000,
+        )
+        .await;
+
+    let result = exchange
+        .process(&[pool.initialize(1_000_000, 1_000_000)], &[&pool.creator])
+        .await;
+    assert_error(result, ExchangeError::TransferHookNotAllowed);
+
+    let too_many_hook_programs = (0..=Pool::MAX_TRANSFER_HOOK_PROGRAMS)
+        .map(|_| hook_program)
+        .collect();
+    let result = exchange
+        .process(
+            &[instruction(
+                pool.initialize_accounts(),
+                exchange::instruction::Initialize {
+                    fee_tier: 0,
+                    transfer_hook_programs: too_many_hook_programs,
+                    initial_token_a_amount: 1_000_000,
+                    initial_token_b_amount: 1_000_000,
+                },
+            )],
+            &[&pool.creator],
+        )
+        .await;
+    assert_error(result, ExchangeError::TooManyTransferHookPrograms);
+}
+
+#[tokio::test]
+async fn initialize_pool_with_frozen_default_account_state_fails() {
+    let mut exchange = TestExchange::start().await;
+
+    let mut mints = [Keypair::new(), Keypair::new()];
+    mints.sort_by_key(|mint| mint.pubkey());
+    let [mint_a, mint_b] = mints;
+    let mut mint_addresses = vec![];
+    for mint in [&mint_a, &mint_b] {
+        let address = exchange
+            .create_mint_with_extensions(
+                mint,
+                &spl_token_2022::id(),
+                &[ExtensionType::DefaultAccountState],
+                vec![
+                    default_account_state::instruction::initialize_default_account_state(
+                        &spl_token_2022::id(),
+                        &mint.pubkey(),
+                        &AccountState::Frozen,
+                    )
+                    .unwrap(),
+                ],
+            )
+            .await;
+        mint_addresses.push(address);
+    }
+
+    // the creator accounts start frozen too, so nothing is minted into them
+    let pool = exchange
+        .prepare_pool(
+            &mint_addresses[0],
+            &spl_token_2022::id(),
+            &mint_addresses[1],
+            &spl_token_2022::id(),
+            0,
+            0,
+        )
+        .await;
+
+    let result = exchange
+        .process(&[pool.initialize(1_000_000, 1_000_000)], &[&pool.creator])
+        .await;
+    assert_error(result, ExchangeError::FrozenPoolTokenAccount);
+}
+
+#[tokio::test]
+async fn initialize_pool_past_registry_capacity_fails() {
+    let mut exchange = TestExchange::start().await;
+    let (token_a_mint, token_b_mint) = exchange.create_mint_pair(&spl_token::id()).await;
+
+    for _ in 0..PoolRegistry::MAX_POOLS {
+        exchange
+            .create_pool_with_mints(&token_a_mint, &token_b_mint, 1_000_000, 1_000_000)
+            .await;
+    }
+
+    let pool = exchange
+        .prepare_pool(
+            &token_a_mint,
+            &spl_token::id(),
+            &token_b_mint,
+            &spl_token::id(),
+            0,
+            1_000_000,
+        )
+        .await;
+    let result = exchange
+        .process(&[pool.initialize(1_000_000, 1_000_000)], &[&pool.creator])
+        .await;
+    assert_error(result, ExchangeError::RegistryFull);
+}
+
+#[tokio::test]
+async fn unregister_pool_frees_registry_capacity() {
+    let mut exchange = TestExchange::start().await;
+    let admin = exchange.admin.insecure_clone();
+    let (token_a_mint, token_b_mint) = exchange.create_mint_pair(&spl_token::id()).await;
+
+    let mut pools = vec![];
+    for _ in 0..PoolRegistry::MAX_POOLS {
+        pools.push(
+            exchange
+                .create_pool_with_mints(&token_a_mint, &token_b_mint, 1_000_000, 1_000_000)
+                .await,
+        );
+    }
+    let dust = &pools[0];
+
+    let other = exchange.create_funded_keypair().await;
+    let result = exchange
+        .process(
+            &[unregister_pool(&other.pubkey(), &dust.registry, &dust.pool)],
+            &[&other],
+        )
+        .await;
+    assert_error(result, ExchangeError::InvalidAuthority);
+
+    exchange
+        .process(
+            &[unregister_pool(&admin.pubkey(), &dust.registry, &dust.pool)],
+            &[&admin],
+        )
+        .await
+        .unwrap();
+    let registry: PoolRegistry = exchange.anchor_account(&dust.registry).await;
+    assert!(!registry.pools.contains(&dust.pool));
+
+    // the same transaction under the same blockhash would only be deduplicated
+    exchange.refresh_blockhash().await;
+    let result = exchange
+        .process(
+            &[unregister_pool(&admin.pubkey(), &dust.registry, &dust.pool)],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, ExchangeError::PoolNotRegistered);
+
+    exchange
+        .create_pool_with_mints(&token_a_mint, &token_b_mint, 1_000_000, 1_000_000)
+        .await;
+}
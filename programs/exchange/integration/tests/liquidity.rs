@@ -0,0 +1,244 @@
+use anchor_spl::token::spl_token;
+use exchange::errors::ExchangeError;
+use exchange::quote::{LiquidityQuote, SwapQuote};
+use exchange_integration::*;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn deposit_all_tokens() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 100_000, 100_000).await;
+
+    // a tenth of the 1_000_000 supply costs a tenth of each reserve
+    exchange
+        .process(
+            &[pool.deposit_all(&user, 100_000, 100_000, 100_000)],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(exchange.token_amount(&user.pool_tokens).await, 100_000);
+    assert_eq!(exchange.token_amount(&user.token_a).await, 0);
+    assert_eq!(exchange.token_amount(&user.token_b).await, 0);
+    assert_eq!(exchange.token_amount(&pool.token_a).await, 1_100_000);
+    assert_eq!(exchange.token_amount(&pool.token_b).await, 1_100_000);
+    assert_eq!(exchange.mint_supply(&pool.mint).await, 1_100_000);
+}
+
+#[tokio::test]
+async fn deposit_all_tokens_past_slippage_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 100_000, 100_000).await;
+
+    let result = exchange
+        .process(
+            &[pool.deposit_all(&user, 100_000, 99_999, 100_000)],
+            &[&user.keypair],
+        )
+        .await;
+    assert_error(result, ExchangeError::SlippageExceeded);
+
+    let result = exchange
+        .process(
+            &[pool.deposit_all(&user, 100_000, 100_000, 99_999)],
+            &[&user.keypair],
+        )
+        .await;
+    assert_error(result, ExchangeError::SlippageExceeded);
+}
+
+#[tokio::test]
+async fn deposit_and_withdraw_single_token() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 0).await;
+
+    exchange
+        .process(
+            &[pool.deposit_single(&user, true, 10_000)],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+
+    // sqrt(1_010_000 / 1_000_000) - 1 of the supply, less than half the deposit
+    let pool_tokens = exchange.token_amount(&user.pool_tokens).await;
+    assert!(pool_tokens > 0 && pool_tokens < 5_000);
+    assert_eq!(exchange.token_amount(&pool.token_a).await, 1_010_000);
+    assert_eq!(exchange.token_amount(&user.token_a).await, 0);
+
+    exchange
+        .process(
+            &[pool.withdraw_single(&user, true, 5_000)],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+
+    let burned = pool_tokens - exchange.token_amount(&user.pool_tokens).await;
+    assert!(burned > 0 && burned <= pool_tokens);
+    assert_eq!(exchange.token_amount(&user.token_a).await, 5_000);
+    assert_eq!(exchange.token_amount(&pool.token_a).await, 1_005_000);
+}
+
+#[tokio::test]
+async fn deposit_then_withdrawal_of_the_other_token_pays_the_swap_fees() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 0).await;
+    let swap: SwapQuote = exchange.quote(pool.quote_swap(true, 10_000)).await.unwrap();
+
+    exchange
+        .process(
+            &[pool.deposit_single(&user, true, 10_000)],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+
+    // going through the pool tokens gets no more than swapping would
+    let result = exchange
+        .process(
+            &[pool.withdraw_single(&user, false, swap.destination_amount + 1)],
+            &[&user.keypair],
+        )
+        .await;
+    assert_error(result, ExchangeError::NotEnoughFunds);
+}
+
+#[tokio::test]
+async fn deposit_single_token_of_another_mint_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 0).await;
+
+    let other_mint = exchange
+        .create_mint_with_extensions(&Keypair::new(), &spl_token::id(), &[], vec![])
+        .await;
+    let other_token = exchange
+        .create_token_account(
+            &other_mint,
+            &user.keypair.pubkey(),
+            &spl_token::id(),
+            10_000,
+        )
+        .await;
+
+    let deposit = instruction(
+        exchange::accounts::DepositSingleToken {
+            user_source_token_account: other_token,
+            source_mint: other_mint,
+            ..pool.deposit_single_accounts(&user, true)
+        },
+        exchange::instruction::DepositSingleToken {
+            source_amount: 10_000,
+            minimum_pool_tokens_out: 0,
+        },
+    );
+    let result = exchange.process(&[deposit], &[&user.keypair]).await;
+    assert_error(result, ExchangeError::InvalidMint);
+}
+
+#[tokio::test]
+async fn deposit_single_token_more_than_balance_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 0).await;
+
+    let result = exchange
+        .process(
+            &[pool.deposit_single(&user, true, 10_001)],
+            &[&user.keypair],
+        )
+        .await;
+    assert_error(result, ExchangeError::NotEnoughFunds);
+}
+
+#[tokio::test]
+async fn withdraw_single_token_past_reserve_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 0, 0).await;
+
+    let result = exchange
+        .process(
+            &[pool.withdraw_single(&user, false, 1_000)],
+            &[&user.keypair],
+        )
+        .await;
+    assert_error(result, ExchangeError::NotEnoughFunds);
+
+    // the vault can't pay out more than it holds
+    let result = exchange
+        .process(
+            &[pool.withdraw_single(&user, false, 1_000_001)],
+            &[&user.keypair],
+        )
+        .await;
+    assert_error(result, ExchangeError::NumeralOverflow);
+}
+
+#[tokio::test]
+async fn deposit_or_withdraw_single_token_of_nothing_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 0).await;
+
+    let result = exchange
+        .process(&[pool.deposit_single(&user, true, 0)], &[&user.keypair])
+        .await;
+    assert_error(result, ExchangeError::ZeroTradingTokens);
+
+    let result = exchange
+        .process(&[pool.withdraw_single(&user, true, 0)], &[&user.keypair])
+        .await;
+    assert_error(result, ExchangeError::ZeroTradingTokens);
+}
+
+#[tokio::test]
+async fn single_token_past_slippage_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 0).await;
+
+    let deposit: LiquidityQuote = exchange
+        .quote(pool.quote_deposit_single(true, 10_000))
+        .await
+        .unwrap();
+    let result = exchange
+        .process(
+            &[pool.deposit_single_with_minimum(&user, true, 10_000, deposit.pool_tokens + 1)],
+            &[&user.keypair],
+        )
+        .await;
+    assert_error(result, ExchangeError::SlippageExceeded);
+    exchange
+        .process(
+            &[pool.deposit_single_with_minimum(&user, true, 10_000, deposit.pool_tokens)],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+
+    let withdraw: LiquidityQuote = exchange
+        .quote(pool.quote_withdraw_single(true, 5_000))
+        .await
+        .unwrap();
+    let result = exchange
+        .process(
+            &[pool.withdraw_single_with_maximum(&user, true, 5_000, withdraw.pool_tokens - 1)],
+            &[&user.keypair],
+        )
+        .await;
+    assert_error(result, ExchangeError::SlippageExceeded);
+    exchange
+        .process(
+            &[pool.withdraw_single_with_maximum(&user, true, 5_000, withdraw.pool_tokens)],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+}
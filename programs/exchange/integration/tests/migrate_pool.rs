@@ -0,0 +1,157 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::system_program;
+use exchange::errors::ExchangeError;
+use exchange::quote::SwapQuote;
+use exchange::state::{LegacyPool, Pool};
+use exchange_integration::*;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn unmigrated_pool_is_rejected_until_migrated() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 0).await;
+    exchange.unversion_pool(&pool.pool).await;
+
+    let result = exchange
+        .process(&[pool.swap(&user, true, 10_000)], &[&user.keypair])
+        .await;
+    assert_error(result, ExchangeError::PoolNotMigrated);
+    let result = exchange
+        .quote::<SwapQuote>(pool.quote_swap(true, 10_000))
+        .await
+        .map(|_| ());
+    assert_error(result, ExchangeError::PoolNotMigrated);
+
+    let payer = exchange.create_funded_keypair().await;
+    let lamports = exchange.account(&payer.pubkey()).await.unwrap().lamports;
+    exchange
+        .process(&[pool.migrate(&payer.pubkey())], &[&payer])
+        .await
+        .unwrap();
+
+    // the zero-copy layout is larger, the payer tops up the rent
+    let account = exchange.account(&pool.pool).await.unwrap();
+    assert_eq!(account.data.len(), Pool::MAX_SIZE);
+    assert_eq!(
+        account.lamports,
+        Rent::default().minimum_balance(Pool::MAX_SIZE)
+    );
+    let top_up = Rent::default().minimum_balance(Pool::MAX_SIZE)
+        - Rent::default().minimum_balance(LegacyPool::UNVERSIONED_SIZE);
+    assert_eq!(
+        exchange.account(&payer.pubkey()).await.unwrap().lamports,
+        lamports - top_up
+    );
+    let state: Pool = exchange.anchor_account(&pool.pool).await;
+    assert_eq!(state.version, Pool::VERSION);
+    assert_eq!(state.creator, pool.creator.pubkey());
+    assert_eq!(
+        state.fees.trade_fee_numerator,
+        default_fees().trade_fee_numerator
+    );
+
+    exchange
+        .process(&[pool.swap(&user, true, 10_000)], &[&user.keypair])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn baseline_pool_is_rejected_until_migrated() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_baseline_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 0).await;
+
+    // shorter than the zero-copy layout, the pool must not be loaded at all
+    let result = exchange
+        .process(&[pool.swap(&user, true, 10_000)], &[&user.keypair])
+        .await;
+    assert_error(result, ExchangeError::PoolNotMigrated);
+
+    let payer = exchange.create_funded_keypair().await;
+    let lamports = exchange.account(&payer.pubkey()).await.unwrap().lamports;
+    exchange
+        .process(&[pool.migrate(&payer.pubkey())], &[&payer])
+        .await
+        .unwrap();
+
+    // the zero-copy layout is larger, the payer tops up the rent
+    let account = exchange.account(&pool.pool).await.unwrap();
+    assert_eq!(account.data.len(), Pool::MAX_SIZE);
+    assert_eq!(
+        account.lamports,
+        Rent::default().minimum_balance(Pool::MAX_SIZE)
+    );
+    let top_up = Rent::default().minimum_balance(Pool::MAX_SIZE)
+        - Rent::default().minimum_balance(LegacyPool::BASELINE_SIZE);
+    assert_eq!(
+        exchange.account(&payer.pubkey()).await.unwrap().lamports,
+        lamports - top_up
+    );
+    let state: Pool = exchange.anchor_account(&pool.pool).await;
+    assert_eq!(state.version, Pool::VERSION);
+    assert_eq!(state.creator, pool.creator.pubkey());
+    assert_eq!(state.fee_tier, 0);
+    assert_eq!(
+        state.fees.trade_fee_numerator,
+        default_fees().trade_fee_numerator
+    );
+    assert!(!state.dynamic_fee.is_enabled());
+    assert!(state.transfer_hook_programs().is_empty());
+
+    exchange
+        .process(&[pool.swap(&user, true, 10_000)], &[&user.keypair])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn migrate_current_pool_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let payer = exchange.create_funded_keypair().await;
+
+    let result = exchange
+        .process(&[pool.migrate(&payer.pubkey())], &[&payer])
+        .await;
+    assert_error(result, ExchangeError::PoolAlreadyMigrated);
+}
+
+#[tokio::test]
+async fn migrate_pool_checks_the_discriminator_and_seeds() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    exchange.unversion_pool(&pool.pool).await;
+    let payer = exchange.create_funded_keypair().await;
+    let migrate = |address| {
+        instruction(
+            exchange::accounts::MigratePool {
+                pool: address,
+                payer: payer.pubkey(),
+                system_program: system_program::ID,
+            },
+            exchange::instruction::MigratePool {},
+        )
+    };
+
+    // an older pool copied to an address its seeds don't derive
+    let copy = Pubkey::new_unique();
+    let account = exchange.account(&pool.pool).await.unwrap();
+    exchange.context.set_account(&copy, &account.into());
+    let result = exchange.process(&[migrate(copy)], &[&payer]).await;
+    assert_error(result, ErrorCode::ConstraintSeeds);
+
+    // another account of the program
+    let result = exchange
+        .process(&[migrate(config_address())], &[&payer])
+        .await;
+    assert_error(result, ErrorCode::AccountDiscriminatorMismatch);
+
+    exchange
+        .process(&[migrate(pool.pool)], &[&payer])
+        .await
+        .unwrap();
+}
@@ -0,0 +1,158 @@
+use exchange::errors::ExchangeError;
+use exchange::quote::{LiquidityQuote, SwapQuote};
+use exchange::state::{DynamicFeeConfig, Pool};
+use exchange_integration::*;
+
+#[tokio::test]
+async fn quote_swap_matches_the_swap() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 0).await;
+
+    let quote: SwapQuote = exchange.quote(pool.quote_swap(true, 10_000)).await.unwrap();
+    // quoting twice leaves the pool as it was
+    let again: SwapQuote = exchange.quote(pool.quote_swap(true, 10_000)).await.unwrap();
+    assert_eq!(quote, again);
+
+    exchange
+        .process(&[pool.swap(&user, true, 10_000)], &[&user.keypair])
+        .await
+        .unwrap();
+
+    assert_eq!(quote.source_amount, 10_000);
+    assert_eq!(
+        exchange.token_amount(&user.token_b).await,
+        quote.destination_amount
+    );
+    let state: Pool = exchange.anchor_account(&pool.pool).await;
+    assert_eq!(state.protocol_fees_token_a, quote.protocol_fee);
+    assert!(quote.price_impact_bps > 0);
+}
+
+#[tokio::test]
+async fn quote_single_token_matches_deposit_and_withdrawal() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 0).await;
+
+    let deposit: LiquidityQuote = exchange
+        .quote(pool.quote_deposit_single(true, 10_000))
+        .await
+        .unwrap();
+    exchange
+        .process(
+            &[pool.deposit_single(&user, true, 10_000)],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        exchange.token_amount(&user.pool_tokens).await,
+        deposit.pool_tokens
+    );
+
+    let withdraw: LiquidityQuote = exchange
+        .quote(pool.quote_withdraw_single(true, 5_000))
+        .await
+        .unwrap();
+    exchange
+        .process(
+            &[pool.withdraw_single(&user, true, 5_000)],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        exchange.token_amount(&user.pool_tokens).await,
+        deposit.pool_tokens - withdraw.pool_tokens
+    );
+}
+
+#[tokio::test]
+async fn quote_single_token_decays_the_dynamic_fee() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange
+        .create_pool_with_dynamic_fee(DynamicFeeConfig {
+            min_fee_numerator: 10,
+            max_fee_numerator: 100,
+            max_volatility: 1_000,
+            decay_period: 600,
+        })
+        .await;
+    let user = exchange.create_user(&pool, 100_000, 0).await;
+
+    // a large swap charges the max fee, which has half decayed by the quotes
+    exchange
+        .process(&[pool.swap(&user, true, 50_000)], &[&user.keypair])
+        .await
+        .unwrap();
+    exchange.advance_clock(300).await;
+
+    let deposit: LiquidityQuote = exchange
+        .quote(pool.quote_deposit_single(true, 10_000))
+        .await
+        .unwrap();
+    exchange
+        .process(
+            &[pool.deposit_single(&user, true, 10_000)],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        exchange.token_amount(&user.pool_tokens).await,
+        deposit.pool_tokens
+    );
+
+    exchange.advance_clock(300).await;
+    let withdraw: LiquidityQuote = exchange
+        .quote(pool.quote_withdraw_single(true, 5_000))
+        .await
+        .unwrap();
+    exchange
+        .process(
+            &[pool.withdraw_single(&user, true, 5_000)],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        exchange.token_amount(&user.pool_tokens).await,
+        deposit.pool_tokens - withdraw.pool_tokens
+    );
+}
+
+#[tokio::test]
+async fn quote_swap_of_nothing_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+
+    let result = exchange
+        .quote::<SwapQuote>(pool.quote_swap(true, 0))
+        .await
+        .map(|_| ());
+    assert_error(result, ExchangeError::ZeroTradingTokens);
+}
+
+#[tokio::test]
+async fn quote_deposit_of_another_mint_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let other_pool = exchange.create_pool().await;
+
+    let quote = instruction(
+        exchange::accounts::QuoteDepositSingle {
+            pool: pool.pool,
+            pool_token_a_account: pool.token_a,
+            pool_token_b_account: pool.token_b,
+            source_mint: other_pool.token_a_mint,
+            pool_mint: pool.mint,
+            config: config_address(),
+        },
+        exchange::instruction::QuoteDepositSingle {
+            source_amount: 10_000,
+        },
+    );
+    let result = exchange.quote::<LiquidityQuote>(quote).await.map(|_| ());
+    assert_error(result, ExchangeError::InvalidMint);
+}
@@ -0,0 +1,341 @@
+use anchor_lang::error::ErrorCode;
+use anchor_spl::token::spl_token;
+use exchange::errors::ExchangeError;
+use exchange::quote::SwapQuote;
+use exchange::state::{DynamicFeeConfig, Pool};
+use exchange_integration::*;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn swap_both_directions() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 0).await;
+    let supply = exchange.mint_supply(&pool.mint).await;
+
+    exchange
+        .process(&[pool.swap(&user, true, 10_000)], &[&user.keypair])
+        .await
+        .unwrap();
+
+    let token_a = exchange.token_amount(&pool.token_a).await;
+    let token_b = exchange.token_amount(&pool.token_b).await;
+    let received = exchange.token_amount(&user.token_b).await;
+    assert_eq!(
+        token_a + exchange.token_amount(&user.token_a).await,
+        1_010_000
+    );
+    assert_eq!(token_b, 1_000_000 - received);
+    // less than the fee free 1_000_000 * 10_000 / 1_010_000
+    assert!(received > 0 && received < 9_900);
+    assert!(token_a as u128 * token_b as u128 >= 1_000_000 * 1_000_000);
+
+    // the owner fee is minted as pool tokens to the pool fee account
+    let fee_pool_tokens = exchange.token_amount(&pool.fee_account).await;
+    assert!(fee_pool_tokens > 0);
+    assert_eq!(
+        exchange.mint_supply(&pool.mint).await,
+        supply + fee_pool_tokens
+    );
+
+    exchange
+        .process(&[pool.swap(&user, false, received)], &[&user.keypair])
+        .await
+        .unwrap();
+
+    // the round trip pays the fees twice
+    assert!(exchange.token_amount(&user.token_a).await < 10_000);
+}
+
+#[tokio::test]
+async fn swap_with_host_fee_account() {
+    let mut exchange = TestExchange::start().await;
+    let admin = exchange.admin.insecure_clone();
+    exchange
+        .process(&[set_host_fee(&admin.pubkey(), 1, 2)], &[&admin])
+        .await
+        .unwrap();
+
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 100_000, 0).await;
+
+    let swap = instruction(
+        exchange::accounts::Swap {
+            host_fee_account: Some(user.pool_tokens),
+            ..pool.swap_accounts(&user, true)
+        },
+        exchange::instruction::Swap {
+            source_amount: 100_000,
+            minimum_amount_out: 0,
+        },
+    );
+    exchange.process(&[swap], &[&user.keypair]).await.unwrap();
+
+    let host_pool_tokens = exchange.token_amount(&user.pool_tokens).await;
+    let fee_pool_tokens = exchange.token_amount(&pool.fee_account).await;
+    assert!(host_pool_tokens > 0);
+    assert!(fee_pool_tokens >= host_pool_tokens);
+}
+
+#[tokio::test]
+async fn swap_and_collect_protocol_fees() {
+    let mut exchange = TestExchange::start().await;
+    let admin = exchange.admin.insecure_clone();
+    let treasury = exchange.create_funded_keypair().await;
+    exchange
+        .process(
+            &[set_protocol_fee(&admin.pubkey(), 1, 2, treasury.pubkey())],
+            &[&admin],
+        )
+        .await
+        .unwrap();
+
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 100_000, 0).await;
+    exchange
+        .process(&[pool.swap(&user, true, 100_000)], &[&user.keypair])
+        .await
+        .unwrap();
+
+    // half of the 0.3% trade fee on 100_000, rounded up then halved down
+    let state: Pool = exchange.anchor_account(&pool.pool).await;
+    assert_eq!(state.protocol_fees_token_a, 150);
+    assert_eq!(state.protocol_fees_token_b, 0);
+
+    let treasury_token_a = exchange
+        .create_token_account(&pool.token_a_mint, &treasury.pubkey(), &spl_token::id(), 0)
+        .await;
+    let treasury_token_b = exchange
+        .create_token_account(&pool.token_b_mint, &treasury.pubkey(), &spl_token::id(), 0)
+        .await;
+
+    let result = exchange
+        .process(
+            &[pool.collect_protocol_fees(&admin.pubkey(), &treasury_token_a, &treasury_token_b)],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, ExchangeError::InvalidAuthority);
+
+    exchange
+        .process(
+            &[
+                pool.collect_protocol_fees(
+                    &treasury.pubkey(),
+                    &treasury_token_a,
+                    &treasury_token_b,
+                ),
+            ],
+            &[&treasury],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(exchange.token_amount(&treasury_token_a).await, 150);
+    let state: Pool = exchange.anchor_account(&pool.pool).await;
+    assert_eq!(state.protocol_fees_token_a, 0);
+}
+
+#[tokio::test]
+async fn dynamic_fee_decays_linearly_between_trades() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange
+        .create_pool_with_dynamic_fee(DynamicFeeConfig {
+            min_fee_numerator: 10,
+            max_fee_numerator: 100,
+            max_volatility: 1_000,
+            decay_period: 600,
+        })
+        .await;
+    let user = exchange.create_user(&pool, 100_000, 0).await;
+
+    exchange
+        .process(&[pool.swap(&user, true, 50_000)], &[&user.keypair])
+        .await
+        .unwrap();
+    let state: Pool = exchange.anchor_account(&pool.pool).await;
+    let reference = state.dynamic_fee.volatility_reference;
+    assert!(reference > 0);
+
+    // trades too small to move the price decay the volatility without adding to it
+    for amount in 10..13 {
+        exchange.advance_clock(150).await;
+        exchange
+            .process(&[pool.swap(&user, true, amount)], &[&user.keypair])
+            .await
+            .unwrap();
+    }
+
+    // three quarters of the period decay three quarters, not 1 - 0.75^3 of it
+    let decayed: Pool = exchange.anchor_account(&pool.pool).await;
+    assert_eq!(
+        decayed.dynamic_fee.reference_timestamp,
+        state.dynamic_fee.reference_timestamp
+    );
+    let elapsed = decayed.dynamic_fee.last_update_timestamp - state.dynamic_fee.reference_timestamp;
+    assert!(elapsed >= 450 && elapsed < 600);
+    assert_eq!(
+        decayed.dynamic_fee.volatility_accumulator,
+        reference * (600 - elapsed) as u64 / 600
+    );
+}
+
+#[tokio::test]
+async fn swap_zero_or_dust_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 0).await;
+
+    let result = exchange
+        .process(&[pool.swap(&user, true, 0)], &[&user.keypair])
+        .await;
+    assert_error(result, ExchangeError::ZeroTradingTokens);
+
+    // two tokens only cover the rounded up fees and swap out nothing
+    let result = exchange
+        .process(&[pool.swap(&user, true, 2)], &[&user.keypair])
+        .await;
+    assert_error(result, ExchangeError::ZeroTradingTokens);
+}
+
+#[tokio::test]
+async fn swap_more_than_balance_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 0).await;
+
+    let result = exchange
+        .process(&[pool.swap(&user, true, 10_001)], &[&user.keypair])
+        .await;
+    assert_error(result, ExchangeError::NotEnoughFunds);
+}
+
+#[tokio::test]
+async fn swap_under_the_minimum_amount_out_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 0).await;
+    let quote: SwapQuote = exchange.quote(pool.quote_swap(true, 10_000)).await.unwrap();
+
+    let result = exchange
+        .process(
+            &[pool.swap_with_minimum(&user, true, 10_000, quote.destination_amount + 1)],
+            &[&user.keypair],
+        )
+        .await;
+    assert_error(result, ExchangeError::SlippageExceeded);
+
+    exchange
+        .process(
+            &[pool.swap_with_minimum(&user, true, 10_000, quote.destination_amount)],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        exchange.token_amount(&user.token_b).await,
+        quote.destination_amount
+    );
+}
+
+#[tokio::test]
+async fn swap_with_wrong_accounts_fails() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 10_000).await;
+
+    // a user account posing as the source vault
+    let swap = instruction(
+        exchange::accounts::Swap {
+            pool_source_token_account: user.token_a,
+            ..pool.swap_accounts(&user, true)
+        },
+        exchange::instruction::Swap {
+            source_amount: 1_000,
+            minimum_amount_out: 0,
+        },
+    );
+    let result = exchange.process(&[swap], &[&user.keypair]).await;
+    assert_error(result, ExchangeError::InvalidPoolTokenAccount);
+
+    // the source vault on both sides
+    let swap = instruction(
+        exchange::accounts::Swap {
+            pool_destination_token_account: pool.token_a,
+            destination_mint: pool.token_a_mint,
+            user_destination_token_account: user.token_a,
+            ..pool.swap_accounts(&user, true)
+        },
+        exchange::instruction::Swap {
+            source_amount: 1_000,
+            minimum_amount_out: 0,
+        },
+    );
+    let result = exchange.process(&[swap], &[&user.keypair]).await;
+    assert_error(result, ExchangeError::SameTokenMints);
+
+    // a host fee account of another mint than the pool token
+    let swap = instruction(
+        exchange::accounts::Swap {
+            host_fee_account: Some(user.token_b),
+            ..pool.swap_accounts(&user, true)
+        },
+        exchange::instruction::Swap {
+            source_amount: 1_000,
+            minimum_amount_out: 0,
+        },
+    );
+    let result = exchange.process(&[swap], &[&user.keypair]).await;
+    assert_error(result, ExchangeError::InvalidHostFeeAccount);
+
+    // the pool mint of another pool
+    let other_pool = exchange.create_pool().await;
+    let swap = instruction(
+        exchange::accounts::Swap {
+            pool_mint: other_pool.mint,
+            ..pool.swap_accounts(&user, true)
+        },
+        exchange::instruction::Swap {
+            source_amount: 1_000,
+            minimum_amount_out: 0,
+        },
+    );
+    let result = exchange.process(&[swap], &[&user.keypair]).await;
+    assert_error(result, ExchangeError::InvalidMint);
+
+    // a pool token program that isn't the one of the pool mint
+    let swap = instruction(
+        exchange::accounts::Swap {
+            token_program: spl_token::id(),
+            ..pool.swap_accounts(&user, true)
+        },
+        exchange::instruction::Swap {
+            source_amount: 1_000,
+            minimum_amount_out: 0,
+        },
+    );
+    let result = exchange.process(&[swap], &[&user.keypair]).await;
+    assert_error(result, ErrorCode::ConstraintMintTokenProgram);
+
+    assert_eq!(
+        exchange.token_amount(&pool.token_a).await,
+        1_000_000,
+        "failed swaps leave the vaults untouched"
+    );
+}
+
+#[tokio::test]
+async fn swap_fits_the_default_compute_budget() {
+    let mut exchange = TestExchange::start().await;
+    let pool = exchange.create_pool().await;
+    let user = exchange.create_user(&pool, 10_000, 10_000).await;
+
+    // the zero-copy pool is read in place, tests/compute_units.rs tracks the usage
+    for a_to_b in [true, false] {
+        let units = exchange
+            .compute_units(&[pool.swap(&user, a_to_b, 10_000)], &[&user.keypair])
+            .await
+            .unwrap();
+        assert!(units > 0 && units < 200_000);
+    }
+}
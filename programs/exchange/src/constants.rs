@@ -1,2 +1,7 @@
 pub const PREFIX: &[u8] = b"pool";
 pub const AUTHORITY: &[u8] = b"authority";
+pub const REGISTRY: &[u8] = b"registry";
+pub const CONFIG: &[u8] = b"config";
+pub const POOL_MINT: &[u8] = b"mint";
+pub const VAULT: &[u8] = b"vault";
+pub const LOCKED_LIQUIDITY: &[u8] = b"locked";
@@ -1 +0,0 @@
-pub mod constant_product;
@@ -1,12 +1,12 @@
 use anchor_lang::prelude::*;
+use exchange_math::CurveError;
 
+// codes of removed errors are skipped rather than reused, so clients keep
+// decoding the codes they know
 #[error_code]
 pub enum ExchangeError {
-    #[msg("Pool mint supply should be zero")]
-    PoolMintSupplyNotZero,
-
     #[msg("Authority is Invalid")]
-    InvalidAuthority,
+    InvalidAuthority = 1,
 
     #[msg("Mint is Invalid")]
     InvalidMint,
@@ -29,6 +29,72 @@ pub enum ExchangeError {
     #[msg("Numeral Overflow")]
     NumeralOverflow,
 
-    #[msg("Failed To Create Precise Number")]
-    FailedToCreatePreciseNumber,
+    #[msg("Token mints are not in canonical order")]
+    InvalidMintOrder = 10,
+
+    #[msg("Pool registry is full")]
+    RegistryFull,
+
+    #[msg("Pool is not in the registry")]
+    PoolNotRegistered,
+
+    #[msg("Registry has no pool with liquidity")]
+    NoLiquidPool,
+
+    #[msg("Fee is Invalid")]
+    InvalidFee,
+
+    #[msg("Fee tier does not exist")]
+    InvalidFeeTier,
+
+    #[msg("Fee tier is disabled")]
+    FeeTierDisabled,
+
+    #[msg("Config has no room for more fee tiers")]
+    FeeTiersFull,
+
+    #[msg("Host fee account is not a pool token account")]
+    InvalidHostFeeAccount,
+
+    #[msg("Transfer hook program is not allowed by the pool")]
+    TransferHookNotAllowed,
+
+    #[msg("Too many transfer hook programs")]
+    TooManyTransferHookPrograms,
+
+    #[msg("Pool token account is empty")]
+    EmptyPoolTokenAccount,
+
+    #[msg("Pool token account is frozen")]
+    FrozenPoolTokenAccount,
+
+    #[msg("Initial liquidity is too small")]
+    InsufficientInitialLiquidity,
+
+    #[msg("Trade moves zero tokens")]
+    ZeroTradingTokens,
+
+    #[msg("Pool has an older layout, migrate it with migrate_pool")]
+    PoolNotMigrated,
+
+    #[msg("Pool already has the current layout")]
+    PoolAlreadyMigrated,
+
+    #[msg("Pool tokens are still held outside of the pool")]
+    PoolNotEmpty,
+
+    #[msg("Protocol fees must be collected first")]
+    ProtocolFeesNotCollected,
+
+    #[msg("Every registered pool must be passed, in registry order")]
+    RegisteredPoolMissing,
+}
+
+impl From<CurveError> for ExchangeError {
+    fn from(error: CurveError) -> Self {
+        match error {
+            CurveError::NumeralOverflow => ExchangeError::NumeralOverflow,
+            CurveError::ConversionFailure => ExchangeError::ConversionFailure,
+        }
+    }
 }
@@ -0,0 +1,41 @@
+use crate::constants::CONFIG;
+use crate::errors::ExchangeError;
+use crate::{Config, DynamicFeeConfig, Fee, FeeTier};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AddFeeTier<'info> {
+    #[account(
+        mut,
+        seeds=[CONFIG],
+        bump=config.bump,
+        has_one=admin @ ExchangeError::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn add_fee_tier(
+    ctx: Context<AddFeeTier>,
+    fees: Fee,
+    dynamic_fee: Option<DynamicFeeConfig>,
+) -> Result<()> {
+    fees.validate()?;
+    if let Some(dynamic_fee) = &dynamic_fee {
+        dynamic_fee.validate(&fees)?;
+    }
+
+    let config = &mut ctx.accounts.config;
+    if config.fee_tiers.len() >= Config::MAX_FEE_TIERS {
+        return Err(ExchangeError::FeeTiersFull.into());
+    }
+
+    config.fee_tiers.push(FeeTier {
+        fees,
+        enabled: true,
+        dynamic_fee,
+    });
+
+    Ok(())
+}
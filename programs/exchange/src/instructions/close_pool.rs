@@ -0,0 +1,268 @@
+use crate::constants::{AUTHORITY, CONFIG, LOCKED_LIQUIDITY, PREFIX, REGISTRY};
+use crate::errors::ExchangeError;
+use crate::transfer_fee::harvest_withheld_fees;
+use crate::transfer_hook::transfer_checked_with_hook;
+use crate::{Config, Pool, PoolRegistry};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    burn, close_account, Burn, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    #[account(seeds=[CONFIG], bump=config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(
+        seeds=[
+            PREFIX,
+            pool.key().as_ref(),
+            AUTHORITY
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            PREFIX,
+            Pool::load_migrated(&pool)?.token_a_mint.as_ref(),
+            Pool::load_migrated(&pool)?.token_b_mint.as_ref(),
+            Pool::load_migrated(&pool)?.creator.as_ref()
+        ],
+        bump=Pool::load_migrated(&pool)?.bump,
+        close=receiver
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Registry of the pool, absent for pools created before registries
+    #[account(
+        mut,
+        seeds=[
+            REGISTRY,
+            pool.load()?.token_a_mint.as_ref(),
+            pool.load()?.token_b_mint.as_ref(),
+            &pool.load()?.fee_tier.to_le_bytes()
+        ],
+        bump=registry.bump
+    )]
+    pub registry: Option<Box<Account<'info, PoolRegistry>>>,
+
+    #[account(
+        mut,
+        address=pool.load()?.token_a @ ExchangeError::InvalidPoolTokenAccount,
+        token::token_program=token_a_program
+    )]
+    pub pool_token_a_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address=pool.load()?.token_b @ ExchangeError::InvalidPoolTokenAccount,
+        token::token_program=token_b_program
+    )]
+    pub pool_token_b_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Writable to harvest the transfer fees withheld in the vault
+    #[account(mut, address=pool.load()?.token_a_mint @ ExchangeError::InvalidMint)]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Writable to harvest the transfer fees withheld in the vault
+    #[account(mut, address=pool.load()?.token_b_mint @ ExchangeError::InvalidMint)]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        address=pool.load()?.mint @ ExchangeError::InvalidMint,
+        mint::token_program=token_program
+    )]
+    pub pool_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        address=pool.load()?.fee_account @ ExchangeError::InvalidPoolTokenAccount
+    )]
+    pub pool_token_fee_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Absent for pools created before the minimum liquidity was locked
+    #[account(
+        mut,
+        seeds=[
+            PREFIX,
+            pool.key().as_ref(),
+            LOCKED_LIQUIDITY
+        ],
+        bump
+    )]
+    pub locked_liquidity: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Receives the token A left in the vault
+    #[account(
+        mut,
+        token::mint=token_a_mint,
+        token::authority=receiver,
+        token::token_program=token_a_program
+    )]
+    pub receiver_token_a_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Receives the token B left in the vault
+    #[account(
+        mut,
+        token::mint=token_b_mint,
+        token::authority=receiver,
+        token::token_program=token_b_program
+    )]
+    pub receiver_token_b_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Only receives the rent of the closed accounts
+    #[account(mut)]
+    pub receiver: AccountInfo<'info>,
+
+    /// Admin of the config or creator of the pool
+    #[account(
+        constraint=authority.key() == config.admin
+            || authority.key() == pool.load()?.creator @ ExchangeError::InvalidAuthority
+    )]
+    pub authority: Signer<'info>,
+
+    /// Token program of the pool mint, SPL Token for pools created before Token-2022
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
+
+/// Closes a pool nobody provides liquidity to anymore, along with its vaults,
+/// fee account and locked liquidity account.
+///
+/// The only pool tokens left must be the minimum liquidity and the owner fees,
+/// both held by the pool authority, they are burned. The tokens backing them
+/// go to the receiver's token accounts and every rent to the receiver.
+///
+/// Pools of the first deployment have neither a registry nor locked liquidity.
+/// A registered pool closed without its registry can still be unregistered by
+/// the admin.
+pub fn close_pool<'info>(ctx: Context<'_, '_, 'info, 'info, ClosePool<'info>>) -> Result<()> {
+    let pool = ctx.accounts.pool.load()?;
+    if pool.protocol_fees_token_a != 0 || pool.protocol_fees_token_b != 0 {
+        return Err(ExchangeError::ProtocolFeesNotCollected.into());
+    }
+
+    let locked_amount = ctx
+        .accounts
+        .locked_liquidity
+        .as_ref()
+        .map_or(0, |locked_liquidity| locked_liquidity.amount);
+    let owner_fee_amount = ctx.accounts.pool_token_fee_account.amount;
+    let pool_held_amount = locked_amount
+        .checked_add(owner_fee_amount)
+        .ok_or(ExchangeError::NumeralOverflow)?;
+    if ctx.accounts.pool_mint.supply != pool_held_amount {
+        return Err(ExchangeError::PoolNotEmpty.into());
+    }
+
+    let pool_key = ctx.accounts.pool.key();
+    let signer_seeds = &[
+        PREFIX,
+        pool_key.as_ref(),
+        AUTHORITY,
+        &[ctx.bumps.pool_authority],
+    ];
+    let signer = &[&signer_seeds[..]];
+
+    let locked_liquidity = ctx
+        .accounts
+        .locked_liquidity
+        .as_ref()
+        .map(|locked_liquidity| (locked_liquidity.to_account_info(), locked_amount));
+    let owner_fees = (
+        ctx.accounts.pool_token_fee_account.to_account_info(),
+        owner_fee_amount,
+    );
+    for (pool_tokens, amount) in locked_liquidity.into_iter().chain([owner_fees]) {
+        if amount > 0 {
+            let cpi_context = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.pool_mint.to_account_info(),
+                    from: pool_tokens.clone(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer,
+            );
+            burn(cpi_context, amount)?;
+        }
+
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: pool_tokens,
+                destination: ctx.accounts.receiver.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            signer,
+        );
+        close_account(cpi_context)?;
+    }
+
+    for (vault, mint, receiver_token_account, token_program) in [
+        (
+            &ctx.accounts.pool_token_a_account,
+            &ctx.accounts.token_a_mint,
+            &ctx.accounts.receiver_token_a_account,
+            &ctx.accounts.token_a_program,
+        ),
+        (
+            &ctx.accounts.pool_token_b_account,
+            &ctx.accounts.token_b_mint,
+            &ctx.accounts.receiver_token_b_account,
+            &ctx.accounts.token_b_program,
+        ),
+    ] {
+        if vault.amount > 0 {
+            let cpi_context = CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: vault.to_account_info(),
+                    to: receiver_token_account.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                    mint: mint.to_account_info(),
+                },
+                signer,
+            )
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+            transfer_checked_with_hook(
+                cpi_context,
+                vault.amount,
+                mint.decimals,
+                pool.transfer_hook_programs(),
+            )?;
+        }
+
+        harvest_withheld_fees(
+            mint,
+            vault.to_account_info(),
+            token_program.to_account_info(),
+        )?;
+
+        let cpi_context = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            CloseAccount {
+                account: vault.to_account_info(),
+                destination: ctx.accounts.receiver.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            signer,
+        );
+        close_account(cpi_context)?;
+    }
+    drop(pool);
+
+    if let Some(registry) = &mut ctx.accounts.registry {
+        registry.pools.retain(|registered| *registered != pool_key);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use crate::constants::{AUTHORITY, CONFIG, PREFIX};
+use crate::errors::ExchangeError;
+use crate::transfer_hook::transfer_checked_with_hook;
+use crate::{Config, Pool};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+#[derive(Accounts)]
+pub struct CollectProtocolFees<'info> {
+    #[account(
+        seeds=[CONFIG],
+        bump=config.bump,
+        has_one=treasury @ ExchangeError::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Account seeds checked in constraints
+    #[account(
+        seeds=[
+            PREFIX,
+            pool.key().as_ref(),
+            AUTHORITY
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            PREFIX,
+            Pool::load_migrated(&pool)?.token_a_mint.as_ref(),
+            Pool::load_migrated(&pool)?.token_b_mint.as_ref(),
+            Pool::load_migrated(&pool)?.creator.as_ref()
+        ],
+        bump=Pool::load_migrated(&pool)?.bump
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        mut,
+        address=pool.load()?.token_a @ ExchangeError::InvalidPoolTokenAccount,
+        token::token_program=token_a_program
+    )]
+    pub pool_token_a_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address=pool.load()?.token_b @ ExchangeError::InvalidPoolTokenAccount,
+        token::token_program=token_b_program
+    )]
+    pub pool_token_b_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address=pool.load()?.token_a_mint @ ExchangeError::InvalidMint)]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(address=pool.load()?.token_b_mint @ ExchangeError::InvalidMint)]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint=pool.load()?.token_a_mint
+    )]
+    pub treasury_token_a_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint=pool.load()?.token_b_mint
+    )]
+    pub treasury_token_b_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub treasury: Signer<'info>,
+
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
+
+pub fn collect_protocol_fees<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CollectProtocolFees<'info>>,
+) -> Result<()> {
+    let pool = ctx.accounts.pool.load()?;
+    let protocol_fees_token_a = pool.protocol_fees_token_a;
+    let protocol_fees_token_b = pool.protocol_fees_token_b;
+
+    let pool_key = ctx.accounts.pool.key();
+    let signer_seeds = &[
+        PREFIX,
+        pool_key.as_ref(),
+        AUTHORITY,
+        &[ctx.bumps.pool_authority],
+    ];
+    let signer = &[&signer_seeds[..]];
+
+    let token_a_transfer_accounts = TransferChecked {
+        from: ctx.accounts.pool_token_a_account.to_account_info(),
+        to: ctx.accounts.treasury_token_a_account.to_account_info(),
+        authority: ctx.accounts.pool_authority.to_account_info(),
+        mint: ctx.accounts.token_a_mint.to_account_info(),
+    };
+    let token_a_transfer_context = CpiContext::new_with_signer(
+        ctx.accounts.token_a_program.to_account_info(),
+        token_a_transfer_accounts,
+        signer,
+    )
+    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    transfer_checked_with_hook(
+        token_a_transfer_context,
+        protocol_fees_token_a,
+        ctx.accounts.token_a_mint.decimals,
+        pool.transfer_hook_programs(),
+    )?;
+
+    let token_b_transfer_accounts = TransferChecked {
+        from: ctx.accounts.pool_token_b_account.to_account_info(),
+        to: ctx.accounts.treasury_token_b_account.to_account_info(),
+        authority: ctx.accounts.pool_authority.to_account_info(),
+        mint: ctx.accounts.token_b_mint.to_account_info(),
+    };
+    let token_b_transfer_context = CpiContext::new_with_signer(
+        ctx.accounts.token_b_program.to_account_info(),
+        token_b_transfer_accounts,
+        signer,
+    )
+    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    transfer_checked_with_hook(
+        token_b_transfer_context,
+        protocol_fees_token_b,
+        ctx.accounts.token_b_mint.decimals,
+        pool.transfer_hook_programs(),
+    )?;
+
+    drop(pool);
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    pool.protocol_fees_token_a = 0;
+    pool.protocol_fees_token_b = 0;
+
+    Ok(())
+}
@@ -1,8 +1,14 @@
 use crate::constants::{AUTHORITY, PREFIX};
 use crate::errors::ExchangeError;
-use crate::{curve::constant_product::*, Pool};
+use crate::native_sol::{unwrap_native, wrap_native};
+use crate::transfer_fee::amount_before_transfer_fee;
+use crate::transfer_hook::transfer_checked_with_hook;
+use crate::Pool;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{mint_to, transfer, Mint, MintTo, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{
+    mint_to, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
+use exchange_math::{constant_product::*, RoundDirection};
 
 #[derive(Accounts)]
 pub struct DepositAllTokens<'info> {
@@ -24,58 +30,68 @@ pub struct DepositAllTokens<'info> {
             pool_token_b_account.mint.as_ref(),
             creator.key().as_ref()
         ],
-        bump
+        bump,
+        constraint=Pool::is_migrated(&pool) @ ExchangeError::PoolNotMigrated
     )]
-    pub pool: Box<Account<'info, Pool>>,
+    pub pool: AccountLoader<'info, Pool>,
 
     /// Non-zero token A account
     #[account(
         mut,
-        address=pool.token_a @ ExchangeError::InvalidPoolTokenAccount,
-        token::authority=pool_authority.key()
+        address=pool.load()?.token_a @ ExchangeError::InvalidPoolTokenAccount,
+        token::authority=pool_authority.key(),
+        token::token_program=token_a_program
     )]
-    pub pool_token_a_account: Account<'info, TokenAccount>,
+    pub pool_token_a_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// Non-zero token B account
     #[account(
         mut,
-        address=pool.token_b @ ExchangeError::InvalidPoolTokenAccount,
-        token::authority=pool_authority.key()
+        address=pool.load()?.token_b @ ExchangeError::InvalidPoolTokenAccount,
+        token::authority=pool_authority.key(),
+        token::token_program=token_b_program
     )]
-    pub pool_token_b_account: Account<'info, TokenAccount>,
+    pub pool_token_b_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address=pool.load()?.token_a_mint @ ExchangeError::InvalidMint)]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(address=pool.load()?.token_b_mint @ ExchangeError::InvalidMint)]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
-        token::mint=pool.token_a_mint,
+        token::mint=pool.load()?.token_a_mint,
         token::authority=user.key()
     )]
-    pub user_token_a_account: Account<'info, TokenAccount>,
+    pub user_token_a_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
-        token::mint=pool.token_b_mint,
+        token::mint=pool.load()?.token_b_mint,
         token::authority=user.key()
     )]
-    pub user_token_b_account: Account<'info, TokenAccount>,
+    pub user_token_b_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         token::mint=pool_mint,
         token::authority=user.key()
     )]
-    pub user_pool_token_receipt: Account<'info, TokenAccount>,
+    pub user_pool_token_receipt: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
-        address=pool.mint @ ExchangeError::InvalidMint,
+        address=pool.load()?.mint @ ExchangeError::InvalidMint,
         mint::authority = pool_authority,
         mint::freeze_authority = pool_authority,
-        mint::decimals = 9
+        mint::decimals = 9,
+        mint::token_program = token_program
     )]
-    pub pool_mint: Account<'info, Mint>,
+    pub pool_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(token::mint=pool_mint)]
-    pub pool_token_fee_account: Box<Account<'info, TokenAccount>>,
+    pub pool_token_fee_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
     pub user: Signer<'info>,
@@ -85,52 +101,99 @@ pub struct DepositAllTokens<'info> {
 
     pub system_program: Program<'info, System>,
 
-    pub token_program: Program<'info, Token>,
+    /// Token program of the pool mint
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    pub token_b_program: Interface<'info, TokenInterface>,
 }
 
-pub fn deposit_all_tokens_in(
-    ctx: Context<DepositAllTokens>,
+pub fn deposit_all_tokens_in<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DepositAllTokens<'info>>,
     min_pool_tokens: u64,
     max_token_a: u64,
     max_token_b: u64,
 ) -> Result<()> {
+    let pool = ctx.accounts.pool.load()?;
     let pool_mint_account = &ctx.accounts.pool_mint;
 
     let (token_a_amount, token_b_amount) = calculate_trade_tokens_propotional_to_pool_tokens(
         min_pool_tokens as u128,
         pool_mint_account.supply as u128,
-        ctx.accounts.pool_token_a_account.amount as u128,
-        ctx.accounts.pool_token_b_account.amount as u128,
+        pool.token_a_reserve(ctx.accounts.pool_token_a_account.amount)? as u128,
+        pool.token_b_reserve(ctx.accounts.pool_token_b_account.amount)? as u128,
+        RoundDirection::Ceiling,
     )
-    .unwrap();
-
-    if token_a_amount as u64 > max_token_a || token_b_amount as u64 > max_token_b {
+    .map_err(ExchangeError::from)?;
+
+    // the user pays the transfer fees on top of what the vaults must receive
+    let token_a_amount = amount_before_transfer_fee(
+        &ctx.accounts.token_a_mint,
+        u64::try_from(token_a_amount).map_err(|_| ExchangeError::ConversionFailure)?,
+    )?;
+    let token_b_amount = amount_before_transfer_fee(
+        &ctx.accounts.token_b_mint,
+        u64::try_from(token_b_amount).map_err(|_| ExchangeError::ConversionFailure)?,
+    )?;
+
+    if token_a_amount > max_token_a || token_b_amount > max_token_b {
         return Err(ExchangeError::SlippageExceeded.into());
     }
 
-    let transfer_token_a_accounts = Transfer {
+    // native SOL sides are wrapped from the user's lamports
+    let token_a_wrapped = wrap_native(
+        &mut ctx.accounts.user_token_a_account,
+        &ctx.accounts.user,
+        &ctx.accounts.system_program,
+        ctx.accounts.token_a_program.to_account_info(),
+        token_a_amount,
+    )?;
+    let token_b_wrapped = wrap_native(
+        &mut ctx.accounts.user_token_b_account,
+        &ctx.accounts.user,
+        &ctx.accounts.system_program,
+        ctx.accounts.token_b_program.to_account_info(),
+        token_b_amount,
+    )?;
+
+    let transfer_token_a_accounts = TransferChecked {
         from: ctx.accounts.user_token_a_account.to_account_info(),
         to: ctx.accounts.pool_token_a_account.to_account_info(),
         authority: ctx.accounts.user.to_account_info(),
+        mint: ctx.accounts.token_a_mint.to_account_info(),
     };
 
     let transfer_token_a_context = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.token_a_program.to_account_info(),
         transfer_token_a_accounts,
-    );
+    )
+    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
 
-    transfer(transfer_token_a_context, token_a_amount as u64)?;
+    transfer_checked_with_hook(
+        transfer_token_a_context,
+        token_a_amount,
+        ctx.accounts.token_a_mint.decimals,
+        pool.transfer_hook_programs(),
+    )?;
 
-    let transfer_token_b_accounts = Transfer {
+    let transfer_token_b_accounts = TransferChecked {
         from: ctx.accounts.user_token_b_account.to_account_info(),
         to: ctx.accounts.pool_token_b_account.to_account_info(),
         authority: ctx.accounts.user.to_account_info(),
+        mint: ctx.accounts.token_b_mint.to_account_info(),
     };
     let transfer_token_b_context = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.token_b_program.to_account_info(),
         transfer_token_b_accounts,
-    );
-    transfer(transfer_token_b_context, token_b_amount as u64)?;
+    )
+    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    transfer_checked_with_hook(
+        transfer_token_b_context,
+        token_b_amount,
+        ctx.accounts.token_b_mint.decimals,
+        pool.transfer_hook_programs(),
+    )?;
 
     let mint_to_accounts = MintTo {
         to: ctx.accounts.user_pool_token_receipt.to_account_info(),
@@ -154,5 +217,21 @@ pub fn deposit_all_tokens_in(
     );
     mint_to(mint_to_context, min_pool_tokens)?;
 
+    // temporary wrapped accounts are unwrapped back to lamports
+    if token_a_wrapped {
+        unwrap_native(
+            &ctx.accounts.user_token_a_account,
+            &ctx.accounts.user,
+            ctx.accounts.token_a_program.to_account_info(),
+        )?;
+    }
+    if token_b_wrapped {
+        unwrap_native(
+            &ctx.accounts.user_token_b_account,
+            &ctx.accounts.user,
+            ctx.accounts.token_b_program.to_account_info(),
+        )?;
+    }
+
     Ok(())
 }
@@ -1,9 +1,15 @@
-use crate::constants::{AUTHORITY, PREFIX};
+use crate::constants::{AUTHORITY, CONFIG, PREFIX};
 use crate::errors::ExchangeError;
-use crate::{curve::constant_product::*, Pool};
+use crate::native_sol::{unwrap_native, wrap_native};
+use crate::transfer_fee::amount_after_transfer_fee;
+use crate::transfer_hook::transfer_checked_with_hook;
+use crate::{Config, Pool};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{mint_to, transfer, Mint, MintTo, Token, TokenAccount, Transfer};
 use anchor_spl::token_interface::spl_token_2022::cmp_pubkeys;
+use anchor_spl::token_interface::{
+    mint_to, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
+use exchange_math::quote::{quote_deposit, LiquidityQuote};
 
 use super::TradeDirection;
 
@@ -21,106 +27,175 @@ pub struct DepositSingleToken<'info> {
     pub pool_authority: AccountInfo<'info>,
 
     #[account(
+        mut,
         seeds=[
             PREFIX,
             pool_token_a_account.mint.as_ref(),
             pool_token_b_account.mint.as_ref(),
-            pool.creator.as_ref()
+            Pool::load_migrated(&pool)?.creator.as_ref()
         ],
         bump
     )]
-    pub pool: Account<'info, Pool>,
+    pub pool: AccountLoader<'info, Pool>,
 
     /// Non-zero token A account
     #[account(
         mut,
-        address=pool.token_a @ ExchangeError::InvalidPoolTokenAccount,
+        address=pool.load()?.token_a @ ExchangeError::InvalidPoolTokenAccount,
         token::authority=pool_authority.key()
     )]
-    pub pool_token_a_account: Account<'info, TokenAccount>,
+    pub pool_token_a_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// Non-zero token B account
     #[account(
         mut,
-        address=pool.token_b @ ExchangeError::InvalidPoolTokenAccount,
+        address=pool.load()?.token_b @ ExchangeError::InvalidPoolTokenAccount,
         token::authority=pool_authority.key()
     )]
-    pub pool_token_b_account: Account<'info, TokenAccount>,
+    pub pool_token_b_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         token::mint=source_mint,
-        token::authority=user.key()
+        token::authority=user.key(),
+        token::token_program=source_token_program
     )]
-    pub user_source_token_account: Account<'info, TokenAccount>,
+    pub user_source_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    pub source_mint: Account<'info, Mint>,
+    #[account(mint::token_program=source_token_program)]
+    pub source_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
-        token::mint=pool.mint
+        token::mint=pool.load()?.mint
     )]
-    pub user_pool_token_receipt: Account<'info, TokenAccount>,
+    pub user_pool_token_receipt: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
-        address=pool.mint @ ExchangeError::InvalidMint
+        address=pool.load()?.mint @ ExchangeError::InvalidMint,
+        mint::token_program=token_program
     )]
-    pub pool_mint: Account<'info, Mint>,
+    pub pool_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(mut)]
     pub user: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 
-    pub token_program: Program<'info, Token>,
+    /// Token program of the pool mint
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Token program of the source mint
+    pub source_token_program: Interface<'info, TokenInterface>,
+
+    #[account(seeds=[CONFIG], bump=config.bump)]
+    pub config: Account<'info, Config>,
 }
 
-pub fn deposit_single_token_in(ctx: Context<DepositSingleToken>, source_amount: u64) -> Result<()> {
-    let source_mint = &ctx.accounts.source_mint;
-    let pool = &ctx.accounts.pool;
-    let user_source_token_account = &ctx.accounts.user_source_token_account;
+/// Pool tokens minted for a deposit of one side, shared by `deposit_single_token` and
+/// `quote_deposit_single`
+pub fn calculate_single_token_deposit(
+    pool: &Pool,
+    config: &Config,
+    pool_token_a_account: &TokenAccount,
+    pool_token_b_account: &TokenAccount,
+    source_mint: &InterfaceAccount<Mint>,
+    pool_mint: &Mint,
+    source_amount: u64,
+) -> Result<(TradeDirection, LiquidityQuote)> {
+    let token_a_amount = pool.token_a_reserve(pool_token_a_account.amount)?;
+    let token_b_amount = pool.token_b_reserve(pool_token_b_account.amount)?;
+
+    let (trade_direction, pool_source_amount, pool_other_amount) =
+        if cmp_pubkeys(&source_mint.key(), &pool.token_a_mint) {
+            (TradeDirection::TokenAtoB, token_a_amount, token_b_amount)
+        } else if cmp_pubkeys(&source_mint.key(), &pool.token_b_mint) {
+            (TradeDirection::TokenBtoA, token_b_amount, token_a_amount)
+        } else {
+            return Err(ExchangeError::InvalidMint.into());
+        };
+
+    // pool tokens are minted for what the vault receives after the transfer fee
+    let source_amount_received = amount_after_transfer_fee(source_mint, source_amount)?;
+
+    let quote = quote_deposit(
+        source_amount_received,
+        pool_source_amount,
+        pool_other_amount,
+        pool_mint.supply,
+        &pool.current_fees().trade_fees(),
+        config.protocol_fee_numerator,
+        config.protocol_fee_denominator,
+    )
+    .map_err(ExchangeError::from)?;
+
+    Ok((trade_direction, quote))
+}
 
-    if !cmp_pubkeys(&source_mint.key(), &pool.token_a_mint)
-        && !cmp_pubkeys(&source_mint.key(), &pool.token_b_mint)
-    {
-        return Err(ExchangeError::InvalidMint.into());
+pub fn deposit_single_token_in<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DepositSingleToken<'info>>,
+    source_amount: u64,
+    minimum_pool_tokens_out: u64,
+) -> Result<()> {
+    if source_amount == 0 {
+        return Err(ExchangeError::ZeroTradingTokens.into());
     }
 
+    ctx.accounts.pool.load_mut()?.decay_dynamic_fee()?;
+    let pool = ctx.accounts.pool.load()?;
+    let (trade_direction, quote) = calculate_single_token_deposit(
+        &pool,
+        &ctx.accounts.config,
+        &ctx.accounts.pool_token_a_account,
+        &ctx.accounts.pool_token_b_account,
+        &ctx.accounts.source_mint,
+        &ctx.accounts.pool_mint,
+        source_amount,
+    )?;
+    if quote.pool_tokens < minimum_pool_tokens_out {
+        return Err(ExchangeError::SlippageExceeded.into());
+    }
+
+    // a native SOL source is wrapped from the user's lamports
+    let source_wrapped = wrap_native(
+        &mut ctx.accounts.user_source_token_account,
+        &ctx.accounts.user,
+        &ctx.accounts.system_program,
+        ctx.accounts.source_token_program.to_account_info(),
+        source_amount,
+    )?;
+
+    let source_mint = &ctx.accounts.source_mint;
+    let user_source_token_account = &ctx.accounts.user_source_token_account;
     if user_source_token_account.amount < source_amount {
         return Err(ExchangeError::NotEnoughFunds.into());
     }
 
-    let (_, pool_source_token_account) = if cmp_pubkeys(&source_mint.key(), &pool.token_a_mint) {
-        (
-            TradeDirection::TokenAtoB,
-            &ctx.accounts.pool_token_a_account,
-        )
-    } else {
-        (
-            TradeDirection::TokenBtoA,
-            &ctx.accounts.pool_token_b_account,
-        )
+    let pool_source_token_account = match trade_direction {
+        TradeDirection::TokenAtoB => &ctx.accounts.pool_token_a_account,
+        TradeDirection::TokenBtoA => &ctx.accounts.pool_token_b_account,
     };
 
-    let user_source_pool_tokens = calculate_pool_tokens_propotional_to_single_token_deposit(
-        source_amount as u128,
-        pool_source_token_account.amount as u128,
-        ctx.accounts.pool_mint.supply as u128,
-    )?;
-
     // transfer the source amount
-    let source_amount_transfer_accounts = Transfer {
+    let source_amount_transfer_accounts = TransferChecked {
         to: pool_source_token_account.to_account_info(),
         from: user_source_token_account.to_account_info(),
         authority: ctx.accounts.user.to_account_info(),
+        mint: source_mint.to_account_info(),
     };
 
     let source_amount_transfer_context = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.source_token_program.to_account_info(),
         source_amount_transfer_accounts,
-    );
-    transfer(source_amount_transfer_context, source_amount as u64)?;
+    )
+    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    transfer_checked_with_hook(
+        source_amount_transfer_context,
+        source_amount,
+        source_mint.decimals,
+        pool.transfer_hook_programs(),
+    )?;
 
     let pool_key = ctx.accounts.pool.key();
     let signer_seeds = &[
@@ -143,7 +218,30 @@ pub fn deposit_single_token_in(ctx: Context<DepositSingleToken>, source_amount:
         mint_pool_tokens_account,
         signer,
     );
-    mint_to(mint_pool_tokens_context, user_source_pool_tokens as u64)?;
+    mint_to(mint_pool_tokens_context, quote.pool_tokens)?;
+
+    // the temporary wrapped account is unwrapped back to lamports
+    if source_wrapped {
+        unwrap_native(
+            &ctx.accounts.user_source_token_account,
+            &ctx.accounts.user,
+            ctx.accounts.source_token_program.to_account_info(),
+        )?;
+    }
+
+    drop(pool);
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let protocol_fees = match trade_direction {
+        TradeDirection::TokenAtoB => &mut pool.protocol_fees_token_a,
+        TradeDirection::TokenBtoA => &mut pool.protocol_fees_token_b,
+    };
+    *protocol_fees = protocol_fees
+        .checked_add(quote.protocol_fee)
+        .ok_or(ExchangeError::NumeralOverflow)?;
+
+    if pool.dynamic_fee.is_enabled() {
+        pool.dynamic_fee.accumulate(quote.price_impact_bps);
+    }
 
     Ok(())
 }
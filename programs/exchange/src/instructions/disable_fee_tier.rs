@@ -0,0 +1,30 @@
+use crate::constants::CONFIG;
+use crate::errors::ExchangeError;
+use crate::Config;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct DisableFeeTier<'info> {
+    #[account(
+        mut,
+        seeds=[CONFIG],
+        bump=config.bump,
+        has_one=admin @ ExchangeError::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+/// Stops new pools from using the tier, existing pools keep their fees
+pub fn disable_fee_tier(ctx: Context<DisableFeeTier>, fee_tier: u16) -> Result<()> {
+    let tier = ctx
+        .accounts
+        .config
+        .fee_tiers
+        .get_mut(fee_tier as usize)
+        .ok_or(ExchangeError::InvalidFeeTier)?;
+    tier.enabled = false;
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use crate::constants::REGISTRY;
+use crate::errors::ExchangeError;
+use crate::{Pool, PoolRegistry};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::spl_token_2022::cmp_pubkeys;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct FindBestPool<'info> {
+    #[account(
+        seeds=[
+            REGISTRY,
+            registry.token_a_mint.as_ref(),
+            registry.token_b_mint.as_ref(),
+            &registry.fee_tier.to_le_bytes()
+        ],
+        bump=registry.bump
+    )]
+    pub registry: Account<'info, PoolRegistry>,
+}
+
+/// Returns the registered pool with the deepest liquidity.
+///
+/// Remaining accounts are `[pool, pool_token_a, pool_token_b]` triples for every
+/// registered pool, in registry order, so a caller can't leave out a deeper pool.
+pub fn find_best_pool<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FindBestPool<'info>>,
+) -> Result<Pubkey> {
+    let registry = &ctx.accounts.registry;
+
+    if ctx.remaining_accounts.len() != 3 * registry.pools.len() {
+        return Err(ExchangeError::RegisteredPoolMissing.into());
+    }
+
+    let mut best_pool: Option<(Pubkey, u128)> = None;
+    for (registered, accounts) in registry.pools.iter().zip(ctx.remaining_accounts.chunks(3)) {
+        let [pool_info, token_a_info, token_b_info] = accounts else {
+            return Err(ErrorCode::AccountNotEnoughKeys.into());
+        };
+
+        if !cmp_pubkeys(registered, pool_info.key) {
+            return Err(ExchangeError::PoolNotRegistered.into());
+        }
+
+        let pool = AccountLoader::<Pool>::try_from(pool_info)?;
+        if !Pool::is_migrated(&pool) {
+            return Err(ExchangeError::PoolNotMigrated.into());
+        }
+        let pool = pool.load()?;
+        if !cmp_pubkeys(&pool.token_a, token_a_info.key)
+            || !cmp_pubkeys(&pool.token_b, token_b_info.key)
+        {
+            return Err(ExchangeError::InvalidPoolTokenAccount.into());
+        }
+
+        let token_a = InterfaceAccount::<TokenAccount>::try_from(token_a_info)?;
+        let token_b = InterfaceAccount::<TokenAccount>::try_from(token_b_info)?;
+
+        // A * B grows with sqrt(A * B) so comparing the invariant is enough
+        let liquidity = (pool.token_a_reserve(token_a.amount)? as u128)
+            .checked_mul(pool.token_b_reserve(token_b.amount)? as u128)
+            .ok_or(ExchangeError::NumeralOverflow)?;
+
+        if liquidity > best_pool.map_or(0, |(_, best)| best) {
+            best_pool = Some((pool_info.key(), liquidity));
+        }
+    }
+
+    let (pool, _) = best_pool.ok_or(ExchangeError::NoLiquidPool)?;
+    Ok(pool)
+}
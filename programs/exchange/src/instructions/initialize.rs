@@ -1,32 +1,58 @@
-use crate::constants::{AUTHORITY, PREFIX};
+use crate::constants::{AUTHORITY, CONFIG, LOCKED_LIQUIDITY, POOL_MINT, PREFIX, REGISTRY, VAULT};
 use crate::errors::*;
+use crate::native_sol::{unwrap_native, wrap_native};
 use crate::pool::Pool;
-use crate::Fee;
+use crate::transfer_hook::{check_transfer_hook, transfer_checked_with_hook};
+use crate::{Config, DynamicFee, PoolRegistry};
 
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
 use anchor_lang::Accounts;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::mint_to;
-use anchor_spl::token::MintTo;
-use anchor_spl::token::Token;
-use anchor_spl::token::{Mint, TokenAccount};
-use anchor_spl::token_2022::spl_token_2022::cmp_pubkeys;
+use anchor_spl::token_2022::spl_token_2022::{
+    self, cmp_pubkeys,
+    extension::{BaseStateWithExtensions, StateWithExtensions},
+};
+use anchor_spl::token_interface::spl_token_metadata_interface::state::TokenMetadata;
+use anchor_spl::token_interface::{
+    mint_to, token_metadata_initialize, Mint, MintTo, Token2022, TokenAccount, TokenInterface,
+    TokenMetadataInitialize, TransferChecked,
+};
+use exchange_math::constant_product::calculate_initial_pool_tokens;
 
 #[derive(Accounts)]
+#[instruction(fee_tier: u16)]
 pub struct InitializePool<'info> {
     #[account(
         init,
         seeds=[
             PREFIX,
-            token_a.mint.key().as_ref(),
-            token_b.mint.key().as_ref(),
+            token_a_mint.key().as_ref(),
+            token_b_mint.key().as_ref(),
             creator.key().as_ref()
         ],
         bump,
         payer=creator,
         space=Pool::MAX_SIZE
     )]
-    pub pool: Box<Account<'info, Pool>>,
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        init_if_needed,
+        seeds=[
+            REGISTRY,
+            token_a_mint.key().as_ref(),
+            token_b_mint.key().as_ref(),
+            &fee_tier.to_le_bytes()
+        ],
+        bump,
+        payer=creator,
+        space=PoolRegistry::MAX_SIZE
+    )]
+    pub registry: Box<Account<'info, PoolRegistry>>,
+
+    #[account(seeds=[CONFIG], bump=config.bump)]
+    pub config: Box<Account<'info, Config>>,
 
     /// CHECK: Account seeds checked in constraints
     #[account(
@@ -39,49 +65,140 @@ pub struct InitializePool<'info> {
     )]
     pub pool_authority: AccountInfo<'info>,
 
-    /// Non-zero token A account
-    #[account(token::authority=pool_authority.key())]
-    pub token_a: Box<Account<'info, TokenAccount>>,
+    /// Token A vault owned by the pool authority
+    #[account(
+        init,
+        seeds=[
+            PREFIX,
+            pool.key().as_ref(),
+            VAULT,
+            token_a_mint.key().as_ref()
+        ],
+        bump,
+        payer=creator,
+        token::mint=token_a_mint,
+        token::authority=pool_authority,
+        token::token_program=token_a_program
+    )]
+    pub token_a: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Non-zero token B account
-    #[account(token::authority=pool_authority.key())]
-    pub token_b: Box<Account<'info, TokenAccount>>,
+    /// Token B vault owned by the pool authority
+    #[account(
+        init,
+        seeds=[
+            PREFIX,
+            pool.key().as_ref(),
+            VAULT,
+            token_b_mint.key().as_ref()
+        ],
+        bump,
+        payer=creator,
+        token::mint=token_b_mint,
+        token::authority=pool_authority,
+        token::token_program=token_b_program
+    )]
+    pub token_b: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    #[account(mint::token_program=token_a_program)]
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program=token_b_program)]
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Creator's token A moved into the vault as the initial liquidity
     #[account(
         mut,
+        token::mint=token_a_mint,
+        token::authority=creator,
+        token::token_program=token_a_program
+    )]
+    pub creator_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Creator's token B moved into the vault as the initial liquidity
+    #[account(
+        mut,
+        token::mint=token_b_mint,
+        token::authority=creator,
+        token::token_program=token_b_program
+    )]
+    pub creator_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token-2022 LP mint carrying its own metadata
+    #[account(
+        init,
+        seeds=[
+            PREFIX,
+            pool.key().as_ref(),
+            POOL_MINT
+        ],
+        bump,
+        payer=creator,
         mint::authority = pool_authority,
         mint::freeze_authority = pool_authority,
-        mint::decimals = 9
+        mint::decimals = 9,
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = pool_authority,
+        extensions::metadata_pointer::metadata_address = pool_mint
     )]
-    pub pool_mint: Box<Account<'info, Mint>>,
+    pub pool_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// pool token reciept as per the token A|B input
     #[account(
-        mut,
-        token::authority = creator,
-        token::mint = pool_mint
+        init_if_needed,
+        payer=creator,
+        associated_token::authority = creator,
+        associated_token::mint = pool_mint,
+        associated_token::token_program = token_program
     )]
-    pub user_pool_token_receipt: Box<Account<'info, TokenAccount>>,
+    pub user_pool_token_receipt: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
         payer=creator,
         associated_token::authority = pool_authority,
-        associated_token::mint = pool_mint
+        associated_token::mint = pool_mint,
+        associated_token::token_program = token_program
     )]
-    pub pool_fee_account: Box<Account<'info, TokenAccount>>,
+    pub pool_fee_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Holds the minimum liquidity pool tokens, nothing ever moves them out
+    #[account(
+        init,
+        seeds=[
+            PREFIX,
+            pool.key().as_ref(),
+            LOCKED_LIQUIDITY
+        ],
+        bump,
+        payer=creator,
+        token::mint=pool_mint,
+        token::authority=pool_authority,
+        token::token_program=token_program
+    )]
+    pub locked_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
     pub creator: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 
-    pub token_program: Program<'info, Token>,
+    /// Token program of the pool mint
+    pub token_program: Program<'info, Token2022>,
+
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    pub token_b_program: Interface<'info, TokenInterface>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn initialize(ctx: Context<InitializePool>, fees: Fee) -> Result<()> {
+pub fn initialize<'info>(
+    ctx: Context<'_, '_, 'info, 'info, InitializePool<'info>>,
+    fee_tier: u16,
+    transfer_hook_programs: Vec<Pubkey>,
+    initial_token_a_amount: u64,
+    initial_token_b_amount: u64,
+) -> Result<()> {
     let pool_mint = &ctx.accounts.pool_mint;
     let pool_authority = &ctx.accounts.pool_authority;
 
@@ -92,8 +209,47 @@ pub fn initialize(ctx: Context<InitializePool>, fees: Fee) -> Result<()> {
         return Err(ExchangeError::SameTokenMints.into());
     }
 
-    let pool = &mut ctx.accounts.pool;
-    pool.fees = fees;
+    // pools are keyed by the sorted mint pair so A/B and B/A share a registry
+    if token_a.mint > token_b.mint {
+        return Err(ExchangeError::InvalidMintOrder.into());
+    }
+
+    // mints with a frozen default account state would lock the vaults
+    if token_a.is_frozen() || token_b.is_frozen() {
+        return Err(ExchangeError::FrozenPoolTokenAccount.into());
+    }
+
+    if initial_token_a_amount == 0 || initial_token_b_amount == 0 {
+        return Err(ExchangeError::EmptyPoolTokenAccount.into());
+    }
+
+    if transfer_hook_programs.len() > Pool::MAX_TRANSFER_HOOK_PROGRAMS {
+        return Err(ExchangeError::TooManyTransferHookPrograms.into());
+    }
+    check_transfer_hook(
+        &ctx.accounts.token_a_mint.to_account_info(),
+        &transfer_hook_programs,
+    )?;
+    check_transfer_hook(
+        &ctx.accounts.token_b_mint.to_account_info(),
+        &transfer_hook_programs,
+    )?;
+
+    let tier = ctx
+        .accounts
+        .config
+        .fee_tiers
+        .get(fee_tier as usize)
+        .ok_or(ExchangeError::InvalidFeeTier)?;
+    if !tier.enabled {
+        return Err(ExchangeError::FeeTierDisabled.into());
+    }
+
+    let mut pool = ctx.accounts.pool.load_init()?;
+    pool.set_transfer_hook_programs(&transfer_hook_programs)?;
+    pool.fees = tier.fees;
+    pool.fee_tier = fee_tier;
+    pool.dynamic_fee = DynamicFee::new(tier.dynamic_fee, Clock::get()?.unix_timestamp);
     pool.token_a = token_a.key();
     pool.token_b = token_b.key();
     pool.token_a_mint = ctx.accounts.token_a.mint;
@@ -102,13 +258,12 @@ pub fn initialize(ctx: Context<InitializePool>, fees: Fee) -> Result<()> {
     pool.creator = ctx.accounts.creator.key();
     pool.fee_account = ctx.accounts.pool_fee_account.key();
 
-    // todo: validate fees
-
-    let initial_supply: u64 = Pool::INITIAL_POOL_TOKEN_SUPPLY;
     let bump = ctx.bumps.pool;
     pool.bump = bump;
+    pool.version = Pool::VERSION;
+    drop(pool);
 
-    let pool_key = pool.key();
+    let pool_key = ctx.accounts.pool.key();
     let signer_seeds = &[
         PREFIX,
         pool_key.as_ref(),
@@ -117,6 +272,140 @@ pub fn initialize(ctx: Context<InitializePool>, fees: Fee) -> Result<()> {
     ];
     let signer = &[&signer_seeds[..]];
 
+    let registry = &mut ctx.accounts.registry;
+    if registry.pools.is_empty() {
+        registry.bump = ctx.bumps.registry;
+        registry.token_a_mint = token_a.mint;
+        registry.token_b_mint = token_b.mint;
+        registry.fee_tier = fee_tier;
+    }
+    if registry.pools.len() >= PoolRegistry::MAX_POOLS {
+        return Err(ExchangeError::RegistryFull.into());
+    }
+    registry.pools.push(pool_key);
+
+    // a native SOL side is wrapped from the creator's lamports
+    let token_a_wrapped = wrap_native(
+        &mut ctx.accounts.creator_token_a,
+        &ctx.accounts.creator,
+        &ctx.accounts.system_program,
+        ctx.accounts.token_a_program.to_account_info(),
+        initial_token_a_amount,
+    )?;
+    let token_b_wrapped = wrap_native(
+        &mut ctx.accounts.creator_token_b,
+        &ctx.accounts.creator,
+        &ctx.accounts.system_program,
+        ctx.accounts.token_b_program.to_account_info(),
+        initial_token_b_amount,
+    )?;
+
+    // move the creator's initial liquidity into the vaults
+    let cpi_context = CpiContext::new(
+        ctx.accounts.token_a_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.creator_token_a.to_account_info(),
+            to: ctx.accounts.token_a.to_account_info(),
+            authority: ctx.accounts.creator.to_account_info(),
+            mint: ctx.accounts.token_a_mint.to_account_info(),
+        },
+    )
+    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    transfer_checked_with_hook(
+        cpi_context,
+        initial_token_a_amount,
+        ctx.accounts.token_a_mint.decimals,
+        &transfer_hook_programs,
+    )?;
+
+    let cpi_context = CpiContext::new(
+        ctx.accounts.token_b_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.creator_token_b.to_account_info(),
+            to: ctx.accounts.token_b.to_account_info(),
+            authority: ctx.accounts.creator.to_account_info(),
+            mint: ctx.accounts.token_b_mint.to_account_info(),
+        },
+    )
+    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    transfer_checked_with_hook(
+        cpi_context,
+        initial_token_b_amount,
+        ctx.accounts.token_b_mint.decimals,
+        &transfer_hook_programs,
+    )?;
+
+    // transfer fees may leave nothing behind
+    ctx.accounts.token_a.reload()?;
+    ctx.accounts.token_b.reload()?;
+    if ctx.accounts.token_a.amount == 0 || ctx.accounts.token_b.amount == 0 {
+        return Err(ExchangeError::EmptyPoolTokenAccount.into());
+    }
+
+    let initial_supply = calculate_initial_pool_tokens(
+        ctx.accounts.token_a.amount as u128,
+        ctx.accounts.token_b.amount as u128,
+    )
+    .map_err(ExchangeError::from)?;
+    let initial_supply =
+        u64::try_from(initial_supply).map_err(|_| ExchangeError::ConversionFailure)?;
+    let creator_pool_tokens = initial_supply
+        .checked_sub(Pool::MINIMUM_LIQUIDITY)
+        .filter(|amount| *amount > 0)
+        .ok_or(ExchangeError::InsufficientInitialLiquidity)?;
+
+    let label_a = mint_label(&ctx.accounts.token_a_mint.to_account_info())?;
+    let label_b = mint_label(&ctx.accounts.token_b_mint.to_account_info())?;
+    let metadata = TokenMetadata {
+        name: format!("{}-{} LP", label_a, label_b),
+        symbol: format!("{}-{}", label_a, label_b),
+        ..Default::default()
+    };
+
+    // the metadata is appended to the mint account, which must be rent exempt
+    // for its new size before the token program reallocates it
+    let pool_mint_info = pool_mint.to_account_info();
+    let new_len = pool_mint_info.data_len() + metadata.tlv_size_of()?;
+    let lamports = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(pool_mint_info.lamports());
+    if lamports > 0 {
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.creator.to_account_info(),
+                to: pool_mint_info.clone(),
+            },
+        );
+        transfer(cpi_context, lamports)?;
+    }
+
+    let cpi_context = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TokenMetadataInitialize {
+            token_program_id: ctx.accounts.token_program.to_account_info(),
+            metadata: pool_mint_info.clone(),
+            update_authority: pool_authority.to_account_info(),
+            mint_authority: pool_authority.to_account_info(),
+            mint: pool_mint_info,
+        },
+        signer,
+    );
+    token_metadata_initialize(cpi_context, metadata.name, metadata.symbol, metadata.uri)?;
+
+    let cpi_accounts = MintTo {
+        mint: pool_mint.to_account_info(),
+        to: ctx.accounts.locked_liquidity.to_account_info(),
+        authority: pool_authority.to_account_info(),
+    };
+
+    let cpi_context = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    mint_to(cpi_context, Pool::MINIMUM_LIQUIDITY)?;
+
     let cpi_accounts = MintTo {
         mint: pool_mint.to_account_info(),
         to: ctx.accounts.user_pool_token_receipt.to_account_info(),
@@ -128,7 +417,37 @@ pub fn initialize(ctx: Context<InitializePool>, fees: Fee) -> Result<()> {
         cpi_accounts,
         signer,
     );
-    mint_to(cpi_context, initial_supply)?;
+    mint_to(cpi_context, creator_pool_tokens)?;
+
+    if token_a_wrapped {
+        unwrap_native(
+            &ctx.accounts.creator_token_a,
+            &ctx.accounts.creator,
+            ctx.accounts.token_a_program.to_account_info(),
+        )?;
+    }
+    if token_b_wrapped {
+        unwrap_native(
+            &ctx.accounts.creator_token_b,
+            &ctx.accounts.creator,
+            ctx.accounts.token_b_program.to_account_info(),
+        )?;
+    }
 
     Ok(())
 }
+
+/// Symbol of the mint's token metadata, or the start of its address when it has none
+fn mint_label(mint: &AccountInfo) -> Result<String> {
+    if *mint.owner == spl_token_2022::ID {
+        let mint_data = mint.try_borrow_data()?;
+        let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+        if let Ok(metadata) = mint.get_variable_len_extension::<TokenMetadata>() {
+            if !metadata.symbol.is_empty() {
+                return Ok(metadata.symbol);
+            }
+        }
+    }
+
+    Ok(mint.key.to_string()[..4].to_string())
+}
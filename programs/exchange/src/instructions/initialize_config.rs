@@ -0,0 +1,46 @@
+use crate::constants::CONFIG;
+use crate::errors::ExchangeError;
+use crate::program::Exchange;
+use crate::Config;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        seeds=[CONFIG],
+        bump,
+        payer=admin,
+        space=Config::MAX_SIZE
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Only the upgrade authority of the program can create the config
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, Exchange>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ ExchangeError::InvalidAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.bump = ctx.bumps.config;
+    config.admin = ctx.accounts.admin.key();
+    config.treasury = ctx.accounts.admin.key();
+    config.protocol_fee_numerator = 0;
+    config.protocol_fee_denominator = 1;
+    config.host_fee_numerator = 0;
+    config.host_fee_denominator = 1;
+    config.fee_tiers = Vec::new();
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use crate::constants::PREFIX;
+use crate::errors::ExchangeError;
+use crate::{LegacyPool, Pool};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    /// CHECK: Older layouts can be shorter than the zero-copy one, so the
+    /// discriminator and seeds are checked by `migrate_pool` on the raw data
+    #[account(mut, owner=crate::ID)]
+    pub pool: UncheckedAccount<'info>,
+
+    /// Pays the rent when the current layout needs more space
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Rewrites a Borsh pool of an older layout in the current zero-copy one
+pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+    let pool_info = ctx.accounts.pool.to_account_info();
+    let pool = {
+        let data = pool_info.try_borrow_data()?;
+        if data.get(..8) != Some(Pool::DISCRIMINATOR.as_slice()) {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        // legacy layouts never have the size of the current one
+        if data.len() == Pool::MAX_SIZE {
+            return Err(ExchangeError::PoolAlreadyMigrated.into());
+        }
+
+        let legacy = LegacyPool::try_from_account_data(&data)?;
+        let mut pool = Pool::try_from(legacy)?;
+        pool.version = Pool::VERSION;
+        pool
+    };
+
+    // the bump and keys are read from the data, the seeds of the other instructions
+    let address = Pubkey::create_program_address(
+        &[
+            PREFIX,
+            pool.token_a_mint.as_ref(),
+            pool.token_b_mint.as_ref(),
+            pool.creator.as_ref(),
+            &[pool.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::ConstraintSeeds)?;
+    if address != pool_info.key() {
+        return Err(ErrorCode::ConstraintSeeds.into());
+    }
+
+    // every older layout is smaller, the payer tops up the rent
+    let lamports = Rent::get()?
+        .minimum_balance(Pool::MAX_SIZE)
+        .saturating_sub(pool_info.lamports());
+    if lamports > 0 {
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.payer.to_account_info(),
+                to: pool_info.clone(),
+            },
+        );
+        transfer(cpi_context, lamports)?;
+    }
+    pool_info.realloc(Pool::MAX_SIZE, false)?;
+
+    pool_info.try_borrow_mut_data()?[8..].copy_from_slice(bytemuck::bytes_of(&pool));
+
+    Ok(())
+}
@@ -1,15 +1,42 @@
+pub mod add_fee_tier;
+pub mod close_pool;
+pub mod collect_protocol_fees;
 pub mod deposit_all_tokens;
 pub mod deposit_single_token;
+pub mod disable_fee_tier;
+pub mod find_best_pool;
 pub mod initialize;
+pub mod initialize_config;
+pub mod migrate_pool;
+pub mod quote_deposit_single;
+pub mod quote_swap;
+pub mod quote_withdraw_single;
+pub mod set_host_fee;
+pub mod set_protocol_fee;
 pub mod swap;
+pub mod unregister_pool;
 pub mod withdraw_single_token_out;
 
+pub use add_fee_tier::*;
+pub use close_pool::*;
+pub use collect_protocol_fees::*;
 pub use deposit_all_tokens::*;
 pub use deposit_single_token::*;
+pub use disable_fee_tier::*;
+pub use find_best_pool::*;
 pub use initialize::*;
+pub use initialize_config::*;
+pub use migrate_pool::*;
+pub use quote_deposit_single::*;
+pub use quote_swap::*;
+pub use quote_withdraw_single::*;
+pub use set_host_fee::*;
+pub use set_protocol_fee::*;
 pub use swap::*;
+pub use unregister_pool::*;
 pub use withdraw_single_token_out::*;
 
+#[derive(Clone, Copy)]
 pub enum TradeDirection {
     TokenAtoB,
     TokenBtoA,
@@ -0,0 +1,61 @@
+use crate::constants::{CONFIG, PREFIX};
+use crate::errors::ExchangeError;
+use crate::quote::LiquidityQuote;
+use crate::{Config, Pool};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use super::calculate_single_token_deposit;
+
+#[derive(Accounts)]
+pub struct QuoteDepositSingle<'info> {
+    #[account(
+        seeds=[
+            PREFIX,
+            Pool::load_migrated(&pool)?.token_a_mint.as_ref(),
+            Pool::load_migrated(&pool)?.token_b_mint.as_ref(),
+            Pool::load_migrated(&pool)?.creator.as_ref()
+        ],
+        bump=Pool::load_migrated(&pool)?.bump
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(address=pool.load()?.token_a @ ExchangeError::InvalidPoolTokenAccount)]
+    pub pool_token_a_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address=pool.load()?.token_b @ ExchangeError::InvalidPoolTokenAccount)]
+    pub pool_token_b_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub source_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(address=pool.load()?.mint @ ExchangeError::InvalidMint)]
+    pub pool_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(seeds=[CONFIG], bump=config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+/// Returns the pool tokens `deposit_single_token` would mint for `source_amount`
+pub fn quote_deposit_single(
+    ctx: Context<QuoteDepositSingle>,
+    source_amount: u64,
+) -> Result<LiquidityQuote> {
+    // the decay is only applied to a copy, the pool account is left untouched
+    let mut pool = *ctx.accounts.pool.load()?;
+    pool.decay_dynamic_fee()?;
+
+    let (_, quote) = calculate_single_token_deposit(
+        &pool,
+        &ctx.accounts.config,
+        &ctx.accounts.pool_token_a_account,
+        &ctx.accounts.pool_token_b_account,
+        &ctx.accounts.source_mint,
+        &ctx.accounts.pool_mint,
+        source_amount,
+    )?;
+
+    Ok(LiquidityQuote {
+        pool_tokens: quote.pool_tokens,
+        price_impact_bps: quote.price_impact_bps,
+    })
+}
@@ -0,0 +1,80 @@
+use crate::constants::{CONFIG, PREFIX};
+use crate::errors::ExchangeError;
+use crate::quote::SwapQuote;
+use crate::transfer_fee::amount_after_transfer_fee;
+use crate::{Config, Pool};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use super::{calculate_swap, SwapAmounts};
+
+#[derive(Accounts)]
+pub struct QuoteSwap<'info> {
+    #[account(
+        seeds=[
+            PREFIX,
+            Pool::load_migrated(&pool)?.token_a_mint.as_ref(),
+            Pool::load_migrated(&pool)?.token_b_mint.as_ref(),
+            Pool::load_migrated(&pool)?.creator.as_ref()
+        ],
+        bump=Pool::load_migrated(&pool)?.bump
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(seeds=[CONFIG], bump=config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        constraint=pool_source_token_account.key() == pool.load()?.token_a
+            || pool_source_token_account.key() == pool.load()?.token_b
+            @ ExchangeError::InvalidPoolTokenAccount,
+        token::mint=source_mint
+    )]
+    pub pool_source_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        constraint=pool_destination_token_account.key() == pool.load()?.token_a
+            || pool_destination_token_account.key() == pool.load()?.token_b
+            @ ExchangeError::InvalidPoolTokenAccount,
+        constraint=pool_destination_token_account.key() != pool_source_token_account.key()
+            @ ExchangeError::SameTokenMints,
+        token::mint=destination_mint
+    )]
+    pub pool_destination_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub source_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub destination_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+/// Returns what `swap` would do for `source_amount` without moving any tokens
+pub fn quote_swap(ctx: Context<QuoteSwap>, source_amount: u64) -> Result<SwapQuote> {
+    // the decay is only applied to a copy, the pool account is left untouched
+    let mut pool = *ctx.accounts.pool.load()?;
+    pool.decay_dynamic_fee()?;
+
+    let SwapAmounts {
+        source_transfer_amount,
+        quote,
+        ..
+    } = calculate_swap(
+        &pool,
+        &ctx.accounts.config,
+        &ctx.accounts.pool_source_token_account,
+        &ctx.accounts.pool_destination_token_account,
+        &ctx.accounts.source_mint,
+        source_amount,
+    )?;
+
+    Ok(SwapQuote {
+        source_amount: source_transfer_amount,
+        destination_amount: amount_after_transfer_fee(
+            &ctx.accounts.destination_mint,
+            quote.destination_amount,
+        )?,
+        trade_fee: quote.trade_fee,
+        owner_fee: quote.owner_fee,
+        protocol_fee: quote.protocol_fee,
+        price_impact_bps: quote.price_impact_bps,
+    })
+}
@@ -0,0 +1,33 @@
+use crate::constants::CONFIG;
+use crate::errors::ExchangeError;
+use crate::Config;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetHostFee<'info> {
+    #[account(
+        mut,
+        seeds=[CONFIG],
+        bump=config.bump,
+        has_one=admin @ ExchangeError::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn set_host_fee(
+    ctx: Context<SetHostFee>,
+    host_fee_numerator: u64,
+    host_fee_denominator: u64,
+) -> Result<()> {
+    if host_fee_denominator == 0 || host_fee_numerator > host_fee_denominator {
+        return Err(ExchangeError::InvalidFee.into());
+    }
+
+    let config = &mut ctx.accounts.config;
+    config.host_fee_numerator = host_fee_numerator;
+    config.host_fee_denominator = host_fee_denominator;
+
+    Ok(())
+}
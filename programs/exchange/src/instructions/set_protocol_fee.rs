@@ -0,0 +1,35 @@
+use crate::constants::CONFIG;
+use crate::errors::ExchangeError;
+use crate::Config;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetProtocolFee<'info> {
+    #[account(
+        mut,
+        seeds=[CONFIG],
+        bump=config.bump,
+        has_one=admin @ ExchangeError::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn set_protocol_fee(
+    ctx: Context<SetProtocolFee>,
+    protocol_fee_numerator: u64,
+    protocol_fee_denominator: u64,
+    treasury: Pubkey,
+) -> Result<()> {
+    if protocol_fee_denominator == 0 || protocol_fee_numerator > protocol_fee_denominator {
+        return Err(ExchangeError::InvalidFee.into());
+    }
+
+    let config = &mut ctx.accounts.config;
+    config.protocol_fee_numerator = protocol_fee_numerator;
+    config.protocol_fee_denominator = protocol_fee_denominator;
+    config.treasury = treasury;
+
+    Ok(())
+}
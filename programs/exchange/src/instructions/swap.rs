@@ -1,10 +1,17 @@
 use super::TradeDirection;
-use crate::constants::{AUTHORITY, PREFIX};
+use crate::constants::{AUTHORITY, CONFIG, PREFIX};
 use crate::errors::ExchangeError;
-use crate::{curve::constant_product::*, Pool};
+use crate::native_sol::{is_temporary_native, unwrap_native, wrap_native};
+use crate::transfer_fee::{amount_after_transfer_fee, amount_before_transfer_fee};
+use crate::transfer_hook::transfer_checked_with_hook;
+use crate::{Config, Pool};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{mint_to, transfer, Mint, MintTo, Token, TokenAccount, Transfer};
 use anchor_spl::token_interface::spl_token_2022::cmp_pubkeys;
+use anchor_spl::token_interface::{
+    mint_to, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
+use exchange_math::constant_product::*;
+use exchange_math::quote::quote_swap;
 
 #[derive(Accounts)]
 pub struct Swap<'info> {
@@ -20,52 +27,82 @@ pub struct Swap<'info> {
     pub pool_authority: AccountInfo<'info>,
 
     #[account(
+        mut,
         seeds=[
             PREFIX,
-            pool_token_a_account.mint.as_ref(),
-            pool_token_b_account.mint.as_ref(),
+            Pool::load_migrated(&pool)?.token_a_mint.as_ref(),
+            Pool::load_migrated(&pool)?.token_b_mint.as_ref(),
             creator.key().as_ref()
         ],
-        bump
+        bump=Pool::load_migrated(&pool)?.bump
     )]
-    pub pool: Account<'info, Pool>,
+    pub pool: AccountLoader<'info, Pool>,
 
-    /// Non-zero token A account
+    #[account(seeds=[CONFIG], bump=config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// Pool vault receiving the source tokens
     #[account(
         mut,
-        address=pool.token_a @ ExchangeError::InvalidPoolTokenAccount,
-        token::authority=pool_authority.key()
+        constraint=pool_source_token_account.key() == pool.load()?.token_a
+            || pool_source_token_account.key() == pool.load()?.token_b
+            @ ExchangeError::InvalidPoolTokenAccount,
+        token::mint=source_mint,
+        token::authority=pool_authority.key(),
+        token::token_program=source_token_program
     )]
-    pub pool_token_a_account: Account<'info, TokenAccount>,
+    pub pool_source_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Non-zero token B account
+    /// Pool vault paying out the destination tokens
     #[account(
         mut,
-        address=pool.token_b @ ExchangeError::InvalidPoolTokenAccount,
-        token::authority=pool_authority.key()
+        constraint=pool_destination_token_account.key() == pool.load()?.token_a
+            || pool_destination_token_account.key() == pool.load()?.token_b
+            @ ExchangeError::InvalidPoolTokenAccount,
+        constraint=pool_destination_token_account.key() != pool_source_token_account.key()
+            @ ExchangeError::SameTokenMints,
+        token::mint=destination_mint,
+        token::authority=pool_authority.key(),
+        token::token_program=destination_token_program
     )]
-    pub pool_token_b_account: Account<'info, TokenAccount>,
+    pub pool_destination_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mint::token_program=source_token_program)]
+    pub source_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program=destination_token_program)]
+    pub destination_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
+        token::mint=source_mint,
         token::authority=user.key()
     )]
-    pub user_source_token_account: Account<'info, TokenAccount>,
+    pub user_source_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
+        token::mint=destination_mint,
         token::authority=user.key()
     )]
-    pub user_destination_token_account: Account<'info, TokenAccount>,
+    pub user_destination_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
-        address=pool.mint @ ExchangeError::InvalidMint,
+        address=pool.load()?.mint @ ExchangeError::InvalidMint,
+        mint::token_program=token_program
     )]
-    pub pool_mint: Account<'info, Mint>,
+    pub pool_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, address=pool.load()?.fee_account)]
+    pub pool_token_fee_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(mut, address=pool.fee_account)]
-    pub pool_token_fee_account: Account<'info, TokenAccount>,
+    /// Pool token account of the front-end hosting the swap, takes a share of the owner fee.
+    /// Passing the program id leaves it out. It used to be the first remaining account,
+    /// but the remaining accounts are now forwarded to the transfer hooks of the mints,
+    /// where a host account couldn't be told apart from a hook account.
+    #[account(mut)]
+    pub host_fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(mut)]
     pub user: Signer<'info>,
@@ -75,80 +112,165 @@ pub struct Swap<'info> {
 
     pub system_program: Program<'info, System>,
 
-    pub token_program: Program<'info, Token>,
+    /// Token program of the pool mint
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Token program of the source mint
+    pub source_token_program: Interface<'info, TokenInterface>,
+
+    /// Token program of the destination mint
+    pub destination_token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn swap(ctx: Context<Swap>, source_amount: u64) -> Result<()> {
-    let pool = &ctx.accounts.pool;
-    let pool_mint_account = &ctx.accounts.pool_mint;
-    let source_mint = &ctx.accounts.user_source_token_account.mint;
-    let destination_mint = &ctx.accounts.user_destination_token_account.mint;
+/// Swap of a source amount against the pool vaults, shared by `swap` and `quote_swap`
+pub struct SwapAmounts {
+    pub trade_direction: TradeDirection,
+    /// Source tokens the user sends, transfer fee included
+    pub source_transfer_amount: u64,
+    /// Liquidity provider reserve of the source side once the swap is in
+    pub new_pool_source_amount: u128,
+    pub quote: exchange_math::quote::SwapQuote,
+}
 
-    if ctx.accounts.user_source_token_account.amount < source_amount {
-        return Err(ExchangeError::NotEnoughFunds.into());
+pub fn calculate_swap(
+    pool: &Pool,
+    config: &Config,
+    pool_source_token_account: &InterfaceAccount<TokenAccount>,
+    pool_destination_token_account: &InterfaceAccount<TokenAccount>,
+    source_mint: &InterfaceAccount<Mint>,
+    source_amount: u64,
+) -> Result<SwapAmounts> {
+    if source_amount == 0 {
+        return Err(ExchangeError::ZeroTradingTokens.into());
     }
 
-    let trade_direction = if cmp_pubkeys(&source_mint.key(), &pool.token_a_mint) {
-        TradeDirection::TokenAtoB
-    } else {
-        TradeDirection::TokenBtoA
-    };
+    // the vaults are constrained to the pool so the source vault fixes the direction
+    let (trade_direction, pool_source_amount, pool_destination_amount) =
+        if cmp_pubkeys(&pool_source_token_account.key(), &pool.token_a) {
+            (
+                TradeDirection::TokenAtoB,
+                pool.token_a_reserve(pool_source_token_account.amount)?,
+                pool.token_b_reserve(pool_destination_token_account.amount)?,
+            )
+        } else {
+            (
+                TradeDirection::TokenBtoA,
+                pool.token_b_reserve(pool_source_token_account.amount)?,
+                pool.token_a_reserve(pool_destination_token_account.amount)?,
+            )
+        };
 
-    let (pool_source_token_account, pool_destination_token_account) = match trade_direction {
-        TradeDirection::TokenAtoB => (
-            &ctx.accounts.pool_token_a_account,
-            &ctx.accounts.pool_token_b_account,
-        ),
-        TradeDirection::TokenBtoA => (
-            &ctx.accounts.pool_token_b_account,
-            &ctx.accounts.pool_token_a_account,
-        ),
-    };
+    // the vault only receives what is left after the transfer fee of the source mint
+    let source_amount_received = amount_after_transfer_fee(source_mint, source_amount)?;
+
+    // the protocol keeps a share of the trading fee aside from the LP reserves
+    let quote = quote_swap(
+        source_amount_received,
+        pool_source_amount,
+        pool_destination_amount,
+        &pool.current_fees().trade_fees(),
+        config.protocol_fee_numerator,
+        config.protocol_fee_denominator,
+    )
+    .map_err(ExchangeError::from)?;
 
-    if !cmp_pubkeys(&pool_source_token_account.mint.key(), &source_mint)
-        || !cmp_pubkeys(
-            &pool_destination_token_account.mint.key(),
-            &destination_mint,
-        )
-    {
-        return Err(ExchangeError::InvalidMint.into());
+    if quote.destination_amount == 0 {
+        return Err(ExchangeError::ZeroTradingTokens.into());
     }
 
-    let (
+    let new_pool_source_amount = (pool_source_amount as u128 + quote.source_amount as u128)
+        .checked_sub(quote.protocol_fee as u128)
+        .ok_or(ExchangeError::NumeralOverflow)?;
+
+    Ok(SwapAmounts {
+        trade_direction,
+        source_transfer_amount: amount_before_transfer_fee(source_mint, quote.source_amount)?,
+        new_pool_source_amount,
+        quote,
+    })
+}
+
+/// Swaps `source_amount`, failing when the user would receive less than
+/// `minimum_amount_out` once the destination mint takes its transfer fee
+pub fn swap<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Swap<'info>>,
+    source_amount: u64,
+    minimum_amount_out: u64,
+) -> Result<()> {
+    ctx.accounts.pool.load_mut()?.decay_dynamic_fee()?;
+    let pool = ctx.accounts.pool.load()?;
+
+    let SwapAmounts {
+        trade_direction,
+        source_transfer_amount,
         new_pool_source_amount,
-        _new_pool_destination_amount,
-        swapped_source_amount,
-        swapped_destination_amount,
-        owner_fee,
-        _trading_fee,
-    ) = calculate_swap_amounts(
-        source_amount as u128,
-        pool_source_token_account.amount as u128,
-        pool_destination_token_account.amount as u128,
-        &pool.fees,
+        quote,
+    } = calculate_swap(
+        &pool,
+        &ctx.accounts.config,
+        &ctx.accounts.pool_source_token_account,
+        &ctx.accounts.pool_destination_token_account,
+        &ctx.accounts.source_mint,
+        source_amount,
+    )?;
+
+    let pool_mint_account = &ctx.accounts.pool_mint;
+    let pool_source_token_account = &ctx.accounts.pool_source_token_account;
+    let pool_destination_token_account = &ctx.accounts.pool_destination_token_account;
+    let source_mint_account = &ctx.accounts.source_mint;
+    let destination_mint_account = &ctx.accounts.destination_mint;
+    let source_token_program = &ctx.accounts.source_token_program;
+    let destination_token_program = &ctx.accounts.destination_token_program;
+
+    let destination_temporary = is_temporary_native(&ctx.accounts.user_destination_token_account);
+
+    // a native SOL source is wrapped from the user's lamports
+    let source_wrapped = wrap_native(
+        &mut ctx.accounts.user_source_token_account,
+        &ctx.accounts.user,
+        &ctx.accounts.system_program,
+        source_token_program.to_account_info(),
+        source_amount,
     )?;
 
+    if ctx.accounts.user_source_token_account.amount < source_transfer_amount {
+        return Err(ExchangeError::NotEnoughFunds.into());
+    }
+
+    let amount_out = amount_after_transfer_fee(destination_mint_account, quote.destination_amount)?;
+    if amount_out < minimum_amount_out {
+        return Err(ExchangeError::SlippageExceeded.into());
+    }
+
     // transfer the swapped amounts
-    let source_transfer_accounts = Transfer {
+    let source_transfer_accounts = TransferChecked {
         authority: ctx.accounts.user.to_account_info(),
         to: pool_source_token_account.to_account_info(),
         from: ctx.accounts.user_source_token_account.to_account_info(),
+        mint: source_mint_account.to_account_info(),
     };
 
     let source_transfer_context = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
+        source_token_program.to_account_info(),
         source_transfer_accounts,
-    );
+    )
+    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
 
-    transfer(source_transfer_context, swapped_source_amount as u64)?;
+    transfer_checked_with_hook(
+        source_transfer_context,
+        source_transfer_amount,
+        source_mint_account.decimals,
+        pool.transfer_hook_programs(),
+    )?;
 
-    let destination_transfer_accounts = Transfer {
+    let destination_transfer_accounts = TransferChecked {
         authority: ctx.accounts.pool_authority.to_account_info(),
         to: ctx
             .accounts
             .user_destination_token_account
             .to_account_info(),
         from: pool_destination_token_account.to_account_info(),
+        mint: destination_mint_account.to_account_info(),
     };
 
     let pool_key = ctx.accounts.pool.key();
@@ -162,21 +284,47 @@ pub fn swap(ctx: Context<Swap>, source_amount: u64) -> Result<()> {
     let signer = &[&signer_seeds[..]];
 
     let destination_transfer_context = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
+        destination_token_program.to_account_info(),
         destination_transfer_accounts,
         signer,
-    );
-    transfer(
+    )
+    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    transfer_checked_with_hook(
         destination_transfer_context,
-        swapped_destination_amount as u64,
+        quote.destination_amount,
+        destination_mint_account.decimals,
+        pool.transfer_hook_programs(),
     )?;
 
-    // mint the pool_tokens propotional to owner_fee to pool_fee_account
-    let pool_tokens = calculate_pool_tokens_propotional_to_single_token_redeemed(
-        owner_fee,
+    // the owner fee stays in the source vault, so it is valued as a single token deposit
+    let pool_tokens = calculate_owner_fee_pool_tokens(
+        quote.owner_fee as u128,
         new_pool_source_amount,
         pool_mint_account.supply as u128,
-    )?;
+    )
+    .map_err(ExchangeError::from)?;
+
+    // the optional host fee account takes a share of the owner fee
+    let host_fee_account = &ctx.accounts.host_fee_account;
+    let host_pool_tokens = match host_fee_account {
+        Some(host_fee_account) => {
+            if !cmp_pubkeys(&host_fee_account.mint, &pool.mint) {
+                return Err(ExchangeError::InvalidHostFeeAccount.into());
+            }
+
+            calculate_fee_share(
+                pool_tokens,
+                ctx.accounts.config.host_fee_numerator,
+                ctx.accounts.config.host_fee_denominator,
+            )
+            .ok_or(ExchangeError::NumeralOverflow)?
+        }
+        None => 0,
+    };
+    let fee_account_pool_tokens = u64::try_from(pool_tokens - host_pool_tokens)
+        .map_err(|_| ExchangeError::ConversionFailure)?;
+    let host_pool_tokens =
+        u64::try_from(host_pool_tokens).map_err(|_| ExchangeError::ConversionFailure)?;
 
     let pool_mint_to_fee_account = MintTo {
         authority: ctx.accounts.pool_authority.to_account_info(),
@@ -189,7 +337,52 @@ pub fn swap(ctx: Context<Swap>, source_amount: u64) -> Result<()> {
         pool_mint_to_fee_account,
         signer,
     );
-    mint_to(pool_mint_to_context, pool_tokens as u64)?;
+    mint_to(pool_mint_to_context, fee_account_pool_tokens)?;
+
+    if let Some(host_fee_account) = host_fee_account {
+        let pool_mint_to_host_account = MintTo {
+            authority: ctx.accounts.pool_authority.to_account_info(),
+            mint: pool_mint_account.to_account_info(),
+            to: host_fee_account.to_account_info(),
+        };
+
+        let pool_mint_to_host_context = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            pool_mint_to_host_account,
+            signer,
+        );
+        mint_to(pool_mint_to_host_context, host_pool_tokens)?;
+    }
+
+    // temporary native accounts are unwrapped back to lamports
+    if source_wrapped {
+        unwrap_native(
+            &ctx.accounts.user_source_token_account,
+            &ctx.accounts.user,
+            source_token_program.to_account_info(),
+        )?;
+    }
+    if destination_temporary {
+        unwrap_native(
+            &ctx.accounts.user_destination_token_account,
+            &ctx.accounts.user,
+            destination_token_program.to_account_info(),
+        )?;
+    }
+
+    drop(pool);
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let protocol_fees = match trade_direction {
+        TradeDirection::TokenAtoB => &mut pool.protocol_fees_token_a,
+        TradeDirection::TokenBtoA => &mut pool.protocol_fees_token_b,
+    };
+    *protocol_fees = protocol_fees
+        .checked_add(quote.protocol_fee)
+        .ok_or(ExchangeError::NumeralOverflow)?;
+
+    if pool.dynamic_fee.is_enabled() {
+        pool.dynamic_fee.accumulate(quote.price_impact_bps);
+    }
 
     Ok(())
 }
@@ -0,0 +1,41 @@
+use crate::constants::{CONFIG, REGISTRY};
+use crate::errors::ExchangeError;
+use crate::{Config, PoolRegistry};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UnregisterPool<'info> {
+    #[account(
+        seeds=[CONFIG],
+        bump=config.bump,
+        has_one=admin @ ExchangeError::InvalidAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds=[
+            REGISTRY,
+            registry.token_a_mint.as_ref(),
+            registry.token_b_mint.as_ref(),
+            &registry.fee_tier.to_le_bytes()
+        ],
+        bump=registry.bump
+    )]
+    pub registry: Account<'info, PoolRegistry>,
+
+    pub admin: Signer<'info>,
+}
+
+/// Frees the slot of a pool in its registry, e.g. of dust pools filling it up.
+/// The pool keeps working, routers just stop finding it.
+pub fn unregister_pool(ctx: Context<UnregisterPool>, pool: Pubkey) -> Result<()> {
+    let pools = &mut ctx.accounts.registry.pools;
+    let index = pools
+        .iter()
+        .position(|registered| *registered == pool)
+        .ok_or(ExchangeError::PoolNotRegistered)?;
+    pools.remove(index);
+
+    Ok(())
+}
@@ -1,9 +1,17 @@
-use crate::constants::{AUTHORITY, PREFIX};
+use crate::constants::{AUTHORITY, CONFIG, PREFIX};
 use crate::errors::ExchangeError;
-use crate::{curve::constant_product::*, Pool};
+use crate::native_sol::{is_temporary_native, unwrap_native};
+use crate::transfer_fee::amount_before_transfer_fee;
+use crate::transfer_hook::transfer_checked_with_hook;
+use crate::{Config, Pool};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{burn, transfer, Burn, Mint, Token, TokenAccount, Transfer};
 use anchor_spl::token_interface::spl_token_2022::cmp_pubkeys;
+use anchor_spl::token_interface::{
+    burn, Burn, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use exchange_math::quote::{quote_withdraw, LiquidityQuote};
+
+use super::TradeDirection;
 
 #[derive(Accounts)]
 pub struct WithdrawSingleToken<'info> {
@@ -19,104 +27,154 @@ pub struct WithdrawSingleToken<'info> {
     pub pool_authority: AccountInfo<'info>,
 
     #[account(
+        mut,
         seeds=[
             PREFIX,
             pool_token_a_account.mint.as_ref(),
             pool_token_b_account.mint.as_ref(),
-            pool.creator.as_ref()
+            Pool::load_migrated(&pool)?.creator.as_ref()
         ],
         bump
     )]
-    pub pool: Account<'info, Pool>,
+    pub pool: AccountLoader<'info, Pool>,
 
     /// Non-zero token A account
     #[account(
         mut,
-        address=pool.token_a @ ExchangeError::InvalidPoolTokenAccount,
+        address=pool.load()?.token_a @ ExchangeError::InvalidPoolTokenAccount,
         token::authority=pool_authority.key()
     )]
-    pub pool_token_a_account: Account<'info, TokenAccount>,
+    pub pool_token_a_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// Non-zero token B account
     #[account(
         mut,
-        address=pool.token_b @ ExchangeError::InvalidPoolTokenAccount,
+        address=pool.load()?.token_b @ ExchangeError::InvalidPoolTokenAccount,
         token::authority=pool_authority.key()
     )]
-    pub pool_token_b_account: Account<'info, TokenAccount>,
+    pub pool_token_b_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         token::mint=source_mint,
-        token::authority=user.key()
+        token::authority=user.key(),
+        token::token_program=source_token_program
     )]
-    pub user_source_token_account: Account<'info, TokenAccount>,
+    pub user_source_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     // mint of token to be withdrawn
-    pub source_mint: Account<'info, Mint>,
+    #[account(mint::token_program=source_token_program)]
+    pub source_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
         token::mint=pool_mint,
         token::authority=user.key()
     )]
-    pub user_pool_token_receipt: Account<'info, TokenAccount>,
+    pub user_pool_token_receipt: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
-        address=pool.mint @ ExchangeError::InvalidMint
+        address=pool.load()?.mint @ ExchangeError::InvalidMint,
+        mint::token_program=token_program
     )]
-    pub pool_mint: Account<'info, Mint>,
+    pub pool_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(mut, token::mint=pool_mint)]
-    pub pool_token_fee_account: Account<'info, TokenAccount>,
+    pub pool_token_fee_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
     pub user: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 
-    pub token_program: Program<'info, Token>,
+    /// Token program of the pool mint
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Token program of the source mint
+    pub source_token_program: Interface<'info, TokenInterface>,
+
+    #[account(seeds=[CONFIG], bump=config.bump)]
+    pub config: Account<'info, Config>,
 }
 
-pub fn withdraw_single_token_out(
-    ctx: Context<WithdrawSingleToken>,
+/// Pool tokens burned for a withdrawal of one side, shared by `withdraw_single_token_out`
+/// and `quote_withdraw_single`
+pub fn calculate_single_token_withdrawal(
+    pool: &Pool,
+    config: &Config,
+    pool_token_a_account: &TokenAccount,
+    pool_token_b_account: &TokenAccount,
+    source_mint: &InterfaceAccount<Mint>,
+    pool_mint: &Mint,
     source_amount: u64,
+) -> Result<(TradeDirection, u64, LiquidityQuote)> {
+    let token_a_amount = pool.token_a_reserve(pool_token_a_account.amount)?;
+    let token_b_amount = pool.token_b_reserve(pool_token_b_account.amount)?;
+
+    let (trade_direction, pool_source_amount, pool_other_amount) =
+        if cmp_pubkeys(&source_mint.key(), &pool.token_a_mint) {
+            (TradeDirection::TokenAtoB, token_a_amount, token_b_amount)
+        } else if cmp_pubkeys(&source_mint.key(), &pool.token_b_mint) {
+            (TradeDirection::TokenBtoA, token_b_amount, token_a_amount)
+        } else {
+            return Err(ExchangeError::InvalidMint.into());
+        };
+
+    // the vault sends enough for the user to receive the amount after the transfer fee
+    let withdraw_amount = amount_before_transfer_fee(source_mint, source_amount)?;
+
+    let quote = quote_withdraw(
+        withdraw_amount,
+        pool_source_amount,
+        pool_other_amount,
+        pool_mint.supply,
+        &pool.current_fees().trade_fees(),
+        config.protocol_fee_numerator,
+        config.protocol_fee_denominator,
+    )
+    .map_err(ExchangeError::from)?;
+
+    Ok((trade_direction, withdraw_amount, quote))
+}
+
+pub fn withdraw_single_token_out<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawSingleToken<'info>>,
+    source_amount: u64,
+    maximum_pool_tokens_burned: u64,
 ) -> Result<()> {
-    let pool = &ctx.accounts.pool;
+    if source_amount == 0 {
+        return Err(ExchangeError::ZeroTradingTokens.into());
+    }
+
+    ctx.accounts.pool.load_mut()?.decay_dynamic_fee()?;
+    let pool = ctx.accounts.pool.load()?;
+    let (trade_direction, withdraw_amount, quote) = calculate_single_token_withdrawal(
+        &pool,
+        &ctx.accounts.config,
+        &ctx.accounts.pool_token_a_account,
+        &ctx.accounts.pool_token_b_account,
+        &ctx.accounts.source_mint,
+        &ctx.accounts.pool_mint,
+        source_amount,
+    )?;
+
     let user_pool_token_account = &ctx.accounts.user_pool_token_receipt;
-    let user_source_token_account = &ctx.accounts.user_source_token_account;
     let source_mint_account = &ctx.accounts.source_mint;
     let pool_mint = &ctx.accounts.pool_mint;
+    let pool_source_token_account = match trade_direction {
+        TradeDirection::TokenAtoB => &ctx.accounts.pool_token_a_account,
+        TradeDirection::TokenBtoA => &ctx.accounts.pool_token_b_account,
+    };
 
-    if user_source_token_account.amount < source_amount {
-        return Err(ExchangeError::NotEnoughFunds.into());
+    let burn_pool_token_amount = quote.pool_tokens;
+    if burn_pool_token_amount > maximum_pool_tokens_burned {
+        return Err(ExchangeError::SlippageExceeded.into());
     }
-
-    let (pool_source_token_account, _) =
-        if cmp_pubkeys(&source_mint_account.key(), &pool.token_a_mint) {
-            (
-                &ctx.accounts.pool_token_a_account,
-                &ctx.accounts.pool_token_b_account,
-            )
-        } else {
-            (
-                &ctx.accounts.pool_token_b_account,
-                &ctx.accounts.pool_token_a_account,
-            )
-        };
-
-    let burn_pool_token_amount = calculate_pool_tokens_propotional_to_single_token_redeemed(
-        source_amount as u128,
-        pool_source_token_account.amount as u128,
-        pool_mint.supply as u128,
-    )? as u64;
-
     if user_pool_token_account.amount < burn_pool_token_amount {
         return Err(ExchangeError::NotEnoughFunds.into());
     }
-
-    // Todo: transfer withdraw fee
+    let destination_temporary = is_temporary_native(&ctx.accounts.user_source_token_account);
 
     let pool_key = ctx.accounts.pool.key();
     let signer_seeds = &[
@@ -142,18 +200,48 @@ pub fn withdraw_single_token_out(
     burn(burn_pool_tokens_context, burn_pool_token_amount)?;
 
     // transfer the withdrawal source amount
-    let source_amount_transfer_accounts = Transfer {
+    let source_amount_transfer_accounts = TransferChecked {
         to: ctx.accounts.user_source_token_account.to_account_info(),
         from: pool_source_token_account.to_account_info(),
         authority: ctx.accounts.pool_authority.to_account_info(),
+        mint: source_mint_account.to_account_info(),
     };
 
     let source_amount_transfer_context = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.source_token_program.to_account_info(),
         source_amount_transfer_accounts,
         signer,
-    );
-    transfer(source_amount_transfer_context, source_amount as u64)?;
+    )
+    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    transfer_checked_with_hook(
+        source_amount_transfer_context,
+        withdraw_amount,
+        source_mint_account.decimals,
+        pool.transfer_hook_programs(),
+    )?;
+
+    // a temporary native account is unwrapped back to lamports
+    if destination_temporary {
+        unwrap_native(
+            &ctx.accounts.user_source_token_account,
+            &ctx.accounts.user,
+            ctx.accounts.source_token_program.to_account_info(),
+        )?;
+    }
+
+    drop(pool);
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let protocol_fees = match trade_direction {
+        TradeDirection::TokenAtoB => &mut pool.protocol_fees_token_a,
+        TradeDirection::TokenBtoA => &mut pool.protocol_fees_token_b,
+    };
+    *protocol_fees = protocol_fees
+        .checked_add(quote.protocol_fee)
+        .ok_or(ExchangeError::NumeralOverflow)?;
+
+    if pool.dynamic_fee.is_enabled() {
+        pool.dynamic_fee.accumulate(quote.price_impact_bps);
+    }
 
     Ok(())
 }
@@ -1,13 +1,17 @@
 #![allow(unexpected_cfgs)]
 
 use anchor_lang::prelude::*;
-mod constants;
-mod curve;
-mod errors;
+pub mod constants;
+pub mod errors;
 mod instructions;
-mod state;
+mod native_sol;
+pub mod quote;
+pub mod state;
+mod transfer_fee;
+mod transfer_hook;
 
 use instructions::*;
+use quote::*;
 use state::*;
 
 declare_id!("HndsTUfB2AZbQifHN9WdKMMQqXghGVwmak2gy3oyzwqV");
@@ -16,16 +20,88 @@ declare_id!("HndsTUfB2AZbQifHN9WdKMMQqXghGVwmak2gy3oyzwqV");
 pub mod exchange {
     use super::*;
 
-    pub fn initialize(ctx: Context<InitializePool>, fees: Fee) -> Result<()> {
-        instructions::initialize(ctx, fees)
+    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+        instructions::initialize_config(ctx)
     }
 
-    pub fn swap(ctx: Context<Swap>, source_amount: u64) -> Result<()> {
-        instructions::swap(ctx, source_amount)
+    pub fn add_fee_tier(
+        ctx: Context<AddFeeTier>,
+        fees: Fee,
+        dynamic_fee: Option<DynamicFeeConfig>,
+    ) -> Result<()> {
+        instructions::add_fee_tier(ctx, fees, dynamic_fee)
+    }
+
+    pub fn disable_fee_tier(ctx: Context<DisableFeeTier>, fee_tier: u16) -> Result<()> {
+        instructions::disable_fee_tier(ctx, fee_tier)
+    }
+
+    pub fn set_protocol_fee(
+        ctx: Context<SetProtocolFee>,
+        protocol_fee_numerator: u64,
+        protocol_fee_denominator: u64,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        instructions::set_protocol_fee(
+            ctx,
+            protocol_fee_numerator,
+            protocol_fee_denominator,
+            treasury,
+        )
+    }
+
+    pub fn set_host_fee(
+        ctx: Context<SetHostFee>,
+        host_fee_numerator: u64,
+        host_fee_denominator: u64,
+    ) -> Result<()> {
+        instructions::set_host_fee(ctx, host_fee_numerator, host_fee_denominator)
     }
 
-    pub fn deposit_all_tokens_in(
-        ctx: Context<DepositAllTokens>,
+    pub fn unregister_pool(ctx: Context<UnregisterPool>, pool: Pubkey) -> Result<()> {
+        instructions::unregister_pool(ctx, pool)
+    }
+
+    pub fn collect_protocol_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CollectProtocolFees<'info>>,
+    ) -> Result<()> {
+        instructions::collect_protocol_fees(ctx)
+    }
+
+    pub fn initialize<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitializePool<'info>>,
+        fee_tier: u16,
+        transfer_hook_programs: Vec<Pubkey>,
+        initial_token_a_amount: u64,
+        initial_token_b_amount: u64,
+    ) -> Result<()> {
+        instructions::initialize(
+            ctx,
+            fee_tier,
+            transfer_hook_programs,
+            initial_token_a_amount,
+            initial_token_b_amount,
+        )
+    }
+
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        instructions::migrate_pool(ctx)
+    }
+
+    pub fn close_pool<'info>(ctx: Context<'_, '_, 'info, 'info, ClosePool<'info>>) -> Result<()> {
+        instructions::close_pool(ctx)
+    }
+
+    pub fn swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Swap<'info>>,
+        source_amount: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        instructions::swap(ctx, source_amount, minimum_amount_out)
+    }
+
+    pub fn deposit_all_tokens_in<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DepositAllTokens<'info>>,
         pool_tokens: u64,
         max_token_a: u64,
         max_token_b: u64,
@@ -33,17 +109,43 @@ pub mod exchange {
         instructions::deposit_all_tokens_in(ctx, pool_tokens, max_token_a, max_token_b)
     }
 
-    pub fn deposit_single_token(
-        ctx: Context<DepositSingleToken>,
+    pub fn deposit_single_token<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DepositSingleToken<'info>>,
         source_amount: u64,
+        minimum_pool_tokens_out: u64,
     ) -> Result<()> {
-        instructions::deposit_single_token_in(ctx, source_amount)
+        instructions::deposit_single_token_in(ctx, source_amount, minimum_pool_tokens_out)
     }
 
-    pub fn withdraw_single_token_out(
-        ctx: Context<WithdrawSingleToken>,
+    pub fn withdraw_single_token_out<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawSingleToken<'info>>,
         source_amount: u64,
+        maximum_pool_tokens_burned: u64,
     ) -> Result<()> {
-        instructions::withdraw_single_token_out(ctx, source_amount)
+        instructions::withdraw_single_token_out(ctx, source_amount, maximum_pool_tokens_burned)
+    }
+
+    pub fn find_best_pool<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FindBestPool<'info>>,
+    ) -> Result<Pubkey> {
+        instructions::find_best_pool(ctx)
+    }
+
+    pub fn quote_swap(ctx: Context<QuoteSwap>, source_amount: u64) -> Result<SwapQuote> {
+        instructions::quote_swap(ctx, source_amount)
+    }
+
+    pub fn quote_deposit_single(
+        ctx: Context<QuoteDepositSingle>,
+        source_amount: u64,
+    ) -> Result<LiquidityQuote> {
+        instructions::quote_deposit_single(ctx, source_amount)
+    }
+
+    pub fn quote_withdraw_single(
+        ctx: Context<QuoteWithdrawSingle>,
+        source_amount: u64,
+    ) -> Result<LiquidityQuote> {
+        instructions::quote_withdraw_single(ctx, source_amount)
     }
 }
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token_interface::{
+    close_account, sync_native, CloseAccount, SyncNative, TokenAccount,
+};
+
+/// Whether `token_account` is an empty native mint account, a temporary account
+/// created for the instruction that it closes once done. Native accounts already
+/// holding wrapped SOL belong to the user and are left open.
+pub fn is_temporary_native(token_account: &InterfaceAccount<TokenAccount>) -> bool {
+    token_account.is_native() && token_account.amount == 0
+}
+
+/// Wraps lamports of `user` into a native mint `token_account` until it holds
+/// `amount`. Returns whether it only holds what was wrapped, see
+/// [`is_temporary_native`].
+pub fn wrap_native<'info>(
+    token_account: &mut InterfaceAccount<'info, TokenAccount>,
+    user: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<bool> {
+    if !token_account.is_native() {
+        return Ok(false);
+    }
+    let temporary = is_temporary_native(token_account);
+
+    let shortfall = amount.saturating_sub(token_account.amount);
+    if shortfall == 0 {
+        return Ok(false);
+    }
+
+    let cpi_context = CpiContext::new(
+        system_program.to_account_info(),
+        Transfer {
+            from: user.to_account_info(),
+            to: token_account.to_account_info(),
+        },
+    );
+    transfer(cpi_context, shortfall)?;
+
+    let cpi_context = CpiContext::new(
+        token_program,
+        SyncNative {
+            account: token_account.to_account_info(),
+        },
+    );
+    sync_native(cpi_context)?;
+
+    token_account.reload()?;
+    Ok(temporary)
+}
+
+/// Closes a temporary native mint `token_account` of `user`, returning the
+/// wrapped lamports and its rent to the user
+pub fn unwrap_native<'info>(
+    token_account: &InterfaceAccount<'info, TokenAccount>,
+    user: &Signer<'info>,
+    token_program: AccountInfo<'info>,
+) -> Result<()> {
+    if !token_account.is_native() {
+        return Ok(());
+    }
+
+    let cpi_context = CpiContext::new(
+        token_program,
+        CloseAccount {
+            account: token_account.to_account_info(),
+            destination: user.to_account_info(),
+            authority: user.to_account_info(),
+        },
+    );
+    close_account(cpi_context)
+}
@@ -0,0 +1,25 @@
+//! Return data of the quote instructions.
+
+use anchor_lang::prelude::*;
+
+/// Returned by `quote_swap`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapQuote {
+    /// Source tokens sent by the user, transfer fee included
+    pub source_amount: u64,
+    /// Destination tokens received by the user, net of the transfer fee
+    pub destination_amount: u64,
+    pub trade_fee: u64,
+    pub owner_fee: u64,
+    /// Share of the trade fee set aside for the protocol
+    pub protocol_fee: u64,
+    pub price_impact_bps: u64,
+}
+
+/// Returned by `quote_deposit_single` and `quote_withdraw_single`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LiquidityQuote {
+    /// Pool tokens minted on a deposit, or burned on a withdrawal
+    pub pool_tokens: u64,
+    pub price_impact_bps: u64,
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{DynamicFeeConfig, Fee};
+
+#[account]
+pub struct FeeTier {
+    pub fees: Fee,
+    pub enabled: bool,
+    /// Pools of the tier scale their trade fee with volatility when set
+    pub dynamic_fee: Option<DynamicFeeConfig>,
+}
+
+impl FeeTier {
+    pub const MAX_SIZE: usize = Fee::MAX_SIZE + 1 + 1 + DynamicFeeConfig::MAX_SIZE;
+}
+
+/// Program wide settings controlled by the admin
+#[account]
+pub struct Config {
+    pub bump: u8,
+    pub admin: Pubkey,
+    /// Authority allowed to collect the protocol fees
+    pub treasury: Pubkey,
+    /// Share of the trading fee kept by the protocol, zero turns the switch off
+    pub protocol_fee_numerator: u64,
+    pub protocol_fee_denominator: u64,
+    /// Share of the owner fee pool tokens paid to the host of a swap
+    pub host_fee_numerator: u64,
+    pub host_fee_denominator: u64,
+    pub fee_tiers: Vec<FeeTier>,
+}
+
+impl Config {
+    pub const MAX_FEE_TIERS: usize = 16;
+    pub const MAX_SIZE: usize =
+        8 + 1 + 32 * 2 + 8 * 4 + 4 + FeeTier::MAX_SIZE * Self::MAX_FEE_TIERS;
+}
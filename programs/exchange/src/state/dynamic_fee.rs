@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ExchangeError;
+use crate::Fee;
+
+/// Bounds of a trade fee that follows the volatility of the pool
+#[account]
+#[derive(Copy, Default, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+pub struct DynamicFeeConfig {
+    /// Trade fee numerator over `Fee::trade_fee_denominator` when the market is calm
+    pub min_fee_numerator: u64,
+    pub max_fee_numerator: u64,
+    /// Volatility in bps at which the max fee is charged
+    pub max_volatility: u64,
+    /// Seconds for the volatility accumulator to decay to zero
+    pub decay_period: i64,
+}
+
+impl DynamicFeeConfig {
+    pub const MAX_SIZE: usize = 4 * 8;
+
+    pub fn validate(&self, fees: &Fee) -> Result<()> {
+        if self.min_fee_numerator > self.max_fee_numerator
+            || self.max_fee_numerator >= fees.trade_fee_denominator
+            || self.max_volatility == 0
+            || self.decay_period <= 0
+        {
+            return Err(ExchangeError::InvalidFee.into());
+        }
+
+        Ok(())
+    }
+}
+
+#[zero_copy]
+#[derive(Default)]
+pub struct DynamicFee {
+    pub config: DynamicFeeConfig,
+    /// Volatility decayed up to `last_update_timestamp`
+    pub volatility_accumulator: u64,
+    pub last_update_timestamp: i64,
+    /// Non zero when the pool's tier has a dynamic fee
+    pub enabled: u8,
+    pub padding: [u8; 7],
+    /// Volatility right after the last trade that moved the price, which
+    /// `decay` starts from so that decaying in several steps stays linear
+    pub volatility_reference: u64,
+    pub reference_timestamp: i64,
+}
+
+impl DynamicFee {
+    pub fn new(config: Option<DynamicFeeConfig>, timestamp: i64) -> Self {
+        Self {
+            config: config.unwrap_or_default(),
+            volatility_accumulator: 0,
+            last_update_timestamp: timestamp,
+            enabled: config.is_some().into(),
+            padding: [0; 7],
+            volatility_reference: 0,
+            reference_timestamp: timestamp,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != 0
+    }
+
+    /// Linearly decays the reference volatility over the decay period
+    pub fn decay(&mut self, timestamp: i64) {
+        let elapsed = timestamp.saturating_sub(self.reference_timestamp).max(0);
+        self.volatility_accumulator = if elapsed >= self.config.decay_period {
+            0
+        } else {
+            let remaining = (self.config.decay_period - elapsed) as u128;
+            (self.volatility_reference as u128 * remaining / self.config.decay_period as u128)
+                as u64
+        };
+        self.last_update_timestamp = timestamp;
+    }
+
+    /// Adds the price move of a trade to the volatility decayed up to now, which
+    /// becomes the new reference
+    pub fn accumulate(&mut self, price_move_bps: u64) {
+        if price_move_bps == 0 {
+            return;
+        }
+
+        self.volatility_accumulator = self
+            .volatility_accumulator
+            .saturating_add(price_move_bps)
+            .min(self.config.max_volatility);
+        self.volatility_reference = self.volatility_accumulator;
+        self.reference_timestamp = self.last_update_timestamp;
+    }
+
+    /// Trade fee numerator scaled between the bounds by the accumulated volatility
+    pub fn trade_fee_numerator(&self) -> u64 {
+        let config = &self.config;
+        let fee_range = (config.max_fee_numerator - config.min_fee_numerator) as u128;
+        let volatility = self.volatility_accumulator.min(config.max_volatility) as u128;
+
+        config.min_fee_numerator + (fee_range * volatility / config.max_volatility as u128) as u64
+    }
+}
@@ -1,6 +1,11 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::ExchangeError;
+use exchange_math::TradeFees;
+
 #[account]
+#[derive(Copy, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
 pub struct Fee {
     pub trade_fee_numerator: u64,
     pub trade_fee_denominator: u64,
@@ -14,4 +19,36 @@ pub struct Fee {
 
 impl Fee {
     pub const MAX_SIZE: usize = 6 * 8;
+
+    pub fn validate(&self) -> Result<()> {
+        let fractions = [
+            (self.trade_fee_numerator, self.trade_fee_denominator),
+            (
+                self.owner_trade_fee_numerator,
+                self.owner_trade_fee_denominator,
+            ),
+            (
+                self.owner_withdraw_fee_numerator,
+                self.owner_withdraw_fee_denomiator,
+            ),
+        ];
+
+        for (numerator, denominator) in fractions {
+            if denominator == 0 || numerator >= denominator {
+                return Err(ExchangeError::InvalidFee.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fees of a swap, the withdraw fee aside
+    pub fn trade_fees(&self) -> TradeFees {
+        TradeFees {
+            trade_fee_numerator: self.trade_fee_numerator,
+            trade_fee_denominator: self.trade_fee_denominator,
+            owner_trade_fee_numerator: self.owner_trade_fee_numerator,
+            owner_trade_fee_denominator: self.owner_trade_fee_denominator,
+        }
+    }
 }
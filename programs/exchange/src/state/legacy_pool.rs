@@ -0,0 +1,189 @@
+use std::io::{Read, Write};
+
+use anchor_lang::prelude::*;
+
+use crate::{DynamicFeeConfig, Fee, Pool};
+
+/// Version of the Borsh pool layout followed by space reserved for later fields.
+///
+/// Pools created before the layout was versioned end with the transfer hook
+/// programs, their missing bytes read as zeros, i.e. version 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolLayout {
+    pub version: u8,
+    pub reserved: [u8; PoolLayout::RESERVED],
+}
+
+impl PoolLayout {
+    pub const RESERVED: usize = 127;
+    pub const MAX_SIZE: usize = 1 + Self::RESERVED;
+}
+
+impl Default for PoolLayout {
+    fn default() -> Self {
+        Self {
+            version: 0,
+            reserved: [0; Self::RESERVED],
+        }
+    }
+}
+
+impl AnchorSerialize for PoolLayout {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&[self.version])?;
+        writer.write_all(&self.reserved)
+    }
+}
+
+impl AnchorDeserialize for PoolLayout {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut data = [0; Self::MAX_SIZE];
+        let mut read = 0;
+        while read < data.len() {
+            match reader.read(&mut data[read..])? {
+                0 => break,
+                len => read += len,
+            }
+        }
+
+        let mut reserved = [0; Self::RESERVED];
+        reserved.copy_from_slice(&data[1..]);
+        Ok(Self {
+            version: data[0],
+            reserved,
+        })
+    }
+}
+
+/// Dynamic fee of a Borsh pool
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct LegacyDynamicFee {
+    pub enabled: bool,
+    pub config: DynamicFeeConfig,
+    pub volatility_accumulator: u64,
+    pub last_update_timestamp: i64,
+}
+
+/// Borsh layout of the pools of versions 0 and 1, read by `migrate_pool`.
+///
+/// The first pools, of the [`BaselinePool`] layout, read as version 0. Every
+/// layout shares the discriminator of the zero-copy [`Pool`] and its leading
+/// bump and keys, while its size tells it apart, so `Pool::load_migrated`
+/// rejects them.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyPool {
+    pub bump: u8,
+    pub token_a: Pubkey,
+    pub token_b: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub fee_account: Pubkey,
+    pub fees: Fee,
+    pub fee_tier: u16,
+    pub protocol_fees_token_a: u64,
+    pub protocol_fees_token_b: u64,
+    pub dynamic_fee: LegacyDynamicFee,
+    pub transfer_hook_programs: Vec<Pubkey>,
+    pub layout: PoolLayout,
+}
+
+impl LegacyPool {
+    /// Size of the version 1 pools
+    pub const MAX_SIZE: usize = Self::UNVERSIONED_SIZE + PoolLayout::MAX_SIZE;
+    /// Size of the pools created before the layout was versioned
+    pub const UNVERSIONED_SIZE: usize = 512;
+    /// Size of the first pools, see [`BaselinePool`]
+    pub const BASELINE_SIZE: usize = BaselinePool::MAX_SIZE;
+
+    /// Account data, discriminator included
+    pub fn try_from_account_data(data: &[u8]) -> Result<Self> {
+        let len = data.len();
+        let mut data = data
+            .get(8..)
+            .ok_or(ErrorCode::AccountDiscriminatorNotFound)?;
+        let legacy = if len == Self::BASELINE_SIZE {
+            BaselinePool::deserialize(&mut data).map(Self::from)
+        } else {
+            Self::deserialize(&mut data)
+        };
+        legacy.map_err(|_| ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+/// Borsh layout of the first pools, before fee tiers, protocol fees, dynamic
+/// fees and transfer hooks. Their size left room for one more key, unused.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BaselinePool {
+    pub bump: u8,
+    pub token_a: Pubkey,
+    pub token_b: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub fee_account: Pubkey,
+    pub fees: Fee,
+}
+
+impl BaselinePool {
+    pub const MAX_SIZE: usize = 8 + 1 + 32 * 8 + Fee::MAX_SIZE;
+}
+
+impl From<BaselinePool> for LegacyPool {
+    /// Keeps the pool's own fees under the default fee tier, with the dynamic
+    /// fee off and no transfer hook programs
+    fn from(baseline: BaselinePool) -> Self {
+        Self {
+            bump: baseline.bump,
+            token_a: baseline.token_a,
+            token_b: baseline.token_b,
+            token_a_mint: baseline.token_a_mint,
+            token_b_mint: baseline.token_b_mint,
+            mint: baseline.mint,
+            creator: baseline.creator,
+            fee_account: baseline.fee_account,
+            fees: baseline.fees,
+            fee_tier: 0,
+            protocol_fees_token_a: 0,
+            protocol_fees_token_b: 0,
+            dynamic_fee: LegacyDynamicFee::default(),
+            transfer_hook_programs: Vec::new(),
+            layout: PoolLayout::default(),
+        }
+    }
+}
+
+impl TryFrom<LegacyPool> for Pool {
+    type Error = Error;
+
+    /// Keeps the legacy layout version, `migrate_pool` sets the current one
+    fn try_from(legacy: LegacyPool) -> Result<Self> {
+        let mut pool = Pool {
+            bump: legacy.bump,
+            token_a: legacy.token_a,
+            token_b: legacy.token_b,
+            token_a_mint: legacy.token_a_mint,
+            token_b_mint: legacy.token_b_mint,
+            mint: legacy.mint,
+            creator: legacy.creator,
+            fee_account: legacy.fee_account,
+            version: legacy.layout.version,
+            fee_tier: legacy.fee_tier,
+            fees: legacy.fees,
+            protocol_fees_token_a: legacy.protocol_fees_token_a,
+            protocol_fees_token_b: legacy.protocol_fees_token_b,
+            ..bytemuck::Zeroable::zeroed()
+        };
+        pool.dynamic_fee.config = legacy.dynamic_fee.config;
+        pool.dynamic_fee.volatility_accumulator = legacy.dynamic_fee.volatility_accumulator;
+        pool.dynamic_fee.last_update_timestamp = legacy.dynamic_fee.last_update_timestamp;
+        // the Borsh pools decayed from their last update
+        pool.dynamic_fee.volatility_reference = legacy.dynamic_fee.volatility_accumulator;
+        pool.dynamic_fee.reference_timestamp = legacy.dynamic_fee.last_update_timestamp;
+        pool.dynamic_fee.enabled = legacy.dynamic_fee.enabled.into();
+        pool.set_transfer_hook_programs(&legacy.transfer_hook_programs)?;
+        Ok(pool)
+    }
+}
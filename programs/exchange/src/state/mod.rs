@@ -1,5 +1,13 @@
+pub mod config;
+pub mod dynamic_fee;
 pub mod fee;
+pub mod legacy_pool;
 pub mod pool;
+pub mod registry;
 
+pub use config::*;
+pub use dynamic_fee::*;
 pub use fee::*;
+pub use legacy_pool::*;
 pub use pool::*;
+pub use registry::*;
@@ -1,7 +1,17 @@
+use std::cell::Ref;
+
 use anchor_lang::prelude::*;
 
-use crate::Fee;
-#[account]
+use crate::errors::ExchangeError;
+use crate::{DynamicFee, Fee, LegacyPool};
+
+/// Pool state read in place by every instruction.
+///
+/// The bump and keys lead at the offsets of the older Borsh layouts and the
+/// padding is explicit. Older pools can be shorter than this layout, so
+/// instructions go through [`Pool::load_migrated`], which checks the size of
+/// the account before reading it.
+#[account(zero_copy)]
 pub struct Pool {
     pub bump: u8,
     pub token_a: Pubkey,
@@ -11,10 +21,96 @@ pub struct Pool {
     pub mint: Pubkey,
     pub creator: Pubkey,
     pub fee_account: Pubkey,
+    /// Layout written by `initialize` and `migrate_pool`
+    pub version: u8,
+    pub fee_tier: u16,
+    pub transfer_hook_program_count: u8,
+    pub padding: [u8; 3],
     pub fees: Fee,
+    /// Protocol fees held in the vaults until the treasury collects them
+    pub protocol_fees_token_a: u64,
+    pub protocol_fees_token_b: u64,
+    pub dynamic_fee: DynamicFee,
+    /// Transfer hook programs the pool's mints are allowed to invoke, see
+    /// `transfer_hook_programs`
+    pub transfer_hook_program_slots: [Pubkey; Pool::MAX_TRANSFER_HOOK_PROGRAMS],
+    /// Space for later fields, so they don't need another migration
+    pub reserved: [u8; 144],
 }
 
+// the account size alone tells the current layout apart from the legacy ones,
+// and being larger than all of them no migration shrinks a pool
+const _: () = assert!(
+    Pool::MAX_SIZE > LegacyPool::BASELINE_SIZE
+        && Pool::MAX_SIZE > LegacyPool::UNVERSIONED_SIZE
+        && Pool::MAX_SIZE > LegacyPool::MAX_SIZE
+);
+
 impl Pool {
-    pub const MAX_SIZE: usize = 8 + 1 + 32 * 8 + Fee::MAX_SIZE;
-    pub const INITIAL_POOL_TOKEN_SUPPLY: u64 = 1000_000_000;
+    pub const MAX_SIZE: usize = 8 + std::mem::size_of::<Pool>();
+    /// Layout written by `initialize` and `migrate_pool`, 2 went zero-copy
+    pub const VERSION: u8 = 2;
+    /// Pool tokens locked away on initialize so the share price can't be inflated cheaply
+    pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+    pub const MAX_TRANSFER_HOOK_PROGRAMS: usize = 4;
+
+    /// Pools of an older layout must go through `migrate_pool` first
+    pub fn is_migrated(pool: &AccountLoader<Pool>) -> bool {
+        pool.as_ref().data_len() == Self::MAX_SIZE
+            && pool.load().is_ok_and(|pool| pool.version == Self::VERSION)
+    }
+
+    /// Loads a pool of the current layout, without reading past the data of an older one
+    pub fn load_migrated<'a>(pool: &'a AccountLoader<Pool>) -> Result<Ref<'a, Pool>> {
+        if !Self::is_migrated(pool) {
+            return Err(ExchangeError::PoolNotMigrated.into());
+        }
+        pool.load()
+    }
+
+    pub fn transfer_hook_programs(&self) -> &[Pubkey] {
+        &self.transfer_hook_program_slots[..self.transfer_hook_program_count as usize]
+    }
+
+    pub fn set_transfer_hook_programs(&mut self, programs: &[Pubkey]) -> Result<()> {
+        if programs.len() > Self::MAX_TRANSFER_HOOK_PROGRAMS {
+            return Err(ExchangeError::TooManyTransferHookPrograms.into());
+        }
+        self.transfer_hook_program_slots = Default::default();
+        self.transfer_hook_program_slots[..programs.len()].copy_from_slice(programs);
+        self.transfer_hook_program_count = programs.len() as u8;
+        Ok(())
+    }
+
+    /// Decays the volatility of a dynamic fee up to the current time
+    pub fn decay_dynamic_fee(&mut self) -> Result<()> {
+        if self.dynamic_fee.is_enabled() {
+            let timestamp = Clock::get()?.unix_timestamp;
+            self.dynamic_fee.decay(timestamp);
+        }
+        Ok(())
+    }
+
+    /// Fees charged on the next swap, with the trade fee following volatility when dynamic
+    pub fn current_fees(&self) -> Fee {
+        let mut fees = self.fees;
+        if self.dynamic_fee.is_enabled() {
+            fees.trade_fee_numerator = self.dynamic_fee.trade_fee_numerator();
+        }
+        fees
+    }
+
+    /// Token A owned by the liquidity providers
+    pub fn token_a_reserve(&self, vault_amount: u64) -> Result<u64> {
+        vault_amount
+            .checked_sub(self.protocol_fees_token_a)
+            .ok_or(ExchangeError::NumeralOverflow.into())
+    }
+
+    /// Token B owned by the liquidity providers
+    pub fn token_b_reserve(&self, vault_amount: u64) -> Result<u64> {
+        vault_amount
+            .checked_sub(self.protocol_fees_token_b)
+            .ok_or(ExchangeError::NumeralOverflow.into())
+    }
 }
@@ -0,0 +1,16 @@
+use anchor_lang::prelude::*;
+
+/// Registry of every pool created for a canonical (sorted) mint pair and fee tier
+#[account]
+pub struct PoolRegistry {
+    pub bump: u8,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub fee_tier: u16,
+    pub pools: Vec<Pubkey>,
+}
+
+impl PoolRegistry {
+    pub const MAX_POOLS: usize = 16;
+    pub const MAX_SIZE: usize = 8 + 1 + 32 * 2 + 2 + 4 + 32 * Self::MAX_POOLS;
+}
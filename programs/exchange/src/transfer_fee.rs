@@ -0,0 +1,71 @@
+use crate::errors::ExchangeError;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::spl_token_2022::{
+    self,
+    extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+};
+use anchor_spl::token_interface::{
+    harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint, Mint,
+};
+
+fn transfer_fee_config(mint: &InterfaceAccount<Mint>) -> Result<Option<TransferFeeConfig>> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+
+    Ok(mint.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+/// Amount received when `amount` is sent, net of the transfer fee at the current epoch
+pub fn amount_after_transfer_fee(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let Some(config) = transfer_fee_config(mint)? else {
+        return Ok(amount);
+    };
+
+    let epoch = Clock::get()?.epoch;
+    let fee = config
+        .calculate_epoch_fee(epoch, amount)
+        .ok_or(ExchangeError::NumeralOverflow)?;
+
+    amount
+        .checked_sub(fee)
+        .ok_or(ExchangeError::NumeralOverflow.into())
+}
+
+/// Amount to send so that `amount` is received after the transfer fee at the current epoch
+pub fn amount_before_transfer_fee(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let Some(config) = transfer_fee_config(mint)? else {
+        return Ok(amount);
+    };
+
+    let epoch = Clock::get()?.epoch;
+    config
+        .get_epoch_fee(epoch)
+        .calculate_pre_fee_amount(amount)
+        .ok_or(ExchangeError::NumeralOverflow.into())
+}
+
+/// Moves the transfer fees withheld in `token_account` to the mint, Token-2022
+/// only closes accounts with nothing withheld
+pub fn harvest_withheld_fees<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+    token_account: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+) -> Result<()> {
+    if transfer_fee_config(mint)?.is_none() {
+        return Ok(());
+    }
+
+    let cpi_context = CpiContext::new(
+        token_program.clone(),
+        HarvestWithheldTokensToMint {
+            token_program_id: token_program,
+            mint: mint.to_account_info(),
+        },
+    );
+    harvest_withheld_tokens_to_mint(cpi_context, vec![token_account])
+}
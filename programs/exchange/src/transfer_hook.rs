@@ -0,0 +1,49 @@
+use crate::errors::ExchangeError;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::spl_token_2022::{
+    self,
+    extension::{transfer_hook, StateWithExtensions},
+    onchain::invoke_transfer_checked,
+};
+use anchor_spl::token_interface::TransferChecked;
+
+/// Fails when the mint has a transfer hook program outside of the allow-list
+pub fn check_transfer_hook(mint: &AccountInfo, allowed_hook_programs: &[Pubkey]) -> Result<()> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+
+    match transfer_hook::get_program_id(&mint) {
+        Some(program_id) if !allowed_hook_programs.contains(&program_id) => {
+            Err(ExchangeError::TransferHookNotAllowed.into())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// `transfer_checked` that forwards the remaining accounts of the context as the
+/// extra accounts of the mint's transfer hook
+pub fn transfer_checked_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+    allowed_hook_programs: &[Pubkey],
+) -> Result<()> {
+    check_transfer_hook(&ctx.accounts.mint, allowed_hook_programs)?;
+
+    invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )
+    .map_err(Into::into)
+}
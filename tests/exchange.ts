@@ -3,11 +3,22 @@ import { Program } from "@coral-xyz/anchor";
 import { Exchange } from "../target/types/exchange";
 import { BN } from "bn.js";
 import {
+  createAssociatedTokenAccountIdempotentInstruction,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createInitializeTransferHookInstruction,
   createMint,
+  createSyncNativeInstruction,
+  ExtensionType,
   getAssociatedTokenAddressSync,
+  getMintLen,
   getOrCreateAssociatedTokenAccount,
+  getTokenMetadata,
   MintLayout,
   mintTo,
+  NATIVE_MINT,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import { assert } from "chai";
 
@@ -28,6 +39,7 @@ describe("exchange", () => {
   new anchor.Program(program.idl as anchor.Idl, newProvider);
 
   const connection = provider.connection;
+
   const creator = anchor.web3.Keypair.generate();
   const base = 1000_000_000;
   const tradeFeeNumerator = new BN(5);
@@ -37,8 +49,22 @@ describe("exchange", () => {
   const ownerWithdrawFeeNumerator = new BN(1);
   const ownerWithdrawFeeDenomiator = new BN(100);
 
+  // trade and owner fees, rounded up, on the half of a single token amount
+  // traded against the other side of the pool
+  const singleTokenFees = (amount: number) => {
+    const half = Math.max(1, Math.floor(amount / 2));
+    const fee = (numerator: BN, denominator: BN) =>
+      Math.ceil((half * Number(numerator)) / Number(denominator));
+    return (
+      fee(tradeFeeNumerator, tradeFeeDenominator) +
+      fee(ownerTradeFeeNumerator, ownerTradeFeeDenominator)
+    );
+  };
+
   let tokenA: anchor.web3.PublicKey;
   let tokenB: anchor.web3.PublicKey;
+  let creatorTokenA: anchor.web3.PublicKey;
+  let creatorTokenB: anchor.web3.PublicKey;
   let tokenAMint: anchor.web3.PublicKey;
   let tokenBMint: anchor.web3.PublicKey;
   let creatorPoolTokenReceipt: anchor.web3.PublicKey;
@@ -47,6 +73,15 @@ describe("exchange", () => {
   let pool: anchor.web3.PublicKey;
   let poolMint: anchor.web3.PublicKey;
   let poolAuthority: anchor.web3.PublicKey;
+  let registry: anchor.web3.PublicKey;
+  let config: anchor.web3.PublicKey;
+  const feeTier = 0;
+  const treasury = anchor.web3.Keypair.generate();
+  const protocolFeeNumerator = new BN(1);
+  const protocolFeeDenominator = new BN(5);
+  const host = anchor.web3.Keypair.generate();
+  const hostFeeNumerator = new BN(1);
+  const hostFeeDenominator = new BN(2);
 
   before(async () => {
     // airdrops
@@ -79,6 +114,10 @@ describe("exchange", () => {
       null,
       9
     );
+    // pools only accept the canonical (sorted) mint order
+    if (Buffer.compare(tokenAMint.toBuffer(), tokenBMint.toBuffer()) > 0) {
+      [tokenAMint, tokenBMint] = [tokenBMint, tokenAMint];
+    }
 
     //pdas
     pool = anchor.web3.PublicKey.findProgramAddressSync(
@@ -94,82 +133,205 @@ describe("exchange", () => {
       [Buffer.from("pool"), pool.toBuffer(), Buffer.from("authority")],
       program.programId
     )[0];
+    registry = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("registry"),
+        tokenAMint.toBuffer(),
+        tokenBMint.toBuffer(),
+        new BN(feeTier).toArrayLike(Buffer, "le", 2),
+      ],
+      program.programId
+    )[0];
+    config = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    )[0];
 
-    //pool mint
-    poolMint = await createMint(
-      connection,
-      creator,
-      poolAuthority,
-      poolAuthority,
-      9
-    );
+    poolMint = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), pool.toBuffer(), Buffer.from("mint")],
+      program.programId
+    )[0];
+
+    //pool vaults created by initialize
+    tokenA = getVaultAddress(program.programId, pool, tokenAMint);
+    tokenB = getVaultAddress(program.programId, pool, tokenBMint);
 
-    //initialize pool token accounts
-    tokenA = (
+    //creator's initial liquidity
+    creatorTokenA = (
       await getOrCreateAssociatedTokenAccount(
         connection,
         creator,
         tokenAMint,
-        poolAuthority,
-        true
+        creator.publicKey
       )
     ).address;
-    tokenB = (
+    creatorTokenB = (
       await getOrCreateAssociatedTokenAccount(
         connection,
         creator,
         tokenBMint,
-        poolAuthority,
-        true
+        creator.publicKey
       )
     ).address;
 
-    await mintTo(connection, payer, tokenAMint, tokenA, creator, 1000 * base);
-    await mintTo(connection, payer, tokenBMint, tokenB, creator, 1000 * base);
+    await mintTo(
+      connection,
+      payer,
+      tokenAMint,
+      creatorTokenA,
+      creator,
+      1000 * base
+    );
+    await mintTo(
+      connection,
+      payer,
+      tokenBMint,
+      creatorTokenB,
+      creator,
+      1000 * base
+    );
 
     //fee and creator pool token receipt accounts
     poolFeeAccount = getAssociatedTokenAddressSync(
       poolMint,
       poolAuthority,
-      true
+      true,
+      TOKEN_2022_PROGRAM_ID
     );
-    creatorPoolTokenReceipt = (
-      await getOrCreateAssociatedTokenAccount(
-        connection,
-        creator,
-        poolMint,
-        creator.publicKey,
-        false
+    creatorPoolTokenReceipt = getAssociatedTokenAddressSync(
+      poolMint,
+      creator.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+  });
+
+  it("test initialize config ok", async () => {
+    // the deploy wallet is the upgrade authority of the program
+    const admin = provider.wallet as anchor.Wallet;
+    const [programData] = anchor.web3.PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new anchor.web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+
+    await program.methods
+      .initializeConfig()
+      .accountsPartial({
+        config,
+        admin: admin.publicKey,
+        program: program.programId,
+        programData,
+      })
+      .signers([admin.payer])
+      .rpc();
+
+    await program.methods
+      .addFeeTier(
+        {
+          tradeFeeNumerator,
+          tradeFeeDenominator,
+          ownerTradeFeeNumerator,
+          ownerTradeFeeDenominator,
+          ownerWithdrawFeeNumerator,
+          ownerWithdrawFeeDenomiator,
+        },
+        null
       )
-    ).address;
-    userPoolTokenReceipt = (
-      await getOrCreateAssociatedTokenAccount(
-        connection,
-        payer,
-        poolMint,
-        payer.publicKey,
-        false
+      .accountsPartial({ config, admin: admin.publicKey })
+      .signers([admin.payer])
+      .rpc();
+
+    // a second tier that gets disabled right away
+    await program.methods
+      .addFeeTier(
+        {
+          tradeFeeNumerator: new BN(1),
+          tradeFeeDenominator: new BN(10_000),
+          ownerTradeFeeNumerator,
+          ownerTradeFeeDenominator,
+          ownerWithdrawFeeNumerator,
+          ownerWithdrawFeeDenomiator,
+        },
+        null
       )
-    ).address;
+      .accountsPartial({ config, admin: admin.publicKey })
+      .signers([admin.payer])
+      .rpc();
+    // a tier whose trade fee follows volatility between 1% and 10%
+    await program.methods
+      .addFeeTier(
+        {
+          tradeFeeNumerator,
+          tradeFeeDenominator,
+          ownerTradeFeeNumerator,
+          ownerTradeFeeDenominator,
+          ownerWithdrawFeeNumerator,
+          ownerWithdrawFeeDenomiator,
+        },
+        {
+          minFeeNumerator: new BN(1),
+          maxFeeNumerator: new BN(10),
+          maxVolatility: new BN(500),
+          decayPeriod: new BN(600),
+        }
+      )
+      .accountsPartial({ config, admin: admin.publicKey })
+      .signers([admin.payer])
+      .rpc();
+    await program.methods
+      .disableFeeTier(1)
+      .accountsPartial({ config, admin: admin.publicKey })
+      .signers([admin.payer])
+      .rpc();
+
+    await program.methods
+      .setProtocolFee(
+        protocolFeeNumerator,
+        protocolFeeDenominator,
+        treasury.publicKey
+      )
+      .accountsPartial({ config, admin: admin.publicKey })
+      .signers([admin.payer])
+      .rpc();
+
+    await program.methods
+      .setHostFee(hostFeeNumerator, hostFeeDenominator)
+      .accountsPartial({ config, admin: admin.publicKey })
+      .signers([admin.payer])
+      .rpc();
+
+    const configState = await program.account.config.fetch(config);
+    assert.deepEqual(configState.admin, admin.publicKey);
+    assert.deepEqual(configState.treasury, treasury.publicKey);
+    assert.equal(configState.feeTiers.length, 3);
+    assert.isTrue(configState.feeTiers[0].enabled);
+    assert.isNull(configState.feeTiers[0].dynamicFee);
+    assert.isFalse(configState.feeTiers[1].enabled);
+    assert.equal(
+      Number(configState.feeTiers[2].dynamicFee.maxFeeNumerator),
+      10
+    );
   });
 
   it("test initialize pool ok", async () => {
     const txSig = await program.methods
-      .initialize({
-        tradeFeeNumerator,
-        tradeFeeDenominator,
-        ownerTradeFeeNumerator,
-        ownerTradeFeeDenominator,
-        ownerWithdrawFeeNumerator,
-        ownerWithdrawFeeDenomiator,
-      })
+      .initialize(feeTier, [], new BN(1000 * base), new BN(1000 * base))
       .accountsPartial({
         tokenA,
         tokenB,
+        tokenAMint,
+        tokenBMint,
+        creatorTokenA,
+        creatorTokenB,
+        registry,
+        config,
         poolMint,
         poolFeeAccount,
         userPoolTokenReceipt: creatorPoolTokenReceipt,
         creator: creator.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
       })
       .signers([creator])
       .rpc({ skipPreflight: true });
@@ -182,6 +344,7 @@ describe("exchange", () => {
     assert.deepEqual(poolState.tokenB, tokenB);
     assert.deepEqual(poolState.tokenAMint, tokenAMint);
     assert.deepEqual(poolState.tokenBMint, tokenBMint);
+    assert.equal(poolState.feeTier, feeTier);
     assert.equal(
       Number(poolState.fees.ownerTradeFeeNumerator),
       Number(ownerTradeFeeNumerator)
@@ -209,11 +372,64 @@ describe("exchange", () => {
 
     const poolMintInfo = await connection.getAccountInfo(poolMint);
     const poolMintData = MintLayout.decode(new Uint8Array(poolMintInfo.data));
-    assert.equal(Number(poolMintData.supply), 1000_000_000);
+    // sqrt(A * B) of the initial liquidity with the minimum liquidity locked
+    const minimumLiquidity = 1_000;
+    const lockedLiquidity = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), pool.toBuffer(), Buffer.from("locked")],
+      program.programId
+    )[0];
+    assert.equal(Number(poolMintData.supply), 1000 * base);
+    assert.equal(
+      await getTokenAmount(connection, lockedLiquidity),
+      minimumLiquidity
+    );
+    assert.equal(
+      await getTokenAmount(connection, creatorPoolTokenReceipt),
+      1000 * base - minimumLiquidity
+    );
+
+    const metadata = await getTokenMetadata(connection, poolMint);
+    const label = (mint: anchor.web3.PublicKey) => mint.toBase58().slice(0, 4);
+    assert.equal(metadata.name, `${label(tokenAMint)}-${label(tokenBMint)} LP`);
+    assert.equal(metadata.symbol, `${label(tokenAMint)}-${label(tokenBMint)}`);
+    assert.deepEqual(metadata.updateAuthority, poolAuthority);
+
+    // the pool mint only exists once the pool is initialized
+    userPoolTokenReceipt = (
+      await getOrCreateAssociatedTokenAccount(
+        connection,
+        payer,
+        poolMint,
+        payer.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).address;
+
+    const registryState = await program.account.poolRegistry.fetch(registry);
+    assert.deepEqual(registryState.tokenAMint, tokenAMint);
+    assert.deepEqual(registryState.tokenBMint, tokenBMint);
+    assert.deepEqual(registryState.pools, [pool]);
 
     console.log("Your transaction signature", txSig);
   });
 
+  it("test find best pool ok", async () => {
+    const bestPool = await program.methods
+      .findBestPool()
+      .accountsPartial({ registry })
+      .remainingAccounts([
+        { pubkey: pool, isSigner: false, isWritable: false },
+        { pubkey: tokenA, isSigner: false, isWritable: false },
+        { pubkey: tokenB, isSigner: false, isWritable: false },
+      ])
+      .view();
+
+    assert.deepEqual(bestPool, pool);
+  });
+
   it("test deposit all tokens ok", async () => {
     const tokenAAmount = await getTokenAmount(connection, tokenA);
     const tokenBAmount = await getTokenAmount(connection, tokenB);
@@ -279,10 +495,15 @@ describe("exchange", () => {
         poolTokenBAccount: tokenB,
         poolTokenFeeAccount: poolFeeAccount,
         userPoolTokenReceipt,
+        tokenAMint,
+        tokenBMint,
         userTokenAAccount,
         userTokenBAccount,
         user: payer.publicKey,
         creator: creator.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
       })
       .signers([payer])
       .rpc();
@@ -327,12 +548,18 @@ describe("exchange", () => {
 
     const poolTokenSupply = Number(poolMintData.supply);
     const tokenAAmount = await getTokenAmount(connection, tokenA);
+    // the pool tokens burned also pay the swap fees on half of the amount
+    const withdrawnWithFees =
+      tokenAWithdrawAmount + singleTokenFees(tokenAWithdrawAmount);
     const poolTokenPropotionalToWithdrawAmount =
       poolTokenSupply *
-      (1 - Math.sqrt((tokenAAmount - tokenAWithdrawAmount) / tokenAAmount));
+      (1 - Math.sqrt((tokenAAmount - withdrawnWithFees) / tokenAAmount));
 
     const txSig = await program.methods
-      .withdrawSingleTokenOut(new BN(tokenAWithdrawAmount))
+      .withdrawSingleTokenOut(
+        new BN(tokenAWithdrawAmount),
+        new BN(Math.ceil(poolTokenPropotionalToWithdrawAmount))
+      )
       .accountsPartial({
         pool,
         poolAuthority,
@@ -344,6 +571,8 @@ describe("exchange", () => {
         userSourceTokenAccount: userTokenAAccount,
         user: payer.publicKey,
         sourceMint: tokenAMint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        sourceTokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([payer])
       .rpc();
@@ -360,9 +589,10 @@ describe("exchange", () => {
       newUserTokenAAmount,
       Math.round(oldUserTokenAAmount + tokenAWithdrawAmount)
     );
+    // burned pool tokens round up in favour of the pool
     assert.equal(
       newUserPoolTokenAmount,
-      Math.round(oldUserPoolTokenAmount - poolTokenPropotionalToWithdrawAmount)
+      Math.floor(oldUserPoolTokenAmount - poolTokenPropotionalToWithdrawAmount)
     );
 
     console.log("Your transaction signature", txSig);
@@ -409,19 +639,42 @@ describe("exchange", () => {
       connection,
       userTokenBAccount
     );
+    const hostPoolTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        connection,
+        payer,
+        poolMint,
+        host.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).address;
+    const oldFeeAccountAmount = await getTokenAmount(
+      connection,
+      poolFeeAccount
+    );
     const txSig = await program.methods
-      .swap(new BN(tokenASwapAmount))
+      .swap(new BN(tokenASwapAmount), new BN(0))
       .accountsPartial({
         pool,
         poolAuthority,
         poolMint,
-        poolTokenAAccount: tokenA,
-        poolTokenBAccount: tokenB,
+        poolSourceTokenAccount: tokenA,
+        poolDestinationTokenAccount: tokenB,
         poolTokenFeeAccount: poolFeeAccount,
+        sourceMint: tokenAMint,
+        destinationMint: tokenBMint,
         userSourceTokenAccount: userTokenAAccount,
         userDestinationTokenAccount: userTokenBAccount,
         user: payer.publicKey,
         creator: creator.publicKey,
+        config,
+        hostFeeAccount: hostPoolTokenAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        sourceTokenProgram: TOKEN_PROGRAM_ID,
+        destinationTokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([payer])
       .rpc();
@@ -433,6 +686,31 @@ describe("exchange", () => {
     );
 
     assert.equal(newPoolTokenAAmount, oldPoolTokenAAmount + tokenASwapAmount);
+
+    // the host receives its share of the owner fee pool tokens
+    const hostPoolTokens = await getTokenAmount(
+      connection,
+      hostPoolTokenAccount
+    );
+    const ownerPoolTokens =
+      (await getTokenAmount(connection, poolFeeAccount)) - oldFeeAccountAmount;
+    assert.isAbove(hostPoolTokens, 0);
+    assert.equal(
+      hostPoolTokens,
+      Math.floor(
+        ((hostPoolTokens + ownerPoolTokens) * Number(hostFeeNumerator)) /
+          Number(hostFeeDenominator)
+      )
+    );
+
+    const poolState = await program.account.pool.fetch(pool);
+    assert.equal(
+      Number(poolState.protocolFeesTokenA),
+      Math.floor(
+        (tradingFee * Number(protocolFeeNumerator)) /
+          Number(protocolFeeDenominator)
+      )
+    );
     assert.equal(
       newUserTokenBAmount,
       Math.floor(oldUserTokenBAmount + expectedTokenBSwapAmount)
@@ -441,6 +719,95 @@ describe("exchange", () => {
     console.log("Your transaction signature", txSig);
   });
 
+  it("test swap zero tokens fails", async () => {
+    try {
+      await program.methods
+        .swap(new BN(0), new BN(0))
+        .accountsPartial({
+          pool,
+          poolAuthority,
+          poolMint,
+          poolSourceTokenAccount: tokenA,
+          poolDestinationTokenAccount: tokenB,
+          poolTokenFeeAccount: poolFeeAccount,
+          sourceMint: tokenAMint,
+          destinationMint: tokenBMint,
+          userSourceTokenAccount: getAssociatedTokenAddressSync(
+            tokenAMint,
+            payer.publicKey
+          ),
+          userDestinationTokenAccount: getAssociatedTokenAddressSync(
+            tokenBMint,
+            payer.publicKey
+          ),
+          user: payer.publicKey,
+          creator: creator.publicKey,
+          config,
+          hostFeeAccount: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          sourceTokenProgram: TOKEN_PROGRAM_ID,
+          destinationTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([payer])
+        .rpc();
+      assert.fail("swap of zero tokens succeeded");
+    } catch (err) {
+      assert.instanceOf(err, anchor.AnchorError);
+      assert.equal(
+        (err as anchor.AnchorError).error.errorCode.code,
+        "ZeroTradingTokens"
+      );
+    }
+  });
+
+  it("test collect protocol fees ok", async () => {
+    const treasuryTokenAAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        connection,
+        payer,
+        tokenAMint,
+        treasury.publicKey
+      )
+    ).address;
+    const treasuryTokenBAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        connection,
+        payer,
+        tokenBMint,
+        treasury.publicKey
+      )
+    ).address;
+
+    const oldPoolState = await program.account.pool.fetch(pool);
+
+    await program.methods
+      .collectProtocolFees()
+      .accountsPartial({
+        config,
+        pool,
+        poolAuthority,
+        poolTokenAAccount: tokenA,
+        poolTokenBAccount: tokenB,
+        tokenAMint,
+        tokenBMint,
+        treasuryTokenAAccount,
+        treasuryTokenBAccount,
+        treasury: treasury.publicKey,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([treasury])
+      .rpc();
+
+    const poolState = await program.account.pool.fetch(pool);
+    assert.equal(Number(poolState.protocolFeesTokenA), 0);
+    assert.equal(Number(poolState.protocolFeesTokenB), 0);
+    assert.equal(
+      await getTokenAmount(connection, treasuryTokenAAccount),
+      Number(oldPoolState.protocolFeesTokenA)
+    );
+  });
+
   it("test deposit single token ok", async () => {
     const tokenBDepositAmount = 40 * base;
     const userTokenBAccount = (
@@ -468,12 +835,18 @@ describe("exchange", () => {
     const poolMintData = MintLayout.decode(new Uint8Array(poolMintInfo.data));
 
     const poolTokenSupply = Number(poolMintData.supply);
+    // the swap fees on half of the amount stay in the pool
+    const depositedAfterFees =
+      tokenBDepositAmount - singleTokenFees(tokenBDepositAmount);
     const poolTokenPropotionalToDepositAmount =
       poolTokenSupply *
-      (Math.sqrt((tokenBAmount + tokenBDepositAmount) / tokenBAmount) - 1);
+      (Math.sqrt((tokenBAmount + depositedAfterFees) / tokenBAmount) - 1);
 
     const txSig = await program.methods
-      .depositSingleToken(new BN(tokenBDepositAmount))
+      .depositSingleToken(
+        new BN(tokenBDepositAmount),
+        new BN(Math.floor(poolTokenPropotionalToDepositAmount))
+      )
       .accountsPartial({
         pool,
         poolAuthority,
@@ -484,6 +857,8 @@ describe("exchange", () => {
         userSourceTokenAccount: userTokenBAccount,
         user: payer.publicKey,
         sourceMint: tokenBMint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        sourceTokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([payer])
       .rpc();
@@ -503,13 +878,691 @@ describe("exchange", () => {
     );
     assert.equal(
       newUserPoolTokenAmount - oldUserPoolTokenAmount,
-      Math.round(poolTokenPropotionalToDepositAmount)
+      Math.floor(poolTokenPropotionalToDepositAmount)
     );
 
     console.log("Your transaction signature", txSig);
   });
+
+  it("test swap across token programs ok", async () => {
+    // one side on the classic token program and the other on token 2022
+    // with a 1% transfer fee
+    const transferFeeBasisPoints = 100;
+    const classicMint = await createMint(
+      connection,
+      creator,
+      creator.publicKey,
+      null,
+      9
+    );
+    const token2022Mint = await createTransferFeeMint(
+      connection,
+      creator,
+      creator.publicKey,
+      6,
+      transferFeeBasisPoints
+    );
+    const classicIsA =
+      Buffer.compare(classicMint.toBuffer(), token2022Mint.toBuffer()) < 0;
+    const [mintA, mintB] = classicIsA
+      ? [classicMint, token2022Mint]
+      : [token2022Mint, classicMint];
+    const [programA, programB] = classicIsA
+      ? [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID]
+      : [TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID];
+
+    const mixedPool = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("pool"),
+        mintA.toBuffer(),
+        mintB.toBuffer(),
+        creator.publicKey.toBuffer(),
+      ],
+      program.programId
+    )[0];
+    const mixedPoolAuthority = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), mixedPool.toBuffer(), Buffer.from("authority")],
+      program.programId
+    )[0];
+    const mixedRegistry = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("registry"),
+        mintA.toBuffer(),
+        mintB.toBuffer(),
+        new BN(feeTier).toArrayLike(Buffer, "le", 2),
+      ],
+      program.programId
+    )[0];
+    const mixedPoolMint = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), mixedPool.toBuffer(), Buffer.from("mint")],
+      program.programId
+    )[0];
+
+    const vaultA = getVaultAddress(program.programId, mixedPool, mintA);
+    const vaultB = getVaultAddress(program.programId, mixedPool, mintB);
+    const creatorAccountA = (
+      await getOrCreateAssociatedTokenAccount(
+        connection,
+        creator,
+        mintA,
+        creator.publicKey,
+        false,
+        undefined,
+        undefined,
+        programA
+      )
+    ).address;
+    const creatorAccountB = (
+      await getOrCreateAssociatedTokenAccount(
+        connection,
+        creator,
+        mintB,
+        creator.publicKey,
+        false,
+        undefined,
+        undefined,
+        programB
+      )
+    ).address;
+    await mintTo(
+      connection,
+      payer,
+      mintA,
+      creatorAccountA,
+      creator,
+      1000 * base,
+      [],
+      undefined,
+      programA
+    );
+    await mintTo(
+      connection,
+      payer,
+      mintB,
+      creatorAccountB,
+      creator,
+      1000 * base,
+      [],
+      undefined,
+      programB
+    );
+
+    const mixedFeeAccount = getAssociatedTokenAddressSync(
+      mixedPoolMint,
+      mixedPoolAuthority,
+      true,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const mixedCreatorReceipt = getAssociatedTokenAddressSync(
+      mixedPoolMint,
+      creator.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    await program.methods
+      .initialize(feeTier, [], new BN(100 * base), new BN(100 * base))
+      .accountsPartial({
+        tokenA: vaultA,
+        tokenB: vaultB,
+        tokenAMint: mintA,
+        tokenBMint: mintB,
+        creatorTokenA: creatorAccountA,
+        creatorTokenB: creatorAccountB,
+        registry: mixedRegistry,
+        config,
+        poolMint: mixedPoolMint,
+        poolFeeAccount: mixedFeeAccount,
+        userPoolTokenReceipt: mixedCreatorReceipt,
+        creator: creator.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        tokenAProgram: programA,
+        tokenBProgram: programB,
+      })
+      .signers([creator])
+      .rpc();
+
+    const userAccountA = (
+      await getOrCreateAssociatedTokenAccount(
+        connection,
+        payer,
+        mintA,
+        payer.publicKey,
+        false,
+        undefined,
+        undefined,
+        programA
+      )
+    ).address;
+    const userAccountB = (
+      await getOrCreateAssociatedTokenAccount(
+        connection,
+        payer,
+        mintB,
+        payer.publicKey,
+        false,
+        undefined,
+        undefined,
+        programB
+      )
+    ).address;
+    // swap from the transfer fee side so the vault receives less than sent
+    const [userSource, userDestination, vaultSource, vaultDestination] =
+      classicIsA
+        ? [userAccountB, userAccountA, vaultB, vaultA]
+        : [userAccountA, userAccountB, vaultA, vaultB];
+    const swapAmount = 10 * base;
+    await mintTo(
+      connection,
+      payer,
+      token2022Mint,
+      userSource,
+      creator,
+      swapAmount,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const oldVaultSourceAmount = await getTokenAmount(connection, vaultSource);
+
+    await program.methods
+      .swap(new BN(swapAmount), new BN(0))
+      .accountsPartial({
+        pool: mixedPool,
+        poolAuthority: mixedPoolAuthority,
+        poolMint: mixedPoolMint,
+        poolSourceTokenAccount: vaultSource,
+        poolDestinationTokenAccount: vaultDestination,
+        poolTokenFeeAccount: mixedFeeAccount,
+        sourceMint: token2022Mint,
+        destinationMint: classicMint,
+        userSourceTokenAccount: userSource,
+        userDestinationTokenAccount: userDestination,
+        user: payer.publicKey,
+        creator: creator.publicKey,
+        config,
+        hostFeeAccount: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        sourceTokenProgram: TOKEN_2022_PROGRAM_ID,
+        destinationTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([payer])
+      .rpc();
+
+    const sentAmount =
+      swapAmount - (await getTokenAmount(connection, userSource));
+    const receivedAmount =
+      (await getTokenAmount(connection, vaultSource)) - oldVaultSourceAmount;
+    assert.equal(
+      receivedAmount,
+      sentAmount - Math.ceil((sentAmount * transferFeeBasisPoints) / 10_000)
+    );
+    assert.isAbove(await getTokenAmount(connection, userDestination), 0);
+  });
+
+  it("test initialize pool with disallowed transfer hook fails", async () => {
+    const hookProgram = anchor.web3.Keypair.generate().publicKey;
+    const classicMint = await createMint(
+      connection,
+      creator,
+      creator.publicKey,
+      null,
+      9
+    );
+    const hookMint = await createTransferHookMint(
+      connection,
+      creator,
+      creator.publicKey,
+      6,
+      hookProgram
+    );
+    const classicIsA =
+      Buffer.compare(classicMint.toBuffer(), hookMint.toBuffer()) < 0;
+    const [mintA, mintB] = classicIsA
+      ? [classicMint, hookMint]
+      : [hookMint, classicMint];
+    const [programA, programB] = classicIsA
+      ? [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID]
+      : [TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID];
+
+    const hookPool = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("pool"),
+        mintA.toBuffer(),
+        mintB.toBuffer(),
+        creator.publicKey.toBuffer(),
+      ],
+      program.programId
+    )[0];
+    const hookPoolAuthority = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), hookPool.toBuffer(), Buffer.from("authority")],
+      program.programId
+    )[0];
+    const hookRegistry = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("registry"),
+        mintA.toBuffer(),
+        mintB.toBuffer(),
+        new BN(feeTier).toArrayLike(Buffer, "le", 2),
+      ],
+      program.programId
+    )[0];
+    const hookPoolMint = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), hookPool.toBuffer(), Buffer.from("mint")],
+      program.programId
+    )[0];
+    const creatorAccountA = (
+      await getOrCreateAssociatedTokenAccount(
+        connection,
+        creator,
+        mintA,
+        creator.publicKey,
+        false,
+        undefined,
+        undefined,
+        programA
+      )
+    ).address;
+    const creatorAccountB = (
+      await getOrCreateAssociatedTokenAccount(
+        connection,
+        creator,
+        mintB,
+        creator.publicKey,
+        false,
+        undefined,
+        undefined,
+        programB
+      )
+    ).address;
+    const hookCreatorReceipt = getAssociatedTokenAddressSync(
+      hookPoolMint,
+      creator.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    try {
+      await program.methods
+        .initialize(feeTier, [], new BN(base), new BN(base))
+        .accountsPartial({
+          tokenA: getVaultAddress(program.programId, hookPool, mintA),
+          tokenB: getVaultAddress(program.programId, hookPool, mintB),
+          tokenAMint: mintA,
+          tokenBMint: mintB,
+          creatorTokenA: creatorAccountA,
+          creatorTokenB: creatorAccountB,
+          registry: hookRegistry,
+          config,
+          poolMint: hookPoolMint,
+          poolFeeAccount: getAssociatedTokenAddressSync(
+            hookPoolMint,
+            hookPoolAuthority,
+            true,
+            TOKEN_2022_PROGRAM_ID
+          ),
+          userPoolTokenReceipt: hookCreatorReceipt,
+          creator: creator.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          tokenAProgram: programA,
+          tokenBProgram: programB,
+        })
+        .signers([creator])
+        .rpc();
+      assert.fail("pool with a disallowed transfer hook was initialized");
+    } catch (err) {
+      assert.instanceOf(err, anchor.AnchorError);
+      assert.equal(
+        (err as anchor.AnchorError).error.errorCode.code,
+        "TransferHookNotAllowed"
+      );
+    }
+  });
+
+  it("test swap native sol ok", async () => {
+    const otherMint = await createMint(
+      connection,
+      creator,
+      creator.publicKey,
+      null,
+      9
+    );
+    const nativeIsA =
+      Buffer.compare(NATIVE_MINT.toBuffer(), otherMint.toBuffer()) < 0;
+    const [mintA, mintB] = nativeIsA
+      ? [NATIVE_MINT, otherMint]
+      : [otherMint, NATIVE_MINT];
+
+    const solPool = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("pool"),
+        mintA.toBuffer(),
+        mintB.toBuffer(),
+        creator.publicKey.toBuffer(),
+      ],
+      program.programId
+    )[0];
+    const solPoolAuthority = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), solPool.toBuffer(), Buffer.from("authority")],
+      program.programId
+    )[0];
+    const solRegistry = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("registry"),
+        mintA.toBuffer(),
+        mintB.toBuffer(),
+        new BN(feeTier).toArrayLike(Buffer, "le", 2),
+      ],
+      program.programId
+    )[0];
+    const solPoolMint = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), solPool.toBuffer(), Buffer.from("mint")],
+      program.programId
+    )[0];
+
+    const vaultA = getVaultAddress(program.programId, solPool, mintA);
+    const vaultB = getVaultAddress(program.programId, solPool, mintB);
+
+    // the creator's SOL side is wrapped by initialize
+    const creatorNative = getAssociatedTokenAddressSync(
+      NATIVE_MINT,
+      creator.publicKey
+    );
+    const creatorOther = (
+      await getOrCreateAssociatedTokenAccount(
+        connection,
+        creator,
+        otherMint,
+        creator.publicKey
+      )
+    ).address;
+    await mintTo(
+      connection,
+      payer,
+      otherMint,
+      creatorOther,
+      creator,
+      100 * base
+    );
+    const [creatorAccountA, creatorAccountB] = nativeIsA
+      ? [creatorNative, creatorOther]
+      : [creatorOther, creatorNative];
+    const solLiquidity = 100_000_000;
+    const [initialAmountA, initialAmountB] = nativeIsA
+      ? [solLiquidity, 100 * base]
+      : [100 * base, solLiquidity];
+
+    const solFeeAccount = getAssociatedTokenAddressSync(
+      solPoolMint,
+      solPoolAuthority,
+      true,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await program.methods
+      .initialize(feeTier, [], new BN(initialAmountA), new BN(initialAmountB))
+      .accountsPartial({
+        tokenA: vaultA,
+        tokenB: vaultB,
+        tokenAMint: mintA,
+        tokenBMint: mintB,
+        creatorTokenA: creatorAccountA,
+        creatorTokenB: creatorAccountB,
+        registry: solRegistry,
+        config,
+        poolMint: solPoolMint,
+        poolFeeAccount: solFeeAccount,
+        userPoolTokenReceipt: getAssociatedTokenAddressSync(
+          solPoolMint,
+          creator.publicKey,
+          false,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        creator: creator.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        tokenAProgram: TOKEN_PROGRAM_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
+      })
+      .preInstructions([
+        createAssociatedTokenAccountIdempotentInstruction(
+          creator.publicKey,
+          creatorNative,
+          creator.publicKey,
+          NATIVE_MINT
+        ),
+      ])
+      .signers([creator])
+      .rpc();
+    assert.isNull(await connection.getAccountInfo(creatorNative));
+
+    // an empty wrapped SOL account that only lives for the swap
+    const userNative = getAssociatedTokenAddressSync(
+      NATIVE_MINT,
+      payer.publicKey
+    );
+    const userOther = (
+      await getOrCreateAssociatedTokenAccount(
+        connection,
+        payer,
+        otherMint,
+        payer.publicKey
+      )
+    ).address;
+    const swapAccounts = {
+      pool: solPool,
+      poolAuthority: solPoolAuthority,
+      poolMint: solPoolMint,
+      poolTokenFeeAccount: solFeeAccount,
+      user: payer.publicKey,
+      creator: creator.publicKey,
+      config,
+      hostFeeAccount: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      sourceTokenProgram: TOKEN_PROGRAM_ID,
+      destinationTokenProgram: TOKEN_PROGRAM_ID,
+    };
+    const [nativeVault, otherVault] = nativeIsA
+      ? [vaultA, vaultB]
+      : [vaultB, vaultA];
+    const createUserNative = createAssociatedTokenAccountIdempotentInstruction(
+      payer.publicKey,
+      userNative,
+      payer.publicKey,
+      NATIVE_MINT
+    );
+
+    // SOL in, the swap wraps the lamports and closes the temporary account
+    const swapLamports = 10_000_000;
+    const lamportsBeforeSwapIn = await connection.getBalance(payer.publicKey);
+    await program.methods
+      .swap(new BN(swapLamports), new BN(0))
+      .accountsPartial({
+        ...swapAccounts,
+        poolSourceTokenAccount: nativeVault,
+        poolDestinationTokenAccount: otherVault,
+        sourceMint: NATIVE_MINT,
+        destinationMint: otherMint,
+        userSourceTokenAccount: userNative,
+        userDestinationTokenAccount: userOther,
+      })
+      .preInstructions([createUserNative])
+      .signers([payer])
+      .rpc();
+
+    assert.isNull(await connection.getAccountInfo(userNative));
+    assert.isAtLeast(
+      lamportsBeforeSwapIn - (await connection.getBalance(payer.publicKey)),
+      swapLamports
+    );
+    const otherReceived = await getTokenAmount(connection, userOther);
+    assert.isAbove(otherReceived, 0);
+
+    // SOL out, the output is unwrapped back to lamports
+    const lamportsBeforeSwapOut = await connection.getBalance(payer.publicKey);
+    await program.methods
+      .swap(new BN(otherReceived), new BN(0))
+      .accountsPartial({
+        ...swapAccounts,
+        poolSourceTokenAccount: otherVault,
+        poolDestinationTokenAccount: nativeVault,
+        sourceMint: otherMint,
+        destinationMint: NATIVE_MINT,
+        userSourceTokenAccount: userOther,
+        userDestinationTokenAccount: userNative,
+      })
+      .preInstructions([createUserNative])
+      .signers([payer])
+      .rpc();
+
+    assert.isNull(await connection.getAccountInfo(userNative));
+    assert.isAbove(
+      await connection.getBalance(payer.publicKey),
+      lamportsBeforeSwapOut
+    );
+    assert.equal(await getTokenAmount(connection, userOther), 0);
+
+    // a wrapped SOL account already holding tokens belongs to the user and stays open
+    await program.methods
+      .swap(new BN(swapLamports), new BN(0))
+      .accountsPartial({
+        ...swapAccounts,
+        poolSourceTokenAccount: nativeVault,
+        poolDestinationTokenAccount: otherVault,
+        sourceMint: NATIVE_MINT,
+        destinationMint: otherMint,
+        userSourceTokenAccount: userNative,
+        userDestinationTokenAccount: userOther,
+      })
+      .preInstructions([createUserNative])
+      .signers([payer])
+      .rpc();
+    const heldLamports = 1_000_000;
+    await program.methods
+      .swap(
+        new BN(await getTokenAmount(connection, userOther)),
+        new BN(0)
+      )
+      .accountsPartial({
+        ...swapAccounts,
+        poolSourceTokenAccount: otherVault,
+        poolDestinationTokenAccount: nativeVault,
+        sourceMint: otherMint,
+        destinationMint: NATIVE_MINT,
+        userSourceTokenAccount: userOther,
+        userDestinationTokenAccount: userNative,
+      })
+      .preInstructions([
+        createUserNative,
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: payer.publicKey,
+          toPubkey: userNative,
+          lamports: heldLamports,
+        }),
+        createSyncNativeInstruction(userNative),
+      ])
+      .signers([payer])
+      .rpc();
+
+    assert.isAbove(
+      await getTokenAmount(connection, userNative),
+      heldLamports
+    );
+  });
 });
 
+async function createTransferFeeMint(
+  connection: anchor.web3.Connection,
+  payer: anchor.web3.Keypair,
+  authority: anchor.web3.PublicKey,
+  decimals: number,
+  transferFeeBasisPoints: number
+) {
+  const mint = anchor.web3.Keypair.generate();
+  const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+  const lamports = await connection.getMinimumBalanceForRentExemption(mintLen);
+
+  const tx = new anchor.web3.Transaction().add(
+    anchor.web3.SystemProgram.createAccount({
+      fromPubkey: payer.publicKey,
+      newAccountPubkey: mint.publicKey,
+      space: mintLen,
+      lamports,
+      programId: TOKEN_2022_PROGRAM_ID,
+    }),
+    createInitializeTransferFeeConfigInstruction(
+      mint.publicKey,
+      authority,
+      authority,
+      transferFeeBasisPoints,
+      BigInt(Number.MAX_SAFE_INTEGER),
+      TOKEN_2022_PROGRAM_ID
+    ),
+    createInitializeMintInstruction(
+      mint.publicKey,
+      decimals,
+      authority,
+      null,
+      TOKEN_2022_PROGRAM_ID
+    )
+  );
+  await anchor.web3.sendAndConfirmTransaction(connection, tx, [payer, mint]);
+
+  return mint.publicKey;
+}
+
+async function createTransferHookMint(
+  connection: anchor.web3.Connection,
+  payer: anchor.web3.Keypair,
+  authority: anchor.web3.PublicKey,
+  decimals: number,
+  hookProgram: anchor.web3.PublicKey
+) {
+  const mint = anchor.web3.Keypair.generate();
+  const mintLen = getMintLen([ExtensionType.TransferHook]);
+  const lamports = await connection.getMinimumBalanceForRentExemption(mintLen);
+
+  const tx = new anchor.web3.Transaction().add(
+    anchor.web3.SystemProgram.createAccount({
+      fromPubkey: payer.publicKey,
+      newAccountPubkey: mint.publicKey,
+      space: mintLen,
+      lamports,
+      programId: TOKEN_2022_PROGRAM_ID,
+    }),
+    createInitializeTransferHookInstruction(
+      mint.publicKey,
+      authority,
+      hookProgram,
+      TOKEN_2022_PROGRAM_ID
+    ),
+    createInitializeMintInstruction(
+      mint.publicKey,
+      decimals,
+      authority,
+      null,
+      TOKEN_2022_PROGRAM_ID
+    )
+  );
+  await anchor.web3.sendAndConfirmTransaction(connection, tx, [payer, mint]);
+
+  return mint.publicKey;
+}
+
+function getVaultAddress(
+  programId: anchor.web3.PublicKey,
+  pool: anchor.web3.PublicKey,
+  mint: anchor.web3.PublicKey
+) {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("pool"),
+      pool.toBuffer(),
+      Buffer.from("vault"),
+      mint.toBuffer(),
+    ],
+    programId
+  )[0];
+}
+
 async function getTokenAmount(
   connection: anchor.web3.Connection,
   token: anchor.web3.PublicKey